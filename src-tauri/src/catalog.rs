@@ -2,34 +2,128 @@
 //!
 //! Uses rusqlite with FTS5 for full-text search capabilities.
 
-use crate::models::{Category, MusicTrack, Sound, UnitType};
-use rusqlite::{params, Connection};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use crate::error::KitharaError;
+use crate::models::{
+    CatalogStats, Category, ExtractionOptions, FavoriteToggle, HighlightMarkers, LastSession, MusicTrack,
+    Playlist, SearchFilters, SearchResult, Sound, SortOrder, Subcategory, UnitType,
+};
+use rusqlite::backup::Progress;
+use rusqlite::{params, Connection, DatabaseName, OpenFlags, OptionalExtension, ToSql, TransactionBehavior};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
 
-/// Database connection wrapper for Tauri managed state.
-/// Wraps Connection in Mutex since rusqlite Connection is not Sync.
+/// Column list matching the field order expected by `row_to_sound`
+const SOUND_COLUMNS: &str = "s.id, s.event_name, s.display_name, s.category, s.unit_type, s.subcategory,
+     s.duration_ms, s.file_path, s.tags, s.is_favorite, s.gender, s.gain_db, s.language, s.source_bank, s.is_loop,
+     s.short_name";
+
+/// Most sounds returned by a single search
+const SEARCH_LIMIT: usize = 500;
+
+/// Longest user tag accepted, in characters
+const MAX_TAG_LEN: usize = 50;
+
+/// Metadata key for the JSON list of unit names discovered during extraction
+const KNOWN_UNITS_KEY: &str = "known_units";
+
+/// Metadata key for the last playback volume set by the user
+const VOLUME_KEY: &str = "last_volume";
+
+/// Metadata key for the name of the selected audio output device
+const OUTPUT_DEVICE_KEY: &str = "output_device";
+
+/// Metadata key for the JSON sound and position that was playing when playback last stopped
+const LAST_SESSION_KEY: &str = "last_session";
+
+/// Metadata key for a user-chosen directory for extracted sounds
+const OUTPUT_DIR_KEY: &str = "output_dir";
+
+/// Metadata key for the game audio folder used last, so it needn't be located again
+const LAST_GAME_PATH_KEY: &str = "last_game_path";
+
+/// Metadata key for the JSON options of an extraction that hasn't finished
+const PENDING_EXTRACTION_KEY: &str = "pending_extraction";
+
+/// Metadata key for the last conversion concurrency limit chosen by the user
+const MAX_CONCURRENCY_KEY: &str = "max_concurrency";
+
+/// Metadata key for the JSON map of per-category sound counts from the last extraction
+const CATEGORY_COUNTS_KEY: &str = "category_counts";
+
+/// Tables a file must have to be restored as a catalog.
+/// Anything newer is created by `init_schema` after the restore.
+const REQUIRED_TABLES: &[&str] = &["sounds", "metadata"];
+
+/// Most connections a file-backed catalog keeps open. In WAL mode readers run
+/// alongside the single writer, so a long search doesn't hold up inserts.
+const MAX_CONNECTIONS: usize = 4;
+
+/// How long a connection waits on a locked database before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Database connection pool for Tauri managed state.
+/// rusqlite connections aren't Sync, so each caller checks one out for the
+/// duration of a method and it goes back to the pool when dropped.
 pub struct Catalog {
-    conn: Mutex<Connection>,
+    pool: ConnectionPool,
 }
 
 impl Catalog {
     /// Opens or creates the catalog database at the given path.
     /// Creates tables and indexes on first run.
-    pub fn open(db_path: PathBuf) -> Result<Self, String> {
-        let conn = Connection::open(&db_path)
-            .map_err(|e| format!("Failed to open database: {}", e))?;
+    pub fn open(db_path: PathBuf) -> Result<Self, KitharaError> {
+        let conn = open_pooled_connection(&db_path)?;
+        Self::from_pool(ConnectionPool::new(Some(db_path), conn, MAX_CONNECTIONS))
+    }
 
-        let catalog = Self {
-            conn: Mutex::new(conn),
-        };
+    /// Creates a catalog backed by an in-memory database with the full schema.
+    /// Every in-memory connection is its own database, so the pool holds just one.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, KitharaError> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| KitharaError::Database(format!("Failed to open in-memory database: {}", e)))?;
+        Self::from_connection(conn)
+    }
+
+    /// Wraps a single open connection and ensures the schema is up to date.
+    #[cfg(test)]
+    fn from_connection(conn: Connection) -> Result<Self, KitharaError> {
+        // Needed for ON DELETE CASCADE on playlist items
+        conn.execute_batch("PRAGMA foreign_keys = ON;")
+            .map_err(|e| KitharaError::Database(format!("Failed to enable foreign keys: {}", e)))?;
+        register_collations(&conn)?;
+        Self::from_pool(ConnectionPool::new(None, conn, 1))
+    }
+
+    /// Wraps a connection pool and ensures the schema is up to date.
+    fn from_pool(pool: ConnectionPool) -> Result<Self, KitharaError> {
+        let catalog = Self { pool };
         catalog.init_schema()?;
         Ok(catalog)
     }
 
+    /// Checks out a connection, waiting for one to be returned if all are in use.
+    fn conn(&self) -> Result<PooledConnection<'_>, KitharaError> {
+        self.pool.get()
+    }
+
     /// Creates tables, indexes, and FTS virtual table if they don't exist.
-    fn init_schema(&self) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    fn init_schema(&self) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+
+        // Checked before the schema batch so an existing music library can be indexed
+        let has_music_fts: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'music_tracks_fts'",
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
 
         conn.execute_batch(
             r#"
@@ -87,9 +181,61 @@ impl Catalog {
             );
 
             CREATE INDEX IF NOT EXISTS idx_music_tracks_title ON music_tracks(title);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS music_tracks_fts USING fts5(
+                title,
+                content='music_tracks',
+                content_rowid='rowid'
+            );
+
+            -- Triggers to keep FTS in sync with music_tracks table
+            CREATE TRIGGER IF NOT EXISTS music_tracks_ai AFTER INSERT ON music_tracks BEGIN
+                INSERT INTO music_tracks_fts(rowid, title) VALUES (new.rowid, new.title);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS music_tracks_ad AFTER DELETE ON music_tracks BEGIN
+                INSERT INTO music_tracks_fts(music_tracks_fts, rowid, title)
+                VALUES ('delete', old.rowid, old.title);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS music_tracks_au AFTER UPDATE ON music_tracks BEGIN
+                INSERT INTO music_tracks_fts(music_tracks_fts, rowid, title)
+                VALUES ('delete', old.rowid, old.title);
+                INSERT INTO music_tracks_fts(rowid, title) VALUES (new.rowid, new.title);
+            END;
+
+            -- Playlists reference sounds by ID without a foreign key, so
+            -- rebuilding the sound cache doesn't wipe them
+            CREATE TABLE IF NOT EXISTS playlists (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS playlist_items (
+                playlist_id INTEGER NOT NULL REFERENCES playlists(id) ON DELETE CASCADE,
+                sound_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                PRIMARY KEY (playlist_id, sound_id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_playlist_items_position
+                ON playlist_items(playlist_id, position);
+
+            -- Default gain per category, combined with each sound's own trim
+            CREATE TABLE IF NOT EXISTS category_settings (
+                category TEXT PRIMARY KEY,
+                gain_db REAL NOT NULL DEFAULT 0
+            );
         "#,
         )
-        .map_err(|e| format!("Failed to create schema: {}", e))?;
+        .map_err(|e| KitharaError::Database(format!("Failed to create schema: {}", e)))?;
+
+        // Index tracks that were inserted before the FTS table existed
+        if !has_music_fts {
+            conn.execute("INSERT INTO music_tracks_fts(music_tracks_fts) VALUES ('rebuild')", [])
+                .map_err(|e| KitharaError::Database(format!("Failed to build music search index: {}", e)))?;
+        }
 
         // Migration: Add is_favorite column if it doesn't exist
         // SQLite doesn't have ALTER TABLE ADD COLUMN IF NOT EXISTS,
@@ -110,21 +256,133 @@ impl Catalog {
                 CREATE INDEX IF NOT EXISTS idx_sounds_favorite ON sounds(is_favorite);
                 "#,
             )
-            .map_err(|e| format!("Failed to add is_favorite column: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to add is_favorite column: {}", e)))?;
+        }
+
+        // Migration: Add gender column if it doesn't exist
+        let has_gender_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('sounds') WHERE name = 'gender'",
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_gender_column {
+            conn.execute_batch(
+                r#"
+                ALTER TABLE sounds ADD COLUMN gender TEXT;
+                CREATE INDEX IF NOT EXISTS idx_sounds_gender ON sounds(gender);
+                "#,
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to add gender column: {}", e)))?;
+        }
+
+        // Migration: Add language column if it doesn't exist
+        let has_language_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('sounds') WHERE name = 'language'",
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_language_column {
+            conn.execute_batch(
+                r#"
+                ALTER TABLE sounds ADD COLUMN language TEXT;
+                CREATE INDEX IF NOT EXISTS idx_sounds_language ON sounds(language);
+                "#,
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to add language column: {}", e)))?;
+        }
+
+        // Migration: Add source_bank column if it doesn't exist
+        let has_source_bank_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('sounds') WHERE name = 'source_bank'",
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_source_bank_column {
+            conn.execute_batch(
+                r#"
+                ALTER TABLE sounds ADD COLUMN source_bank TEXT;
+                CREATE INDEX IF NOT EXISTS idx_sounds_source_bank ON sounds(source_bank);
+                "#,
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to add source_bank column: {}", e)))?;
+        }
+
+        // Migration: Add gain_db column if it doesn't exist
+        let has_gain_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('sounds') WHERE name = 'gain_db'",
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_gain_column {
+            conn.execute("ALTER TABLE sounds ADD COLUMN gain_db REAL DEFAULT 0 NOT NULL", [])
+                .map_err(|e| KitharaError::Database(format!("Failed to add gain_db column: {}", e)))?;
+        }
+
+        // Migration: Add is_loop column if it doesn't exist
+        let has_loop_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('sounds') WHERE name = 'is_loop'",
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_loop_column {
+            conn.execute("ALTER TABLE sounds ADD COLUMN is_loop INTEGER DEFAULT 0 NOT NULL", [])
+                .map_err(|e| KitharaError::Database(format!("Failed to add is_loop column: {}", e)))?;
+        }
+
+        // Migration: Add short_name column if it doesn't exist. Extraction has
+        // always stored the short_name as the event name, so existing rows can be
+        // re-classified straight away.
+        let has_short_name_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('sounds') WHERE name = 'short_name'",
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .unwrap_or(false);
+
+        if !has_short_name_column {
+            conn.execute_batch(
+                r#"
+                ALTER TABLE sounds ADD COLUMN short_name TEXT;
+                UPDATE sounds SET short_name = event_name;
+                "#,
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to add short_name column: {}", e)))?;
         }
 
         Ok(())
     }
 
     /// Runs one-time data migrations. Should be called on app startup.
-    pub fn run_migrations(&self) -> Result<(), String> {
+    pub fn run_migrations(&self) -> Result<(), KitharaError> {
         self.migrate_remove_excluded_sounds()?;
         Ok(())
     }
 
     /// Migration: Remove sounds matching exclusion patterns (unreleased content).
     /// Runs once, tracked via metadata table.
-    fn migrate_remove_excluded_sounds(&self) -> Result<(), String> {
+    fn migrate_remove_excluded_sounds(&self) -> Result<(), KitharaError> {
         const MIGRATION_KEY: &str = "migration_removed_excluded_sounds_v1";
         const EXCLUSION_PATTERNS: &[&str] = &[];
 
@@ -148,8 +406,8 @@ impl Catalog {
     }
 
     /// Gets a value from the metadata table.
-    fn get_metadata(&self, key: &str) -> Result<Option<String>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    fn get_metadata(&self, key: &str) -> Result<Option<String>, KitharaError> {
+        let conn = self.conn()?;
         let result = conn.query_row(
             "SELECT value FROM metadata WHERE key = ?1",
             params![key],
@@ -158,108 +416,196 @@ impl Catalog {
         match result {
             Ok(value) => Ok(Some(value)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(format!("Failed to get metadata: {}", e)),
+            Err(e) => Err(KitharaError::Database(format!("Failed to get metadata: {}", e))),
         }
     }
 
     /// Sets a value in the metadata table.
-    fn set_metadata(&self, key: &str, value: &str) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    fn set_metadata(&self, key: &str, value: &str) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
         conn.execute(
             "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
             params![key, value],
         )
-        .map_err(|e| format!("Failed to set metadata: {}", e))?;
+        .map_err(|e| KitharaError::Database(format!("Failed to set metadata: {}", e)))?;
         Ok(())
     }
 
-    /// Searches sounds using FTS5 with optional category/unit_type filters.
-    /// Empty query returns all sounds (filtered by category/unit_type if provided).
+    /// Returns the last playback volume saved with `save_volume`, if any.
+    pub fn get_saved_volume(&self) -> Result<Option<f32>, KitharaError> {
+        Ok(self
+            .get_metadata(VOLUME_KEY)?
+            .and_then(|value| value.parse::<f32>().ok())
+            .map(|volume| volume.clamp(0.0, 1.0)))
+    }
+
+    /// Persists the playback volume so it can be restored on next launch.
+    pub fn save_volume(&self, volume: f32) -> Result<(), KitharaError> {
+        self.set_metadata(VOLUME_KEY, &volume.clamp(0.0, 1.0).to_string())
+    }
+
+    /// Returns the saved output device name, if one was selected.
+    pub fn get_saved_output_device(&self) -> Result<Option<String>, KitharaError> {
+        self.get_metadata(OUTPUT_DEVICE_KEY)
+    }
+
+    /// Persists the selected output device, or clears it for the system default.
+    pub fn save_output_device(&self, name: Option<&str>) -> Result<(), KitharaError> {
+        match name {
+            Some(name) => self.set_metadata(OUTPUT_DEVICE_KEY, name),
+            None => {
+                let conn = self.conn()?;
+                conn.execute("DELETE FROM metadata WHERE key = ?1", params![OUTPUT_DEVICE_KEY])
+                    .map_err(|e| KitharaError::Database(format!("Failed to clear output device: {}", e)))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the sound and position saved by `save_last_session`, if any.
+    pub fn get_last_session(&self) -> Result<Option<LastSession>, KitharaError> {
+        Ok(self
+            .get_metadata(LAST_SESSION_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Persists what was playing so it can be offered for resuming on next launch.
+    pub fn save_last_session(&self, session: &LastSession) -> Result<(), KitharaError> {
+        let session_json = serde_json::to_string(session)
+            .map_err(|e| KitharaError::Database(format!("Failed to serialize last session: {}", e)))?;
+        self.set_metadata(LAST_SESSION_KEY, &session_json)
+    }
+
+    /// Searches sounds using FTS5 with optional category/unit_type/gender/language filters.
+    /// The query supports quoted phrases and AND/OR/NOT (see `build_fts_query`).
+    /// Empty query returns all sounds (filtered by category/unit_type/gender if provided).
+    /// With `fuzzy`, a query with no prefix matches falls back to edit-distance
+    /// matching on names so typos still find something, ordered by closeness.
+    /// With `markers`, each FTS match also carries the display or event name it matched,
+    /// with the matched terms wrapped in the markers; tag-only and fuzzy matches have none.
     pub fn search_sounds(
         &self,
         query: &str,
-        category: Option<&str>,
-        unit_type: Option<&str>,
-    ) -> Result<Vec<Sound>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-
-        let trimmed_query = query.trim();
-        let use_fts = !trimmed_query.is_empty();
-
-        // Build the SQL query dynamically
-        let sql = if use_fts {
-            let mut sql = String::from(
-                "SELECT s.id, s.event_name, s.display_name, s.category,
-                        s.unit_type, s.subcategory, s.duration_ms, s.file_path, s.tags, s.is_favorite
-                 FROM sounds s
-                 JOIN sounds_fts fts ON s.rowid = fts.rowid
-                 WHERE sounds_fts MATCH ?1",
-            );
+        filters: &SearchFilters,
+        sort: SortOrder,
+        fuzzy: bool,
+        markers: Option<&HighlightMarkers>,
+    ) -> Result<Vec<SearchResult>, KitharaError> {
+        let Some(fts_query) = build_fts_query(query) else {
+            return self.query_sounds(None, filters, sort, Some(SEARCH_LIMIT), None);
+        };
 
-            if category.is_some() {
-                sql.push_str(" AND s.category = ?2");
-            }
-            if unit_type.is_some() {
-                if category.is_some() {
-                    sql.push_str(" AND s.unit_type = ?3");
-                } else {
-                    sql.push_str(" AND s.unit_type = ?2");
-                }
-            }
-            sql.push_str(" ORDER BY rank LIMIT 500");
-            sql
-        } else {
-            let mut sql = String::from(
-                "SELECT s.id, s.event_name, s.display_name, s.category,
-                        s.unit_type, s.subcategory, s.duration_ms, s.file_path, s.tags, s.is_favorite
-                 FROM sounds s
-                 WHERE 1=1",
-            );
+        let results = self.query_sounds(Some(&fts_query), filters, sort, Some(SEARCH_LIMIT), markers)?;
+        if !results.is_empty() || !fuzzy {
+            return Ok(results);
+        }
 
-            if category.is_some() {
-                sql.push_str(" AND s.category = ?1");
-            }
-            if unit_type.is_some() {
-                if category.is_some() {
-                    sql.push_str(" AND s.unit_type = ?2");
-                } else {
-                    sql.push_str(" AND s.unit_type = ?1");
-                }
+        let terms: Vec<String> = search_words(query).collect();
+        let mut scored: Vec<(usize, SearchResult)> = self
+            .query_sounds(None, filters, SortOrder::NameAsc, None, None)?
+            .into_iter()
+            .filter_map(|result| fuzzy_score(&terms, &result.sound).map(|score| (score, result)))
+            .collect();
+        // Stable sort keeps name order among equally close matches
+        scored.sort_by_key(|(score, _)| *score);
+
+        Ok(scored
+            .into_iter()
+            .take(SEARCH_LIMIT)
+            .map(|(_, result)| result)
+            .collect())
+    }
+
+    /// Runs a filtered sound query, optionally restricted to an FTS match.
+    /// `markers` only applies alongside `fts_query`.
+    fn query_sounds(
+        &self,
+        fts_query: Option<&str>,
+        filters: &SearchFilters,
+        sort: SortOrder,
+        limit: Option<usize>,
+        markers: Option<&HighlightMarkers>,
+    ) -> Result<Vec<SearchResult>, KitharaError> {
+        let conn = self.conn()?;
+
+        // Build the SQL query and its positional params together
+        let mut columns = SOUND_COLUMNS.to_string();
+        let mut conditions: Vec<String> = Vec::new();
+        let mut query_params: Vec<&dyn ToSql> = Vec::new();
+
+        let markers = markers.filter(|_| fts_query.is_some());
+        if let Some(markers) = markers {
+            // highlight() returns the column unchanged when it didn't match, so NULLIF
+            // falls through to the event name; tags hold JSON and aren't worth showing
+            query_params.push(&markers.open);
+            query_params.push(&markers.close);
+            let (open, close) = (query_params.len() - 1, query_params.len());
+            columns.push_str(&format!(
+                ", COALESCE(NULLIF(highlight(sounds_fts, 1, ?{open}, ?{close}), s.display_name), \
+                 NULLIF(highlight(sounds_fts, 0, ?{open}, ?{close}), s.event_name))"
+            ));
+        }
+        let mut sql = format!("SELECT {} FROM sounds s", columns);
+        if let Some(ref fts_query) = fts_query {
+            sql.push_str(" JOIN sounds_fts fts ON s.rowid = fts.rowid");
+            query_params.push(fts_query);
+            conditions.push(format!("sounds_fts MATCH ?{}", query_params.len()));
+        }
+        if let Some(cats) = filters.categories.as_deref().filter(|c| !c.is_empty()) {
+            conditions.push(in_condition("s.category", cats, &mut query_params));
+        }
+        if let Some(units) = filters.unit_types.as_deref().filter(|u| !u.is_empty()) {
+            conditions.push(in_condition("s.unit_type", units, &mut query_params));
+        }
+        if let Some(ref g) = filters.gender {
+            if g == "male" {
+                // Voice lines extracted before male voices were marked have no gender
+                conditions.push("(s.gender = 'male' OR (s.gender IS NULL AND s.category = 'vocal'))".to_string());
+            } else {
+                query_params.push(g);
+                conditions.push(format!("s.gender = ?{}", query_params.len()));
             }
-            sql.push_str(" ORDER BY s.display_name ASC LIMIT 500");
-            sql
-        };
+        }
+        if let Some(ref language) = filters.language {
+            query_params.push(language);
+            conditions.push(format!("s.language = ?{}", query_params.len()));
+        }
+        if filters.favorites_only {
+            conditions.push("s.is_favorite = 1".to_string());
+        }
+        if let Some(is_loop) = filters.is_loop {
+            conditions.push(format!("s.is_loop = {}", is_loop as i32));
+        }
+
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(&format!(" ORDER BY {}", order_by_clause(sort, fts_query.is_some())));
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
 
         let mut stmt = conn
             .prepare(&sql)
-            .map_err(|e| format!("Failed to prepare query: {}", e))?;
-
-        // Build params based on what we have
-        let rows = if use_fts {
-            let fts_query = format!("{}*", trimmed_query); // Prefix search
-            match (category, unit_type) {
-                (Some(cat), Some(unit)) => stmt.query_map(params![fts_query, cat, unit], row_to_sound),
-                (Some(cat), None) => stmt.query_map(params![fts_query, cat], row_to_sound),
-                (None, Some(unit)) => stmt.query_map(params![fts_query, unit], row_to_sound),
-                (None, None) => stmt.query_map(params![fts_query], row_to_sound),
-            }
-        } else {
-            match (category, unit_type) {
-                (Some(cat), Some(unit)) => stmt.query_map(params![cat, unit], row_to_sound),
-                (Some(cat), None) => stmt.query_map(params![cat], row_to_sound),
-                (None, Some(unit)) => stmt.query_map(params![unit], row_to_sound),
-                (None, None) => stmt.query_map([], row_to_sound),
-            }
-        }
-        .map_err(|e| format!("Query failed: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt
+            .query_map(query_params.as_slice(), |row| {
+                Ok(SearchResult {
+                    sound: row_to_sound(row)?,
+                    match_snippet: if markers.is_some() { row.get(16)? } else { None },
+                })
+            })
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
 
         rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to collect results: {}", e))
+            .map_err(|e| KitharaError::Database(format!("Failed to collect results: {}", e)))
     }
 
     /// Returns all categories with their sound counts.
-    pub fn get_categories(&self) -> Result<Vec<Category>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    pub fn get_categories(&self) -> Result<Vec<Category>, KitharaError> {
+        let conn = self.conn()?;
 
         let mut stmt = conn
             .prepare(
@@ -268,7 +614,7 @@ impl Catalog {
                  GROUP BY category
                  ORDER BY count DESC",
             )
-            .map_err(|e| format!("Failed to prepare: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
 
         let rows = stmt
             .query_map([], |row| {
@@ -280,15 +626,70 @@ impl Catalog {
                     count,
                 })
             })
-            .map_err(|e| format!("Query failed: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
+    }
+
+    /// Returns the subcategories within a category with their sound counts, largest first.
+    /// Sounds without a subcategory are left out.
+    pub fn get_subcategories(&self, category: &str) -> Result<Vec<Subcategory>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT subcategory, COUNT(*) as count
+                 FROM sounds
+                 WHERE category = ?1 AND subcategory != ''
+                 GROUP BY subcategory
+                 ORDER BY count DESC, subcategory COLLATE natural_order",
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+
+        let rows = stmt
+            .query_map([category], |row| {
+                let id: String = row.get(0)?;
+                let count: u32 = row.get(1)?;
+                Ok(Subcategory {
+                    name: format_category_name(&id),
+                    id,
+                    count,
+                })
+            })
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
+    }
+
+    /// Returns the distinct languages of localized sounds, alphabetically.
+    pub fn get_languages(&self) -> Result<Vec<String>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT language FROM sounds WHERE language IS NOT NULL ORDER BY language")
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
 
         rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to collect: {}", e))
+            .map_err(|e| KitharaError::Database(format!("Failed to collect languages: {}", e)))
     }
 
     /// Returns all unit types with their sound counts.
-    pub fn get_unit_types(&self) -> Result<Vec<UnitType>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    /// With `include_empty`, every known unit from the last extraction is included,
+    /// even those with no extracted sounds (count 0).
+    pub fn get_unit_types(&self, include_empty: bool) -> Result<Vec<UnitType>, KitharaError> {
+        let known_units = if include_empty {
+            self.get_known_units()?
+        } else {
+            Vec::new()
+        };
+
+        let conn = self.conn()?;
 
         let mut stmt = conn
             .prepare(
@@ -298,7 +699,7 @@ impl Catalog {
                  GROUP BY unit_type
                  ORDER BY unit_type ASC",
             )
-            .map_err(|e| format!("Failed to prepare: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
 
         let rows = stmt
             .query_map([], |row| {
@@ -310,125 +711,904 @@ impl Catalog {
                     count,
                 })
             })
-            .map_err(|e| format!("Query failed: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        let mut unit_types = rows
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))?;
+
+        // Merge in known units that have no sounds
+        let mut added_empty = false;
+        for unit in known_units {
+            if !unit_types.iter().any(|u| u.id == unit) {
+                unit_types.push(UnitType {
+                    name: unit.clone(),
+                    id: unit,
+                    count: 0,
+                });
+                added_empty = true;
+            }
+        }
+        if added_empty {
+            unit_types.sort_by(|a, b| a.id.cmp(&b.id));
+        }
 
-        rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to collect: {}", e))
+        Ok(unit_types)
     }
 
-    /// Inserts a sound into the catalog. FTS is updated via trigger.
-    pub fn insert_sound(&self, sound: &Sound) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    /// Stores the unit names discovered during extraction.
+    pub fn set_known_units(&self, units: &[String]) -> Result<(), KitharaError> {
+        let units_json = serde_json::to_string(units)
+            .map_err(|e| KitharaError::Database(format!("Failed to serialize known units: {}", e)))?;
+        self.set_metadata(KNOWN_UNITS_KEY, &units_json)
+    }
 
-        let tags_json = serde_json::to_string(&sound.tags)
-            .map_err(|e| format!("Failed to serialize tags: {}", e))?;
-        let duration_ms = (sound.duration * 1000.0) as i64;
-        let is_favorite_int = if sound.is_favorite { 1 } else { 0 };
+    /// Returns the unit names discovered during the last extraction.
+    pub fn get_known_units(&self) -> Result<Vec<String>, KitharaError> {
+        Ok(self
+            .get_metadata(KNOWN_UNITS_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
 
-        conn.execute(
-            "INSERT OR REPLACE INTO sounds
-             (id, event_name, display_name, category, unit_type, subcategory,
-              duration_ms, file_path, tags, is_favorite)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                sound.id,
-                sound.event_name,
-                sound.display_name,
-                sound.category,
-                sound.unit_type,
-                sound.subcategory,
-                duration_ms,
-                sound.file_path,
-                tags_json,
-                is_favorite_int,
-            ],
-        )
-        .map_err(|e| format!("Failed to insert sound: {}", e))?;
+    /// Stores per-category sound counts so the next extraction can compare against them.
+    pub fn save_category_counts(&self, counts: &HashMap<String, u32>) -> Result<(), KitharaError> {
+        let counts_json = serde_json::to_string(counts)
+            .map_err(|e| KitharaError::Database(format!("Failed to serialize category counts: {}", e)))?;
+        self.set_metadata(CATEGORY_COUNTS_KEY, &counts_json)
+    }
 
-        Ok(())
+    /// Returns per-category sound counts saved by the previous extraction.
+    pub fn get_saved_category_counts(&self) -> Result<HashMap<String, u32>, KitharaError> {
+        Ok(self
+            .get_metadata(CATEGORY_COUNTS_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    /// Returns the user-chosen output directory for extracted sounds, if any.
+    pub fn get_saved_output_dir(&self) -> Result<Option<String>, KitharaError> {
+        self.get_metadata(OUTPUT_DIR_KEY)
+    }
+
+    /// Persists the output directory so later extractions default to it,
+    /// or clears it to go back to the app data directory.
+    pub fn save_output_dir(&self, dir: Option<&str>) -> Result<(), KitharaError> {
+        match dir {
+            Some(dir) => self.set_metadata(OUTPUT_DIR_KEY, dir),
+            None => {
+                let conn = self.conn()?;
+                conn.execute("DELETE FROM metadata WHERE key = ?1", params![OUTPUT_DIR_KEY])
+                    .map_err(|e| KitharaError::Database(format!("Failed to clear output directory: {}", e)))?;
+                Ok(())
+            }
+        }
     }
 
-    /// Toggles the favorite status of a sound. Returns the new favorite state.
-    pub fn toggle_favorite(&self, sound_id: &str) -> Result<bool, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    /// Returns the game audio folder used last, if any.
+    pub fn get_last_game_path(&self) -> Result<Option<String>, KitharaError> {
+        self.get_metadata(LAST_GAME_PATH_KEY)
+    }
 
-        conn.execute(
+    /// Persists the game audio folder so it's offered again on next launch.
+    pub fn save_last_game_path(&self, path: &str) -> Result<(), KitharaError> {
+        self.set_metadata(LAST_GAME_PATH_KEY, path)
+    }
+
+    /// Returns the last conversion concurrency limit chosen by the user, if any.
+    pub fn get_saved_max_concurrency(&self) -> Result<Option<usize>, KitharaError> {
+        Ok(self
+            .get_metadata(MAX_CONCURRENCY_KEY)?
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|&limit| limit >= 1))
+    }
+
+    /// Persists the conversion concurrency limit so later runs default to it.
+    pub fn save_max_concurrency(&self, limit: usize) -> Result<(), KitharaError> {
+        self.set_metadata(MAX_CONCURRENCY_KEY, &limit.to_string())
+    }
+
+    /// Records the options of an extraction that has started but not finished,
+    /// or clears the record once it completes.
+    pub fn set_pending_extraction(&self, options: Option<&ExtractionOptions>) -> Result<(), KitharaError> {
+        match options {
+            Some(options) => {
+                let options_json = serde_json::to_string(options)
+                    .map_err(|e| KitharaError::Database(format!("Failed to serialize extraction options: {}", e)))?;
+                self.set_metadata(PENDING_EXTRACTION_KEY, &options_json)
+            }
+            None => {
+                let conn = self.conn()?;
+                conn.execute("DELETE FROM metadata WHERE key = ?1", params![PENDING_EXTRACTION_KEY])
+                    .map_err(|e| KitharaError::Database(format!("Failed to clear pending extraction: {}", e)))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the options of an extraction that was interrupted before finishing, if any.
+    pub fn get_pending_extraction(&self) -> Result<Option<ExtractionOptions>, KitharaError> {
+        Ok(self
+            .get_metadata(PENDING_EXTRACTION_KEY)?
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Inserts a sound into the catalog. FTS is updated via trigger.
+    pub fn insert_sound(&self, sound: &Sound) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        insert_sound_row(&conn, sound)
+    }
+
+    /// Inserts or replaces many sounds in one transaction. A row that fails doesn't
+    /// stop the rest; returns the IDs that failed with their errors.
+    pub fn insert_sounds_batch(&self, sounds: &[Sound]) -> Result<Vec<(String, KitharaError)>, KitharaError> {
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        let mut failures = Vec::new();
+        for sound in sounds {
+            if let Err(e) = insert_sound_row(&tx, sound) {
+                failures.push((sound.id.clone(), e));
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit sound batch: {}", e)))?;
+        Ok(failures)
+    }
+
+    /// Toggles the favorite status of a sound. Returns the new state and favorites total.
+    pub fn toggle_favorite(&self, sound_id: &str) -> Result<FavoriteToggle, KitharaError> {
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        tx.execute(
             "UPDATE sounds SET is_favorite = NOT is_favorite WHERE id = ?1",
             params![sound_id],
         )
-        .map_err(|e| format!("Failed to toggle favorite: {}", e))?;
+        .map_err(|e| KitharaError::Database(format!("Failed to toggle favorite: {}", e)))?;
 
-        let new_state: i32 = conn
+        let new_state: i32 = tx
             .query_row(
                 "SELECT is_favorite FROM sounds WHERE id = ?1",
                 params![sound_id],
                 |row| row.get(0),
             )
-            .map_err(|e| format!("Failed to get new favorite state: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to get new favorite state: {}", e)))?;
 
-        Ok(new_state != 0)
-    }
+        // Counted in the same transaction so a concurrent toggle can't make it stale
+        let total_favorites: u64 = tx
+            .query_row("SELECT COUNT(*) FROM sounds WHERE is_favorite = 1", [], |row| row.get(0))
+            .map_err(|e| KitharaError::Database(format!("Failed to count favorites: {}", e)))?;
 
-    /// Returns count of sounds in the catalog.
-    pub fn count_sounds(&self) -> Result<u64, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        let count: u64 = conn
-            .query_row("SELECT COUNT(*) FROM sounds", [], |row| row.get(0))
-            .map_err(|e| format!("Failed to count: {}", e))?;
-        Ok(count)
-    }
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit favorite: {}", e)))?;
 
-    /// Returns count of favorited sounds.
-    pub fn count_favorites(&self) -> Result<u64, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        let count: u64 = conn
-            .query_row("SELECT COUNT(*) FROM sounds WHERE is_favorite = 1", [], |row| row.get(0))
-            .map_err(|e| format!("Failed to count favorites: {}", e))?;
-        Ok(count)
+        Ok(FavoriteToggle {
+            is_favorite: new_state != 0,
+            total_favorites,
+        })
+    }
+
+    /// Adds a user tag to a sound. Tags are compared case-insensitively, so adding
+    /// an existing tag is a no-op. Returns the sound's updated tags.
+    pub fn add_tag(&self, sound_id: &str, tag: &str) -> Result<Vec<String>, KitharaError> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(KitharaError::InvalidInput("Tag cannot be empty".into()));
+        }
+        if tag.chars().count() > MAX_TAG_LEN {
+            return Err(KitharaError::InvalidInput(format!("Tag cannot be longer than {} characters", MAX_TAG_LEN)));
+        }
+
+        self.update_tags(sound_id, |tags| {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        })
+    }
+
+    /// Removes a tag (case-insensitive) from a sound. Returns the sound's updated tags.
+    pub fn remove_tag(&self, sound_id: &str, tag: &str) -> Result<Vec<String>, KitharaError> {
+        let tag = tag.trim();
+        self.update_tags(sound_id, |tags| tags.retain(|t| !t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Writes the classification of each sound (category, unit type, subcategory,
+    /// display name, tags, and gender) in one transaction. Other fields are left alone.
+    pub fn update_classifications(&self, sounds: &[Sound]) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        {
+            let mut stmt = tx
+                .prepare(
+                    "UPDATE sounds SET category = ?1, unit_type = ?2, subcategory = ?3,
+                     display_name = ?4, tags = ?5, gender = ?6 WHERE id = ?7",
+                )
+                .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+            for sound in sounds {
+                let tags_json = serde_json::to_string(&sound.tags)
+                    .map_err(|e| KitharaError::Database(format!("Failed to serialize tags: {}", e)))?;
+                stmt.execute(params![
+                    sound.category,
+                    sound.unit_type,
+                    sound.subcategory,
+                    sound.display_name,
+                    tags_json,
+                    sound.gender,
+                    sound.id,
+                ])
+                .map_err(|e| KitharaError::Database(format!("Failed to update {}: {}", sound.id, e)))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit classifications: {}", e)))
+    }
+
+    /// Loads a sound's tags, applies `edit`, and writes them back.
+    /// The update trigger keeps the FTS index current.
+    fn update_tags(
+        &self,
+        sound_id: &str,
+        edit: impl FnOnce(&mut Vec<String>),
+    ) -> Result<Vec<String>, KitharaError> {
+        let mut conn = self.conn()?;
+        // Takes the write lock up front so a concurrent edit can't land between
+        // the read and the write
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        let result = tx.query_row(
+            "SELECT tags FROM sounds WHERE id = ?1",
+            params![sound_id],
+            |row| row.get::<_, Option<String>>(0),
+        );
+        let tags_json = match result {
+            Ok(tags_json) => tags_json,
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                return Err(KitharaError::NotFound(format!("Sound not found: {}", sound_id)))
+            }
+            Err(e) => return Err(KitharaError::Database(format!("Failed to get tags: {}", e))),
+        };
+
+        let mut tags: Vec<String> = tags_json
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        edit(&mut tags);
+
+        let tags_json = serde_json::to_string(&tags)
+            .map_err(|e| KitharaError::Database(format!("Failed to serialize tags: {}", e)))?;
+        tx.execute(
+            "UPDATE sounds SET tags = ?1 WHERE id = ?2",
+            params![tags_json, sound_id],
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to update tags: {}", e)))?;
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit tags: {}", e)))?;
+
+        Ok(tags)
+    }
+
+    /// Sets the gain trim (in dB) applied when playing a sound.
+    pub fn set_sound_gain(&self, sound_id: &str, gain_db: f64) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+
+        let updated = conn
+            .execute(
+                "UPDATE sounds SET gain_db = ?1 WHERE id = ?2",
+                params![gain_db, sound_id],
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to set sound gain: {}", e)))?;
+
+        if updated == 0 {
+            return Err(KitharaError::NotFound(format!("Sound not found: {}", sound_id)));
+        }
+        Ok(())
+    }
+
+    /// Returns true if a sound with this ID is in the catalog.
+    pub fn has_sound(&self, sound_id: &str) -> Result<bool, KitharaError> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sounds WHERE id = ?1)",
+            params![sound_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to look up sound: {}", e)))
+    }
+
+    /// Returns the audio file path for a sound or music track ID, or None if neither exists.
+    pub fn get_audio_path(&self, id: &str) -> Result<Option<String>, KitharaError> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT file_path FROM sounds WHERE id = ?1
+             UNION ALL
+             SELECT file_path FROM music_tracks WHERE id = ?1
+             LIMIT 1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| KitharaError::Database(format!("Failed to look up audio path: {}", e)))
+    }
+
+    /// Returns the gain (in dB) to play a sound at: its own trim plus its category's
+    /// default gain. 0 if the sound isn't in the catalog.
+    pub fn get_playback_gain(&self, sound_id: &str) -> Result<f64, KitharaError> {
+        let conn = self.conn()?;
+
+        let gain_db: Option<f64> = conn
+            .query_row(
+                "SELECT s.gain_db + COALESCE(c.gain_db, 0)
+                 FROM sounds s
+                 LEFT JOIN category_settings c ON c.category = s.category
+                 WHERE s.id = ?1",
+                params![sound_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| KitharaError::Database(format!("Failed to get playback gain: {}", e)))?;
+        Ok(gain_db.unwrap_or(0.0))
+    }
+
+    /// Sets the default gain (in dB) for every sound in a category.
+    pub fn set_category_gain(&self, category: &str, gain_db: f64) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO category_settings (category, gain_db) VALUES (?1, ?2)
+             ON CONFLICT(category) DO UPDATE SET gain_db = excluded.gain_db",
+            params![category, gain_db],
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to set category gain: {}", e)))?;
+        Ok(())
+    }
+
+    /// Returns the default gain (in dB) of each category that has one.
+    pub fn get_category_gains(&self) -> Result<HashMap<String, f64>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare("SELECT category, gain_db FROM category_settings")
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect category gains: {}", e)))
+    }
+
+    /// Returns true if the sound is marked as a seamless loop.
+    pub fn is_loop_sound(&self, sound_id: &str) -> Result<bool, KitharaError> {
+        let conn = self.conn()?;
+
+        let is_loop: Option<i32> = conn
+            .query_row(
+                "SELECT is_loop FROM sounds WHERE id = ?1",
+                params![sound_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| KitharaError::Database(format!("Failed to check loop flag: {}", e)))?;
+        Ok(is_loop.unwrap_or(0) != 0)
+    }
+
+    /// Sets the favorite state of many sounds at once, atomically.
+    /// Returns the number of sounds updated; unknown IDs are ignored.
+    pub fn set_favorites(&self, ids: &[String], favorite: bool) -> Result<usize, KitharaError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        let favorite_int: i32 = if favorite { 1 } else { 0 };
+        let mut query_params: Vec<&dyn ToSql> = vec![&favorite_int];
+        let condition = in_condition("id", ids, &mut query_params);
+
+        let updated = tx
+            .execute(
+                &format!("UPDATE sounds SET is_favorite = ?1 WHERE {}", condition),
+                query_params.as_slice(),
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to update favorites: {}", e)))?;
+
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit favorites: {}", e)))?;
+        Ok(updated)
+    }
+
+    /// Returns count of sounds in the catalog.
+    pub fn count_sounds(&self) -> Result<u64, KitharaError> {
+        let conn = self.conn()?;
+        let count: u64 = conn
+            .query_row("SELECT COUNT(*) FROM sounds", [], |row| row.get(0))
+            .map_err(|e| KitharaError::Database(format!("Failed to count: {}", e)))?;
+        Ok(count)
+    }
+
+    /// Returns count of favorited sounds.
+    pub fn count_favorites(&self) -> Result<u64, KitharaError> {
+        let conn = self.conn()?;
+        let count: u64 = conn
+            .query_row("SELECT COUNT(*) FROM sounds WHERE is_favorite = 1", [], |row| row.get(0))
+            .map_err(|e| KitharaError::Database(format!("Failed to count favorites: {}", e)))?;
+        Ok(count)
+    }
+
+    /// Returns aggregate counts and total durations for sounds and music.
+    pub fn get_stats(&self) -> Result<CatalogStats, KitharaError> {
+        let conn = self.conn()?;
+        // Both counts read the same snapshot
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        let (sound_count, favorite_count, category_count, unit_type_count, sound_duration_ms) = tx
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(is_favorite), 0), COUNT(DISTINCT category),
+                        COUNT(DISTINCT unit_type), COALESCE(SUM(duration_ms), 0)
+                 FROM sounds",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get::<_, i64>(4)?)),
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to get sound stats: {}", e)))?;
+
+        let (music_track_count, total_music_duration_secs) = tx
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(duration_secs), 0.0) FROM music_tracks",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to get music stats: {}", e)))?;
+
+        Ok(CatalogStats {
+            sound_count,
+            music_track_count,
+            favorite_count,
+            category_count,
+            unit_type_count,
+            total_sound_duration_secs: sound_duration_ms as f64 / 1000.0,
+            total_music_duration_secs,
+        })
+    }
+
+    /// Returns the sounds with the given IDs, in the order the IDs were given.
+    /// IDs that aren't in the catalog are left out.
+    pub fn get_sounds_by_ids(&self, ids: &[String]) -> Result<Vec<Sound>, KitharaError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn()?;
+
+        let mut query_params: Vec<&dyn ToSql> = Vec::new();
+        let condition = in_condition("s.id", ids, &mut query_params);
+        let mut stmt = conn
+            .prepare(&format!("SELECT {} FROM sounds s WHERE {}", SOUND_COLUMNS, condition))
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+
+        let rows = stmt
+            .query_map(query_params.as_slice(), row_to_sound)
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+        let mut by_id: HashMap<String, Sound> = rows
+            .map(|row| row.map(|sound| (sound.id.clone(), sound)))
+            .collect::<Result<_, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))?;
+
+        Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
+    }
+
+    /// Returns the sounds extracted from the named soundbank file, ordered by name.
+    pub fn get_sounds_by_bank(&self, bank_name: &str) -> Result<Vec<Sound>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM sounds s WHERE s.source_bank = ?1 ORDER BY s.display_name COLLATE natural_order ASC",
+                SOUND_COLUMNS
+            ))
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+
+        let rows = stmt
+            .query_map(params![bank_name], row_to_sound)
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
+    }
+
+    /// Returns up to `limit` sounds most like the given one, excluding it. Each shared
+    /// unit type, category, and tag counts as one point; sounds sharing nothing are
+    /// left out. Ties are ordered by name. Empty if the sound isn't in the catalog.
+    pub fn get_similar(&self, sound_id: &str, limit: usize) -> Result<Vec<Sound>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "WITH seed AS (SELECT id, category, unit_type, tags FROM sounds WHERE id = ?1),
+                 scored AS (
+                     SELECT c.id AS id,
+                            (c.unit_type IS NOT NULL AND c.unit_type = seed.unit_type)
+                            + (c.category = seed.category)
+                            + (SELECT COUNT(*) FROM json_each(c.tags) t
+                               WHERE t.value IN (SELECT value FROM json_each(seed.tags))) AS score
+                     FROM sounds c, seed
+                     WHERE c.id != seed.id
+                 )
+                 SELECT {} FROM scored JOIN sounds s ON s.id = scored.id
+                 WHERE scored.score > 0
+                 ORDER BY scored.score DESC, s.display_name COLLATE natural_order ASC
+                 LIMIT ?2",
+                SOUND_COLUMNS
+            ))
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+
+        let rows = stmt
+            .query_map(params![sound_id, limit as i64], row_to_sound)
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect similar sounds: {}", e)))
+    }
+
+    /// Returns every sound in the catalog, ordered by event name.
+    pub fn get_all_sounds(&self) -> Result<Vec<Sound>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM sounds s ORDER BY s.event_name ASC",
+                SOUND_COLUMNS
+            ))
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+
+        let rows = stmt
+            .query_map([], row_to_sound)
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
+    }
+
+    /// Serializes all sounds as a pretty-printed JSON array.
+    pub fn export_json(&self) -> Result<String, KitharaError> {
+        serde_json::to_string_pretty(&self.get_all_sounds()?)
+            .map_err(|e| KitharaError::Database(format!("Failed to serialize catalog: {}", e)))
+    }
+
+    /// Serializes all sounds as CSV with a header row. Tags are joined with
+    /// semicolons, and fields containing commas, quotes, or newlines are quoted.
+    pub fn export_csv(&self) -> Result<String, KitharaError> {
+        let mut csv = String::from(
+            "id,event_name,display_name,category,unit_type,subcategory,duration,tags,is_favorite\n",
+        );
+
+        for sound in self.get_all_sounds()? {
+            let fields = [
+                sound.id,
+                sound.event_name,
+                sound.display_name,
+                sound.category,
+                sound.unit_type.unwrap_or_default(),
+                sound.subcategory,
+                sound.duration.to_string(),
+                sound.tags.join(";"),
+                sound.is_favorite.to_string(),
+            ];
+            let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        Ok(csv)
     }
 
     /// Returns all favorited sounds.
-    pub fn get_favorites(&self) -> Result<Vec<Sound>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    pub fn get_favorites(&self) -> Result<Vec<Sound>, KitharaError> {
+        let conn = self.conn()?;
 
         let mut stmt = conn
             .prepare(
-                "SELECT id, event_name, display_name, category, unit_type, subcategory,
-                        duration_ms, file_path, tags, is_favorite
-                 FROM sounds
-                 WHERE is_favorite = 1
-                 ORDER BY display_name ASC",
+                &format!(
+                    "SELECT {} FROM sounds s
+                     WHERE s.is_favorite = 1
+                     ORDER BY s.display_name COLLATE natural_order ASC",
+                    SOUND_COLUMNS
+                ),
             )
-            .map_err(|e| format!("Failed to prepare: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
 
         let rows = stmt
             .query_map([], row_to_sound)
-            .map_err(|e| format!("Query failed: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
+    }
+
+    /// Creates an empty playlist.
+    pub fn create_playlist(&self, name: &str) -> Result<Playlist, KitharaError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(KitharaError::InvalidInput("Playlist name cannot be empty".into()));
+        }
+
+        let conn = self.conn()?;
+        conn.execute("INSERT INTO playlists (name) VALUES (?1)", params![name])
+            .map_err(|e| KitharaError::Database(format!("Failed to create playlist: {}", e)))?;
+
+        Ok(Playlist {
+            id: conn.last_insert_rowid(),
+            name: name.to_string(),
+            sound_count: 0,
+        })
+    }
+
+    /// Deletes a playlist and its items.
+    pub fn delete_playlist(&self, playlist_id: i64) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+
+        let deleted = conn
+            .execute("DELETE FROM playlists WHERE id = ?1", params![playlist_id])
+            .map_err(|e| KitharaError::Database(format!("Failed to delete playlist: {}", e)))?;
+
+        if deleted == 0 {
+            return Err(KitharaError::NotFound(format!("Playlist not found: {}", playlist_id)));
+        }
+        Ok(())
+    }
+
+    /// Returns all playlists with their sound counts, ordered by name.
+    pub fn get_playlists(&self) -> Result<Vec<Playlist>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT p.id, p.name, COUNT(pi.sound_id)
+                 FROM playlists p
+                 LEFT JOIN playlist_items pi ON pi.playlist_id = p.id
+                 GROUP BY p.id
+                 ORDER BY p.name COLLATE NOCASE ASC",
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(Playlist {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    sound_count: row.get(2)?,
+                })
+            })
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
+    }
+
+    /// Adds a sound to a playlist at `position` (0-based), shifting later items
+    /// down. Appends when `position` is `None` or past the end. A sound that is
+    /// already in the playlist is moved instead of duplicated.
+    pub fn add_to_playlist(
+        &self,
+        playlist_id: i64,
+        sound_id: &str,
+        position: Option<u32>,
+    ) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        let exists: bool = tx
+            .query_row(
+                "SELECT COUNT(*) FROM playlists WHERE id = ?1",
+                params![playlist_id],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .map_err(|e| KitharaError::Database(format!("Failed to look up playlist: {}", e)))?;
+        if !exists {
+            return Err(KitharaError::NotFound(format!("Playlist not found: {}", playlist_id)));
+        }
+
+        remove_playlist_item(&tx, playlist_id, sound_id)?;
+
+        let len: u32 = tx
+            .query_row(
+                "SELECT COUNT(*) FROM playlist_items WHERE playlist_id = ?1",
+                params![playlist_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to count playlist items: {}", e)))?;
+        let position = position.map_or(len, |p| p.min(len));
+
+        tx.execute(
+            "UPDATE playlist_items SET position = position + 1
+             WHERE playlist_id = ?1 AND position >= ?2",
+            params![playlist_id, position],
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to shift playlist items: {}", e)))?;
+
+        tx.execute(
+            "INSERT INTO playlist_items (playlist_id, sound_id, position) VALUES (?1, ?2, ?3)",
+            params![playlist_id, sound_id, position],
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to add to playlist: {}", e)))?;
+
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit playlist change: {}", e)))
+    }
+
+    /// Removes a sound from a playlist, closing the gap in positions.
+    pub fn remove_from_playlist(&self, playlist_id: i64, sound_id: &str) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        if !remove_playlist_item(&tx, playlist_id, sound_id)? {
+            return Err(KitharaError::NotFound(format!("Sound {} is not in playlist {}", sound_id, playlist_id)));
+        }
+
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit playlist change: {}", e)))
+    }
+
+    /// Returns the sounds in a playlist in playlist order. Items whose sound is
+    /// no longer in the catalog are skipped.
+    pub fn get_playlist_sounds(&self, playlist_id: i64) -> Result<Vec<Sound>, KitharaError> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM playlist_items pi
+                 JOIN sounds s ON s.id = pi.sound_id
+                 WHERE pi.playlist_id = ?1
+                 ORDER BY pi.position ASC",
+                SOUND_COLUMNS
+            ))
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+
+        let rows = stmt
+            .query_map(params![playlist_id], row_to_sound)
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
 
         rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to collect: {}", e))
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
     }
 
     /// Clears all sounds from the catalog and resets migration flags.
     /// Used when rebuilding the cache.
-    pub fn clear_all(&self) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    pub fn clear_all(&self) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
 
         // Delete all sounds (triggers will clean up FTS)
-        conn.execute("DELETE FROM sounds", [])
-            .map_err(|e| format!("Failed to clear sounds: {}", e))?;
+        tx.execute("DELETE FROM sounds", [])
+            .map_err(|e| KitharaError::Database(format!("Failed to clear sounds: {}", e)))?;
 
-        // Reset migration flags so they run again on next extraction
-        conn.execute("DELETE FROM metadata", [])
-            .map_err(|e| format!("Failed to clear metadata: {}", e))?;
+        // Reset migration flags so they run again on next extraction.
+        // Other metadata (settings, previous category counts) is kept.
+        tx.execute("DELETE FROM metadata WHERE key LIKE 'migration_%'", [])
+            .map_err(|e| KitharaError::Database(format!("Failed to clear metadata: {}", e)))?;
 
-        Ok(())
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit clear: {}", e)))
+    }
+
+    /// Copies the live database to `dest` using SQLite's online backup API,
+    /// so it's safe while the app is running.
+    pub fn backup_to(&self, dest: &Path) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        conn.backup(DatabaseName::Main, dest, None)
+            .map_err(|e| KitharaError::Database(format!("Failed to back up database: {}", e)))
+    }
+
+    /// Replaces the live database with the catalog at `src`, then brings its
+    /// schema up to date. Refuses files that aren't a catalog.
+    pub fn restore_from(&self, src: &Path) -> Result<(), KitharaError> {
+        validate_catalog_file(src)?;
+
+        {
+            let mut conn = self.conn()?;
+            conn.restore(DatabaseName::Main, src, None::<fn(Progress)>)
+                .map_err(|e| KitharaError::Database(format!("Failed to restore database: {}", e)))?;
+        }
+
+        // The backup may predate newer columns and tables
+        self.init_schema()?;
+        self.run_migrations()
+    }
+
+    /// Compacts the database file and merges the full-text index segments.
+    /// Writes from other connections wait on the database lock until it finishes.
+    pub fn optimize(&self) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+        conn.execute_batch(
+            "INSERT INTO sounds_fts(sounds_fts) VALUES ('optimize');
+             INSERT INTO music_tracks_fts(music_tracks_fts) VALUES ('optimize');
+             VACUUM;
+             PRAGMA optimize;",
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to optimize database: {}", e)))?;
+
+        // In WAL mode the compacted pages land in the -wal file while other
+        // connections are open; copy them back so the database file shrinks
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .map_err(|e| KitharaError::Database(format!("Failed to checkpoint database: {}", e)))
+    }
+
+    /// Deletes a single sound and drops it from any playlists.
+    /// Returns its file path so the caller can remove the file, or `None` if
+    /// the sound wasn't in the catalog.
+    pub fn delete_sound(&self, sound_id: &str) -> Result<Option<String>, KitharaError> {
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        let file_path = delete_sound_row(&tx, sound_id)?;
+
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit sound deletion: {}", e)))?;
+        Ok(file_path)
+    }
+
+    /// Returns the sounds whose audio file no longer exists on disk,
+    /// e.g. after the sounds folder was cleared or edited by hand.
+    pub fn find_orphaned_sounds(&self) -> Result<Vec<Sound>, KitharaError> {
+        Ok(self
+            .get_all_sounds()?
+            .into_iter()
+            .filter(|sound| !Path::new(&sound.file_path).exists())
+            .collect())
+    }
+
+    /// Deletes every sound whose audio file is missing and drops it from any playlists.
+    /// Returns the number of sounds removed.
+    pub fn prune_orphaned_sounds(&self) -> Result<usize, KitharaError> {
+        let orphans = self.find_orphaned_sounds()?;
+        if orphans.is_empty() {
+            return Ok(0);
+        }
+
+        let conn = self.conn()?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
+        for sound in &orphans {
+            delete_sound_row(&tx, &sound.id)?;
+        }
+
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit orphan cleanup: {}", e)))?;
+        Ok(orphans.len())
     }
 
     /// Deletes sounds matching any of the given patterns (case-insensitive substring match on event_name).
     /// Returns the file paths of deleted sounds so they can be removed from disk.
-    pub fn delete_sounds_matching_patterns(&self, patterns: &[&str]) -> Result<Vec<String>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    pub fn delete_sounds_matching_patterns(&self, patterns: &[&str]) -> Result<Vec<String>, KitharaError> {
+        let mut conn = self.conn()?;
 
         // Build WHERE clause for pattern matching
         // Using LIKE with LOWER() for case-insensitive substring matching
@@ -443,45 +1623,64 @@ impl Catalog {
 
         let where_clause = conditions.join(" OR ");
 
+        // Locked up front so a sound inserted meanwhile isn't deleted without its path
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| KitharaError::Database(format!("Failed to start transaction: {}", e)))?;
+
         // First, get the file paths of sounds to delete
         let select_sql = format!("SELECT file_path FROM sounds WHERE {}", where_clause);
-        let mut stmt = conn
-            .prepare(&select_sql)
-            .map_err(|e| format!("Failed to prepare select: {}", e))?;
-
-        let file_paths: Vec<String> = stmt
-            .query_map([], |row| row.get(0))
-            .map_err(|e| format!("Query failed: {}", e))?
-            .filter_map(|r| r.ok())
-            .collect();
+        let file_paths: Vec<String> = {
+            let mut stmt = tx
+                .prepare(&select_sql)
+                .map_err(|e| KitharaError::Database(format!("Failed to prepare select: {}", e)))?;
+            let rows = stmt
+                .query_map([], |row| row.get(0))
+                .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
 
         // Delete the sounds from the database
         let delete_sql = format!("DELETE FROM sounds WHERE {}", where_clause);
-        conn.execute(&delete_sql, [])
-            .map_err(|e| format!("Failed to delete sounds: {}", e))?;
+        tx.execute(&delete_sql, [])
+            .map_err(|e| KitharaError::Database(format!("Failed to delete sounds: {}", e)))?;
 
+        tx.commit()
+            .map_err(|e| KitharaError::Database(format!("Failed to commit deletion: {}", e)))?;
         Ok(file_paths)
     }
 
     // ========== Music Track Methods ==========
 
     /// Inserts a music track into the catalog.
-    pub fn insert_music_track(&self, track: &MusicTrack) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    pub fn insert_music_track(&self, track: &MusicTrack) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
 
         conn.execute(
             "INSERT OR REPLACE INTO music_tracks (id, title, file_path, duration_secs)
              VALUES (?1, ?2, ?3, ?4)",
             params![track.id, track.title, track.file_path, track.duration_secs],
         )
-        .map_err(|e| format!("Failed to insert music track: {}", e))?;
+        .map_err(|e| KitharaError::Database(format!("Failed to insert music track: {}", e)))?;
 
         Ok(())
     }
 
+    /// Returns true if a music track with this ID is in the catalog.
+    pub fn has_music_track(&self, track_id: &str) -> Result<bool, KitharaError> {
+        let conn = self.conn()?;
+
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM music_tracks WHERE id = ?1)",
+            params![track_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to look up music track: {}", e)))
+    }
+
     /// Returns all music tracks, ordered by title.
-    pub fn get_music_tracks(&self) -> Result<Vec<MusicTrack>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    pub fn get_music_tracks(&self) -> Result<Vec<MusicTrack>, KitharaError> {
+        let conn = self.conn()?;
 
         let mut stmt = conn
             .prepare(
@@ -489,7 +1688,7 @@ impl Catalog {
                  FROM music_tracks
                  ORDER BY title ASC",
             )
-            .map_err(|e| format!("Failed to prepare: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
 
         let rows = stmt
             .query_map([], |row| {
@@ -500,30 +1699,34 @@ impl Catalog {
                     duration_secs: row.get(3)?,
                 })
             })
-            .map_err(|e| format!("Query failed: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
 
         rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to collect: {}", e))
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
     }
 
-    /// Searches music tracks by title.
-    pub fn search_music_tracks(&self, query: &str) -> Result<Vec<MusicTrack>, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+    /// Searches music tracks by title using FTS5 prefix matching, best match first.
+    /// Empty query returns all tracks.
+    pub fn search_music_tracks(&self, query: &str) -> Result<Vec<MusicTrack>, KitharaError> {
+        let Some(fts_query) = build_fts_query(query) else {
+            return self.get_music_tracks();
+        };
 
-        let search_pattern = format!("%{}%", query.to_lowercase());
+        let conn = self.conn()?;
 
         let mut stmt = conn
             .prepare(
-                "SELECT id, title, file_path, duration_secs
-                 FROM music_tracks
-                 WHERE LOWER(title) LIKE ?1
-                 ORDER BY title ASC
+                "SELECT m.id, m.title, m.file_path, m.duration_secs
+                 FROM music_tracks m
+                 JOIN music_tracks_fts fts ON m.rowid = fts.rowid
+                 WHERE music_tracks_fts MATCH ?1
+                 ORDER BY rank
                  LIMIT 100",
             )
-            .map_err(|e| format!("Failed to prepare: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
 
         let rows = stmt
-            .query_map(params![search_pattern], |row| {
+            .query_map(params![fts_query], |row| {
                 Ok(MusicTrack {
                     id: row.get(0)?,
                     title: row.get(1)?,
@@ -531,25 +1734,293 @@ impl Catalog {
                     duration_secs: row.get(3)?,
                 })
             })
-            .map_err(|e| format!("Query failed: {}", e))?;
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
 
         rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("Failed to collect: {}", e))
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
     }
 
-    /// Returns count of music tracks.
-    pub fn count_music_tracks(&self) -> Result<u64, String> {
-        let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        let count: u64 = conn
-            .query_row("SELECT COUNT(*) FROM music_tracks", [], |row| row.get(0))
-            .map_err(|e| format!("Failed to count: {}", e))?;
-        Ok(count)
-    }
+    /// Returns music tracks whose duration is unknown (stored as 0).
+    pub fn get_music_tracks_missing_duration(&self) -> Result<Vec<MusicTrack>, KitharaError> {
+        let conn = self.conn()?;
 
-}
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, title, file_path, duration_secs
+                 FROM music_tracks
+                 WHERE duration_secs IS NULL OR duration_secs <= 0",
+            )
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
 
-/// Helper function to convert a row to a Sound struct
-fn row_to_sound(row: &rusqlite::Row) -> rusqlite::Result<Sound> {
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(MusicTrack {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    file_path: row.get(2)?,
+                    duration_secs: row.get::<_, Option<f64>>(3)?.unwrap_or(0.0),
+                })
+            })
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))
+    }
+
+    /// Updates the stored duration of a music track.
+    pub fn update_music_track_duration(&self, track_id: &str, duration_secs: f64) -> Result<(), KitharaError> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            "UPDATE music_tracks SET duration_secs = ?1 WHERE id = ?2",
+            params![duration_secs, track_id],
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to update music track duration: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Returns count of music tracks.
+    pub fn count_music_tracks(&self) -> Result<u64, KitharaError> {
+        let conn = self.conn()?;
+        let count: u64 = conn
+            .query_row("SELECT COUNT(*) FROM music_tracks", [], |row| row.get(0))
+            .map_err(|e| KitharaError::Database(format!("Failed to count: {}", e)))?;
+        Ok(count)
+    }
+
+}
+
+/// Deletes one sound row and its playlist entries, returning its file path,
+/// or `None` if the sound wasn't in the catalog
+fn delete_sound_row(conn: &Connection, sound_id: &str) -> Result<Option<String>, KitharaError> {
+    let file_path = conn
+        .query_row(
+            "SELECT file_path FROM sounds WHERE id = ?1",
+            params![sound_id],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(|e| KitharaError::Database(format!("Failed to look up sound: {}", e)))?;
+    if file_path.is_none() {
+        return Ok(None);
+    }
+
+    // Delete the sound (triggers will clean up FTS)
+    conn.execute("DELETE FROM sounds WHERE id = ?1", params![sound_id])
+        .map_err(|e| KitharaError::Database(format!("Failed to delete sound: {}", e)))?;
+
+    let playlist_ids: Vec<i64> = {
+        let mut stmt = conn
+            .prepare("SELECT playlist_id FROM playlist_items WHERE sound_id = ?1")
+            .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+        let rows = stmt
+            .query_map(params![sound_id], |row| row.get(0))
+            .map_err(|e| KitharaError::Database(format!("Query failed: {}", e)))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| KitharaError::Database(format!("Failed to collect: {}", e)))?
+    };
+    for playlist_id in playlist_ids {
+        remove_playlist_item(conn, playlist_id, sound_id)?;
+    }
+
+    Ok(file_path)
+}
+
+/// Inserts or replaces one sound row (shared by single and batch inserts)
+fn insert_sound_row(conn: &Connection, sound: &Sound) -> Result<(), KitharaError> {
+    let tags_json = serde_json::to_string(&sound.tags)
+        .map_err(|e| KitharaError::Database(format!("Failed to serialize tags: {}", e)))?;
+    let duration_ms = (sound.duration * 1000.0) as i64;
+    let is_favorite_int = if sound.is_favorite { 1 } else { 0 };
+    let is_loop_int = if sound.is_loop { 1 } else { 0 };
+
+    let mut stmt = conn
+        .prepare_cached(
+            "INSERT OR REPLACE INTO sounds
+             (id, event_name, display_name, category, unit_type, subcategory,
+              duration_ms, file_path, tags, is_favorite, gender, gain_db, language, source_bank, is_loop,
+              short_name)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        )
+        .map_err(|e| KitharaError::Database(format!("Failed to prepare: {}", e)))?;
+    stmt.execute(params![
+        sound.id,
+        sound.event_name,
+        sound.display_name,
+        sound.category,
+        sound.unit_type,
+        sound.subcategory,
+        duration_ms,
+        sound.file_path,
+        tags_json,
+        is_favorite_int,
+        sound.gender,
+        sound.gain_db,
+        sound.language,
+        sound.source_bank,
+        is_loop_int,
+        sound.short_name,
+    ])
+    .map_err(|e| KitharaError::Database(format!("Failed to insert sound: {}", e)))?;
+
+    Ok(())
+}
+
+/// Deletes a playlist item and shifts the items after it up by one.
+/// Returns false if the sound wasn't in the playlist.
+fn remove_playlist_item(conn: &Connection, playlist_id: i64, sound_id: &str) -> Result<bool, KitharaError> {
+    let result = conn.query_row(
+        "SELECT position FROM playlist_items WHERE playlist_id = ?1 AND sound_id = ?2",
+        params![playlist_id, sound_id],
+        |row| row.get::<_, i64>(0),
+    );
+    let position = match result {
+        Ok(position) => position,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(false),
+        Err(e) => return Err(KitharaError::Database(format!("Failed to look up playlist item: {}", e))),
+    };
+
+    conn.execute(
+        "DELETE FROM playlist_items WHERE playlist_id = ?1 AND sound_id = ?2",
+        params![playlist_id, sound_id],
+    )
+    .map_err(|e| KitharaError::Database(format!("Failed to remove from playlist: {}", e)))?;
+
+    conn.execute(
+        "UPDATE playlist_items SET position = position - 1
+         WHERE playlist_id = ?1 AND position > ?2",
+        params![playlist_id, position],
+    )
+    .map_err(|e| KitharaError::Database(format!("Failed to shift playlist items: {}", e)))?;
+
+    Ok(true)
+}
+
+/// Builds a `column IN (?n, ...)` condition, appending the values to `params`
+/// so the placeholder numbers line up with their positions.
+fn in_condition<'a>(column: &str, values: &'a [String], params: &mut Vec<&'a dyn ToSql>) -> String {
+    let placeholders: Vec<String> = values
+        .iter()
+        .map(|value| {
+            params.push(value);
+            format!("?{}", params.len())
+        })
+        .collect();
+    format!("{} IN ({})", column, placeholders.join(", "))
+}
+
+/// FTS5 boolean operators, passed through when typed in uppercase between terms
+const FTS_OPERATORS: &[&str] = &["AND", "OR", "NOT"];
+
+/// Turns user input into a safe FTS5 query, or `None` if there's nothing to match.
+/// Quoted phrases pass through as phrases, a stray quote is ignored, and every
+/// other word is quoted so punctuation can't break the query syntax. Operators
+/// are kept only between two terms, and only a final bare word gets the prefix `*`.
+fn build_fts_query(query: &str) -> Option<String> {
+    // (text, is_phrase) pieces, with operators as bare words
+    let mut pieces: Vec<(String, bool)> = Vec::new();
+    let parts: Vec<&str> = query.split('"').collect();
+    for (i, part) in parts.iter().enumerate() {
+        // Odd parts are inside quotes, unless the last quote was never closed
+        if i % 2 == 1 && i + 1 < parts.len() {
+            pieces.push((part.split_whitespace().collect::<Vec<_>>().join(" "), true));
+        } else {
+            pieces.extend(part.split_whitespace().map(|word| (word.to_string(), false)));
+        }
+    }
+    // Pieces without any searchable characters would be empty phrases
+    pieces.retain(|(text, _)| text.chars().any(char::is_alphanumeric));
+
+    let is_operator = |piece: &(String, bool)| !piece.1 && FTS_OPERATORS.contains(&piece.0.as_str());
+    let mut out: Vec<String> = Vec::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        let (text, is_phrase) = piece;
+        let follows_term = out.last().is_some_and(|last| !FTS_OPERATORS.contains(&last.as_str()));
+        let precedes_term = pieces.get(i + 1).is_some_and(|next| !is_operator(next));
+        if is_operator(piece) && follows_term && precedes_term {
+            out.push(text.clone());
+        } else if !is_phrase && i + 1 == pieces.len() {
+            out.push(format!("\"{}\"*", text)); // Prefix search
+        } else {
+            out.push(format!("\"{}\"", text));
+        }
+    }
+
+    (!out.is_empty()).then(|| out.join(" "))
+}
+
+/// Splits text into lowercase words on anything that isn't a letter or digit.
+fn search_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+/// Total edit distance from each query term to its closest word in the sound's
+/// names, or `None` if any term is too far from every word to be a typo.
+/// A term may also match the start of a longer word, as in prefix search.
+fn fuzzy_score(terms: &[String], sound: &Sound) -> Option<usize> {
+    let words: Vec<String> = search_words(&sound.event_name)
+        .chain(search_words(&sound.display_name))
+        .collect();
+
+    terms.iter().try_fold(0, |total, term| {
+        let term_len = term.chars().count();
+        let max_distance = (term_len / 3).max(1);
+        let best = words
+            .iter()
+            .map(|word| {
+                let prefix: String = word.chars().take(term_len).collect();
+                edit_distance(term, word).min(edit_distance(term, &prefix))
+            })
+            .min()?;
+        (best <= max_distance).then_some(total + best)
+    })
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote, or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Maps a sort order to an ORDER BY clause. Ties are broken by name.
+/// FTS rank is only available (and only meaningful) when there is a query.
+fn order_by_clause(sort: SortOrder, use_fts: bool) -> &'static str {
+    match sort {
+        SortOrder::NameAsc => "s.display_name COLLATE natural_order ASC",
+        SortOrder::NameDesc => "s.display_name COLLATE natural_order DESC",
+        SortOrder::DurationAsc => "s.duration_ms ASC, s.display_name COLLATE natural_order ASC",
+        SortOrder::DurationDesc => "s.duration_ms DESC, s.display_name COLLATE natural_order ASC",
+        SortOrder::Category => "s.category ASC, s.display_name COLLATE natural_order ASC",
+        SortOrder::Relevance if use_fts => "rank",
+        SortOrder::Relevance => "s.display_name COLLATE natural_order ASC",
+    }
+}
+
+/// Helper function to convert a row to a Sound struct
+fn row_to_sound(row: &rusqlite::Row) -> rusqlite::Result<Sound> {
     let tags_json: Option<String> = row.get(8)?;
     let tags: Vec<String> = tags_json
         .and_then(|s| serde_json::from_str(&s).ok())
@@ -557,6 +2028,7 @@ fn row_to_sound(row: &rusqlite::Row) -> rusqlite::Result<Sound> {
 
     let duration_ms: i64 = row.get(6)?;
     let is_favorite: i32 = row.get(9)?;
+    let is_loop: i32 = row.get(14)?;
 
     Ok(Sound {
         id: row.get(0)?,
@@ -569,6 +2041,14 @@ fn row_to_sound(row: &rusqlite::Row) -> rusqlite::Result<Sound> {
         file_path: row.get(7)?,
         tags,
         is_favorite: is_favorite != 0,
+        gender: row.get(10)?,
+        language: row.get(12)?,
+        source_bank: row.get(13)?,
+        gain_db: row.get(11)?,
+        is_loop: is_loop != 0,
+        short_name: row.get(15)?,
+        // Checked on demand by `mark_availability`, outside the connection
+        available: true,
     })
 }
 
@@ -602,14 +2082,928 @@ fn format_category_name(category_id: &str) -> String {
     }
 }
 
+/// Checks that `path` is an intact SQLite database with the catalog tables.
+fn validate_catalog_file(path: &Path) -> Result<(), KitharaError> {
+    if !path.is_file() {
+        return Err(KitharaError::NotFound(format!("Backup not found: {}", path.display())));
+    }
+
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| KitharaError::Database(format!("Failed to open backup: {}", e)))?;
+
+    // Also catches files that aren't SQLite databases at all
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| KitharaError::Database(format!("Not a valid catalog database: {}", e)))?;
+    if check != "ok" {
+        return Err(KitharaError::Database(format!("Backup is corrupt: {}", check)));
+    }
+
+    for table in REQUIRED_TABLES {
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                params![table],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|count| count > 0)
+            .map_err(|e| KitharaError::Database(format!("Failed to read backup: {}", e)))?;
+        if !exists {
+            return Err(KitharaError::Database(format!("Not a catalog backup: missing '{}' table", table)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens a connection to the catalog file with the settings every pooled connection needs.
+fn open_pooled_connection(db_path: &Path) -> Result<Connection, KitharaError> {
+    let conn = Connection::open(db_path)
+        .map_err(|e| KitharaError::Database(format!("Failed to open database: {}", e)))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| KitharaError::Database(format!("Failed to set busy timeout: {}", e)))?;
+    // WAL lets reads proceed while another connection writes
+    conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get::<_, String>(0))
+        .map_err(|e| KitharaError::Database(format!("Failed to enable WAL mode: {}", e)))?;
+    // Needed for ON DELETE CASCADE on playlist items
+    conn.execute_batch("PRAGMA foreign_keys = ON;")
+        .map_err(|e| KitharaError::Database(format!("Failed to enable foreign keys: {}", e)))?;
+    register_collations(&conn)?;
+    Ok(conn)
+}
+
+/// Registers the `natural_order` collation used to order display names.
+/// Collations are per connection, so every pooled connection needs it.
+fn register_collations(conn: &Connection) -> Result<(), KitharaError> {
+    conn.create_collation("natural_order", natural_cmp)
+        .map_err(|e| KitharaError::Database(format!("Failed to register collation: {}", e)))
+}
+
+/// Compares strings so that numbered variants sort by value ("Attack 2" before
+/// "Attack 10"). Digit runs compare numerically, everything else case-insensitively;
+/// exact byte order breaks any remaining ties.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a_rest, mut b_rest) = (a, b);
+    while let (Some(a_char), Some(b_char)) = (a_rest.chars().next(), b_rest.chars().next()) {
+        let ordering = if a_char.is_ascii_digit() && b_char.is_ascii_digit() {
+            let a_digits = leading_run(a_rest, true);
+            let b_digits = leading_run(b_rest, true);
+            a_rest = &a_rest[a_digits.len()..];
+            b_rest = &b_rest[b_digits.len()..];
+
+            // Longer runs are bigger numbers once leading zeros are dropped
+            let a_value = a_digits.trim_start_matches('0');
+            let b_value = b_digits.trim_start_matches('0');
+            a_value.len().cmp(&b_value.len()).then_with(|| a_value.cmp(b_value))
+        } else {
+            let a_text = leading_run(a_rest, false);
+            let b_text = leading_run(b_rest, false);
+            a_rest = &a_rest[a_text.len()..];
+            b_rest = &b_rest[b_text.len()..];
+
+            // A digit run sorts before text
+            match (a_text.is_empty(), b_text.is_empty()) {
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                _ => a_text.to_lowercase().cmp(&b_text.to_lowercase()),
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    a_rest.len().cmp(&b_rest.len()).then_with(|| a.cmp(b))
+}
+
+/// The longest prefix of `s` made only of ASCII digits (or only of non-digits)
+fn leading_run(s: &str, digits: bool) -> &str {
+    let end = s
+        .find(|c: char| c.is_ascii_digit() != digits)
+        .unwrap_or(s.len());
+    &s[..end]
+}
+
+/// Connections are opened on demand up to `max_size` and reused once returned.
+struct ConnectionPool {
+    /// File new connections are opened on; None for a single in-memory connection
+    db_path: Option<PathBuf>,
+    max_size: usize,
+    state: Mutex<PoolState>,
+    returned: Condvar,
+}
+
+struct PoolState {
+    idle: Vec<Connection>,
+    /// Connections in existence, idle or checked out
+    open: usize,
+}
+
+impl ConnectionPool {
+    fn new(db_path: Option<PathBuf>, first: Connection, max_size: usize) -> Self {
+        Self {
+            db_path,
+            max_size,
+            state: Mutex::new(PoolState {
+                idle: vec![first],
+                open: 1,
+            }),
+            returned: Condvar::new(),
+        }
+    }
+
+    fn get(&self) -> Result<PooledConnection<'_>, KitharaError> {
+        let mut state = self.state.lock().map_err(|e| KitharaError::Database(e.to_string()))?;
+        loop {
+            if let Some(conn) = state.idle.pop() {
+                return Ok(PooledConnection { pool: self, conn: Some(conn) });
+            }
+            if let Some(db_path) = self.db_path.as_deref().filter(|_| state.open < self.max_size) {
+                // Open outside the lock; the slot is reserved first so the limit holds
+                state.open += 1;
+                drop(state);
+                return match open_pooled_connection(db_path) {
+                    Ok(conn) => Ok(PooledConnection { pool: self, conn: Some(conn) }),
+                    Err(e) => {
+                        if let Ok(mut state) = self.state.lock() {
+                            state.open -= 1;
+                        }
+                        self.returned.notify_one();
+                        Err(e)
+                    }
+                };
+            }
+            state = self.returned.wait(state).map_err(|e| KitharaError::Database(e.to_string()))?;
+        }
+    }
+}
+
+/// A checked-out connection, returned to its pool when dropped
+struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection is present until drop")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("connection is present until drop")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let (Some(conn), Ok(mut state)) = (self.conn.take(), self.pool.state.lock()) {
+            state.idle.push(conn);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+/// Sets `available` on each sound by checking that its file exists.
+/// Costs a stat per sound, so only listings shown in the UI call it.
+pub fn mark_availability(sounds: &mut [Sound]) {
+    for sound in sounds {
+        sound.available = Path::new(&sound.file_path).exists();
+    }
+}
+
+/// Size on disk of the database at `db_path`, including pages still in its WAL file.
+pub fn database_size(db_path: &Path) -> u64 {
+    let mut wal_path = db_path.as_os_str().to_owned();
+    wal_path.push("-wal");
+    [db_path, Path::new(&wal_path)]
+        .iter()
+        .map(|path| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+        .sum()
+}
+
 /// Gets the cross-platform path for the catalog database.
-pub fn get_db_path() -> Result<PathBuf, String> {
+pub fn get_db_path() -> Result<PathBuf, KitharaError> {
     let project_dirs = directories::ProjectDirs::from("com", "kithara", "app")
-        .ok_or_else(|| "Could not determine application data directory".to_string())?;
+        .ok_or_else(|| KitharaError::Io("Could not determine application data directory".into()))?;
 
     let data_dir = project_dirs.data_dir();
     std::fs::create_dir_all(data_dir)
-        .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to create data directory: {}", e)))?;
 
     Ok(data_dir.join("catalog.db"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_sound(id: &str, event_name: &str, category: &str, unit_type: Option<&str>) -> Sound {
+        Sound {
+            id: id.to_string(),
+            event_name: event_name.to_string(),
+            display_name: event_name.replace('.', " "),
+            category: category.to_string(),
+            unit_type: unit_type.map(str::to_string),
+            subcategory: String::new(),
+            duration: 1.5,
+            file_path: format!("/tmp/{}.ogg", id),
+            tags: vec![category.to_string()],
+            is_favorite: false,
+            gender: None,
+            language: None,
+            source_bank: None,
+            gain_db: 0.0,
+            is_loop: false,
+            short_name: None,
+            available: true,
+        }
+    }
+
+    fn seeded_catalog() -> Catalog {
+        let catalog = Catalog::open_in_memory().unwrap();
+        let mut female_grunt = test_sound("5", "vcl.female.grunt.archer", "vocal", Some("Archer"));
+        female_grunt.gender = Some("female".to_string());
+
+        for sound in [
+            test_sound("1", "cmbt.attack.archer", "combat", Some("Archer")),
+            test_sound("2", "cmbt.attack.warrior", "combat", Some("Warrior")),
+            test_sound("3", "vcl.grunt.archer", "vocal", Some("Archer")),
+            test_sound("4", "ui.click", "ui", None),
+            female_grunt,
+        ] {
+            catalog.insert_sound(&sound).unwrap();
+        }
+        catalog
+    }
+
+    fn strs(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    /// Searches without highlighting, returning just the sounds
+    fn search(
+        catalog: &Catalog,
+        query: &str,
+        filters: &SearchFilters,
+        sort: SortOrder,
+        fuzzy: bool,
+    ) -> Result<Vec<Sound>, KitharaError> {
+        let results = catalog.search_sounds(query, filters, sort, fuzzy, None)?;
+        Ok(results.into_iter().map(|result| result.sound).collect())
+    }
+
+    fn ids(sounds: &[Sound]) -> Vec<&str> {
+        let mut ids: Vec<&str> = sounds.iter().map(|s| s.id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_search_filter_combinations() {
+        let catalog = seeded_catalog();
+
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters::default(), SortOrder::Relevance, false).unwrap()), ["1", "2", "3", "4", "5"]);
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { categories: Some(strs(&["combat"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1", "2"]);
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { unit_types: Some(strs(&["Archer"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1", "3", "5"]);
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { categories: Some(strs(&["combat"])), unit_types: Some(strs(&["Archer"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1"]);
+
+        assert_eq!(ids(&search(&catalog, "attack", &SearchFilters::default(), SortOrder::Relevance, false).unwrap()), ["1", "2"]);
+        assert_eq!(ids(&search(&catalog, "arch", &SearchFilters { categories: Some(strs(&["vocal"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["3", "5"]);
+        assert_eq!(ids(&search(&catalog, "attack", &SearchFilters { unit_types: Some(strs(&["Warrior"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["2"]);
+        assert_eq!(ids(&search(&catalog, "archer", &SearchFilters { categories: Some(strs(&["combat"])), unit_types: Some(strs(&["Archer"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1"]);
+        assert!(search(&catalog, "attack", &SearchFilters { categories: Some(strs(&["ui"])), ..Default::default() }, SortOrder::Relevance, false).unwrap().is_empty());
+
+        let multi_category = strs(&["combat", "ui"]);
+        let multi_unit = strs(&["Archer", "Warrior"]);
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { categories: Some(multi_category.clone()), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1", "2", "4"]);
+        assert_eq!(ids(&search(&catalog, "attack", &SearchFilters { categories: Some(multi_category.clone()), unit_types: Some(multi_unit.clone()), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1", "2"]);
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { categories: Some(vec![]), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1", "2", "3", "4", "5"]);
+
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { gender: Some("female".into()), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["5"]);
+        assert_eq!(ids(&search(&catalog, "grunt", &SearchFilters { gender: Some("male".into()), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["3"]);
+        // Only voice lines have a gender, so male leaves out everything else
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { gender: Some("male".into()), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["3"]);
+        assert_eq!(ids(&search(&catalog, "grunt", &SearchFilters { categories: Some(strs(&["vocal"])), unit_types: Some(strs(&["Archer"])), gender: Some("female".into()), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["5"]);
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let catalog = Catalog::open_in_memory().unwrap();
+        for (id, event_name, category, duration) in [
+            ("1", "b.beta", "vocal", 3.0),
+            ("2", "a.alpha", "vocal", 1.0),
+            ("3", "c.gamma", "combat", 2.0),
+        ] {
+            let mut sound = test_sound(id, event_name, category, None);
+            sound.duration = duration;
+            catalog.insert_sound(&sound).unwrap();
+        }
+        let order = |sort| -> Vec<String> {
+            search(&catalog, "", &SearchFilters::default(), sort, false).unwrap().into_iter().map(|s| s.id).collect()
+        };
+
+        assert_eq!(order(SortOrder::NameAsc), ["2", "1", "3"]);
+        assert_eq!(order(SortOrder::NameDesc), ["3", "1", "2"]);
+        assert_eq!(order(SortOrder::DurationAsc), ["2", "3", "1"]);
+        assert_eq!(order(SortOrder::DurationDesc), ["1", "3", "2"]);
+        assert_eq!(order(SortOrder::Category), ["3", "2", "1"]);
+        // No query, so relevance falls back to name order
+        assert_eq!(order(SortOrder::Relevance), ["2", "1", "3"]);
+    }
+
+    #[test]
+    fn test_natural_name_order() {
+        let mut names = vec!["Attack 10", "attack 2", "Attack 1", "Attack", "Attack 02", "Attack 2b", "Attack 2a", "10 Volleys", "9 Volleys"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["9 Volleys", "10 Volleys", "Attack", "Attack 1", "Attack 02", "attack 2", "Attack 2a", "Attack 2b", "Attack 10"]);
+
+        let catalog = Catalog::open_in_memory().unwrap();
+        for (id, display_name) in [("1", "Hit 10"), ("2", "Hit 2"), ("3", "Hit 1")] {
+            let mut sound = test_sound(id, "cmbt.hit", "combat", None);
+            sound.display_name = display_name.to_string();
+            catalog.insert_sound(&sound).unwrap();
+        }
+        let order = |sort| -> Vec<String> {
+            search(&catalog, "", &SearchFilters::default(), sort, false).unwrap().into_iter().map(|s| s.id).collect()
+        };
+        assert_eq!(order(SortOrder::NameAsc), ["3", "2", "1"]);
+        assert_eq!(order(SortOrder::NameDesc), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_insert_sounds_batch() {
+        let catalog = Catalog::open_in_memory().unwrap();
+        let sounds: Vec<Sound> = (0..250)
+            .map(|i| test_sound(&i.to_string(), &format!("cmbt.hit.{}", i), "combat", None))
+            .collect();
+
+        let failures = catalog.insert_sounds_batch(&sounds).unwrap();
+        assert!(failures.is_empty());
+        assert_eq!(catalog.count_sounds().unwrap(), 250);
+        assert_eq!(search(&catalog, "hit", &SearchFilters::default(), SortOrder::Relevance, false).unwrap().len(), 250);
+    }
+
+    #[test]
+    fn test_toggle_favorite() {
+        let catalog = seeded_catalog();
+
+        let toggle = catalog.toggle_favorite("2").unwrap();
+        assert!(toggle.is_favorite);
+        assert_eq!(toggle.total_favorites, 1);
+        assert_eq!(catalog.count_favorites().unwrap(), 1);
+        assert_eq!(ids(&catalog.get_favorites().unwrap()), ["2"]);
+
+        assert!(!catalog.toggle_favorite("2").unwrap().is_favorite);
+        assert_eq!(catalog.count_favorites().unwrap(), 0);
+
+        assert!(catalog.toggle_favorite("missing").is_err());
+    }
+
+    #[test]
+    fn test_search_favorites_only() {
+        let catalog = seeded_catalog();
+        catalog.toggle_favorite("2").unwrap();
+        catalog.toggle_favorite("3").unwrap();
+
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { favorites_only: true, ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["2", "3"]);
+        assert_eq!(ids(&search(&catalog, "attack", &SearchFilters { favorites_only: true, ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["2"]);
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { categories: Some(strs(&["vocal"])), favorites_only: true, ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["3"]);
+    }
+
+    #[test]
+    fn test_search_loops() {
+        let catalog = seeded_catalog();
+        let mut ambience = test_sound("6", "amb.forest.loop", "ambience", None);
+        ambience.is_loop = true;
+        catalog.insert_sound(&ambience).unwrap();
+
+        assert!(catalog.is_loop_sound("6").unwrap());
+        assert!(!catalog.is_loop_sound("1").unwrap());
+        assert!(!catalog.is_loop_sound("missing").unwrap());
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { is_loop: Some(true), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["6"]);
+        assert_eq!(ids(&search(&catalog, "", &SearchFilters { is_loop: Some(false), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn test_edit_tags() {
+        let catalog = seeded_catalog();
+
+        assert_eq!(catalog.add_tag("4", "  Menu  ").unwrap(), ["ui", "Menu"]);
+        assert_eq!(catalog.add_tag("4", "menu").unwrap(), ["ui", "Menu"]);
+        assert_eq!(ids(&search(&catalog, "menu", &SearchFilters::default(), SortOrder::Relevance, false).unwrap()), ["4"]);
+
+        assert!(catalog.add_tag("4", "   ").is_err());
+        assert!(catalog.add_tag("4", &"x".repeat(MAX_TAG_LEN + 1)).is_err());
+        assert!(catalog.add_tag("missing", "tag").is_err());
+
+        assert_eq!(catalog.remove_tag("4", "MENU").unwrap(), ["ui"]);
+        assert!(search(&catalog, "menu", &SearchFilters::default(), SortOrder::Relevance, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_sound() {
+        let catalog = seeded_catalog();
+        let playlist = catalog.create_playlist("Mix").unwrap();
+        for id in ["1", "2", "3"] {
+            catalog.add_to_playlist(playlist.id, id, None).unwrap();
+        }
+
+        assert_eq!(catalog.delete_sound("2").unwrap().as_deref(), Some("/tmp/2.ogg"));
+        assert_eq!(catalog.delete_sound("2").unwrap(), None);
+        assert!(!catalog.has_sound("2").unwrap());
+        assert!(catalog.has_sound("1").unwrap());
+        assert_eq!(catalog.count_sounds().unwrap(), 4);
+        assert!(search(&catalog, "warrior", &SearchFilters::default(), SortOrder::Relevance, false).unwrap().is_empty());
+
+        // Remaining playlist items close the gap
+        catalog.add_to_playlist(playlist.id, "4", Some(1)).unwrap();
+        let order: Vec<String> = catalog.get_playlist_sounds(playlist.id).unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(order, ["1", "4", "3"]);
+    }
+
+    #[test]
+    fn test_prune_orphaned_sounds() {
+        let catalog = seeded_catalog();
+        let file_path = std::env::temp_dir().join(format!("kithara-test-{}-present.ogg", std::process::id()));
+        std::fs::write(&file_path, b"OggS").unwrap();
+        let mut present = test_sound("6", "ui.click", "ui", None);
+        present.file_path = file_path.to_string_lossy().to_string();
+        catalog.insert_sound(&present).unwrap();
+        let playlist = catalog.create_playlist("Mix").unwrap();
+        catalog.add_to_playlist(playlist.id, "2", None).unwrap();
+        catalog.add_to_playlist(playlist.id, "6", None).unwrap();
+
+        // The seeded sounds point at files that were never written
+        let mut sounds = search(&catalog, "", &SearchFilters::default(), SortOrder::Relevance, false).unwrap();
+        mark_availability(&mut sounds);
+        let available: Vec<&str> = sounds.iter().filter(|s| s.available).map(|s| s.id.as_str()).collect();
+        assert_eq!(available, ["6"]);
+        assert_eq!(ids(&catalog.find_orphaned_sounds().unwrap()), ["1", "2", "3", "4", "5"]);
+        assert_eq!(catalog.prune_orphaned_sounds().unwrap(), 5);
+        std::fs::remove_file(&file_path).ok();
+
+        assert_eq!(catalog.count_sounds().unwrap(), 1);
+        assert_eq!(ids(&catalog.get_playlist_sounds(playlist.id).unwrap()), ["6"]);
+    }
+
+    #[test]
+    fn test_set_favorites_bulk() {
+        let catalog = seeded_catalog();
+
+        assert_eq!(catalog.set_favorites(&strs(&["1", "3", "missing"]), true).unwrap(), 2);
+        assert_eq!(ids(&catalog.get_favorites().unwrap()), ["1", "3"]);
+        assert_eq!(ids(&search(&catalog, "archer", &SearchFilters { favorites_only: true, ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["1", "3"]);
+
+        assert_eq!(catalog.set_favorites(&strs(&["1"]), false).unwrap(), 1);
+        assert_eq!(ids(&catalog.get_favorites().unwrap()), ["3"]);
+        assert_eq!(catalog.set_favorites(&[], true).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_sounds_by_ids() {
+        let catalog = seeded_catalog();
+
+        let sounds = catalog.get_sounds_by_ids(&strs(&["3", "missing", "1"])).unwrap();
+        let order: Vec<&str> = sounds.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(order, ["3", "1"]);
+        assert!(catalog.get_sounds_by_ids(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_sounds_by_bank() {
+        let catalog = seeded_catalog();
+        let mut sound = test_sound("6", "vcl.grunt.warrior", "vocal", Some("Warrior"));
+        sound.source_bank = Some("Audio_Animation.bnk".to_string());
+        catalog.insert_sound(&sound).unwrap();
+
+        let sounds = catalog.get_sounds_by_bank("Audio_Animation.bnk").unwrap();
+        assert_eq!(ids(&sounds), ["6"]);
+        assert_eq!(sounds[0].source_bank.as_deref(), Some("Audio_Animation.bnk"));
+        assert!(catalog.get_sounds_by_bank("Missing.bnk").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_subcategories() {
+        let catalog = Catalog::open_in_memory().unwrap();
+        for (id, subcategory) in [("1", "cmbt_rng_slinger"), ("2", "cmbt_rng_slinger"), ("3", "cmbt_melee"), ("4", "")] {
+            let mut sound = test_sound(id, "cmbt.sound", "combat", None);
+            sound.subcategory = subcategory.to_string();
+            catalog.insert_sound(&sound).unwrap();
+        }
+
+        let subcategories = catalog.get_subcategories("combat").unwrap();
+        let names: Vec<_> = subcategories.iter().map(|s| (s.name.as_str(), s.count)).collect();
+        assert_eq!(names, [("Cmbt Rng Slinger", 2), ("Cmbt Melee", 1)]);
+        assert!(catalog.get_subcategories("ui_event").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_update_classifications() {
+        let catalog = seeded_catalog();
+        let mut sound = test_sound("6", "mv.hrs.gallop.00.MSTR.wav", "vocal", None);
+        sound.short_name = Some("mv.hrs.gallop.00.MSTR.wav".to_string());
+        sound.gender = Some("male".to_string());
+        catalog.insert_sound(&sound).unwrap();
+
+        sound.category = "movement".to_string();
+        sound.unit_type = Some("Horse".to_string());
+        sound.display_name = "Movement Horse Gallop".to_string();
+        sound.tags = strs(&["movement", "horse"]);
+        sound.gender = None;
+        catalog.update_classifications(&[sound]).unwrap();
+
+        let updated = &catalog.get_sounds_by_ids(&strs(&["6"])).unwrap()[0];
+        assert_eq!(updated.category, "movement");
+        assert_eq!(updated.unit_type.as_deref(), Some("Horse"));
+        assert_eq!(updated.gender, None);
+        assert_eq!(ids(&search(&catalog, "gallop", &SearchFilters { gender: Some("male".into()), ..Default::default() }, SortOrder::Relevance, false).unwrap()), Vec::<String>::new());
+        assert_eq!(updated.short_name.as_deref(), Some("mv.hrs.gallop.00.MSTR.wav"));
+        assert_eq!(ids(&search(&catalog, "gallop", &SearchFilters { categories: Some(strs(&["movement"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()), ["6"]);
+    }
+
+    #[test]
+    fn test_get_similar() {
+        let catalog = seeded_catalog();
+        let similar = |id, limit| -> Vec<String> {
+            catalog.get_similar(id, limit).unwrap().into_iter().map(|s| s.id).collect()
+        };
+
+        // "2" shares the category and its tag; the vocal archers share only the unit;
+        // the UI click shares nothing
+        assert_eq!(similar("1", 10), ["2", "5", "3"]);
+        assert_eq!(similar("1", 1), ["2"]);
+        assert_eq!(similar("4", 10), Vec::<String>::new());
+        assert!(similar("missing", 10).is_empty());
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let catalog = Catalog::open_in_memory().unwrap();
+        let mut sound = test_sound("1", "vcl.grunt", "vocal", Some("Archer"));
+        sound.display_name = "Grunt, \"loud\"".to_string();
+        sound.tags = vec!["vocal".to_string(), "archer".to_string()];
+        catalog.insert_sound(&sound).unwrap();
+
+        let csv = catalog.export_csv().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,event_name,display_name,category,unit_type,subcategory,duration,tags,is_favorite");
+        assert_eq!(lines[1], "1,vcl.grunt,\"Grunt, \"\"loud\"\"\",vocal,Archer,,1.5,vocal;archer,false");
+
+        let json: Vec<Sound> = serde_json::from_str(&catalog.export_json().unwrap()).unwrap();
+        assert_eq!(json[0].display_name, sound.display_name);
+    }
+
+    #[test]
+    fn test_get_stats() {
+        let catalog = seeded_catalog();
+        catalog.toggle_favorite("1").unwrap();
+
+        let stats = catalog.get_stats().unwrap();
+        assert_eq!(stats.sound_count, 5);
+        assert_eq!(stats.favorite_count, 1);
+        assert_eq!(stats.category_count, 3);
+        assert_eq!(stats.unit_type_count, 2);
+        assert_eq!(stats.total_sound_duration_secs, 7.5);
+        assert_eq!(stats.music_track_count, 0);
+        assert_eq!(stats.total_music_duration_secs, 0.0);
+    }
+
+    #[test]
+    fn test_sound_gain() {
+        let catalog = seeded_catalog();
+
+        assert_eq!(catalog.get_playback_gain("1").unwrap(), 0.0);
+        catalog.set_sound_gain("1", -6.0).unwrap();
+        assert_eq!(catalog.get_playback_gain("1").unwrap(), -6.0);
+        assert_eq!(search(&catalog, "", &SearchFilters { categories: Some(strs(&["combat"])), unit_types: Some(strs(&["Archer"])), ..Default::default() }, SortOrder::Relevance, false).unwrap()[0].gain_db, -6.0);
+
+        assert_eq!(catalog.get_playback_gain("missing").unwrap(), 0.0);
+
+        // Category gain stacks on top of the sound's own trim
+        catalog.set_category_gain("combat", -3.0).unwrap();
+        catalog.set_category_gain("combat", -4.0).unwrap();
+        assert_eq!(catalog.get_playback_gain("1").unwrap(), -10.0);
+        assert_eq!(catalog.get_playback_gain("3").unwrap(), 0.0);
+        assert_eq!(catalog.get_playback_gain("missing").unwrap(), 0.0);
+        assert_eq!(catalog.get_category_gains().unwrap(), HashMap::from([("combat".to_string(), -4.0)]));
+        assert!(catalog.set_sound_gain("missing", 3.0).is_err());
+    }
+
+    #[test]
+    fn test_playlist_ordering() {
+        let catalog = seeded_catalog();
+        let playlist = catalog.create_playlist("Battle").unwrap();
+
+        catalog.add_to_playlist(playlist.id, "1", None).unwrap();
+        catalog.add_to_playlist(playlist.id, "2", None).unwrap();
+        catalog.add_to_playlist(playlist.id, "3", Some(0)).unwrap();
+        let order = |c: &Catalog| -> Vec<String> {
+            c.get_playlist_sounds(playlist.id).unwrap().into_iter().map(|s| s.id).collect()
+        };
+        assert_eq!(order(&catalog), ["3", "1", "2"]);
+
+        // Re-adding moves the sound rather than duplicating it
+        catalog.add_to_playlist(playlist.id, "3", None).unwrap();
+        assert_eq!(order(&catalog), ["1", "2", "3"]);
+
+        catalog.remove_from_playlist(playlist.id, "1").unwrap();
+        catalog.add_to_playlist(playlist.id, "4", Some(1)).unwrap();
+        assert_eq!(order(&catalog), ["2", "4", "3"]);
+        assert_eq!(catalog.get_playlists().unwrap()[0].sound_count, 3);
+
+        assert!(catalog.add_to_playlist(999, "1", None).is_err());
+    }
+
+    #[test]
+    fn test_delete_playlist_cascades() {
+        let catalog = seeded_catalog();
+        let playlist = catalog.create_playlist("Temp").unwrap();
+        catalog.add_to_playlist(playlist.id, "1", None).unwrap();
+
+        catalog.delete_playlist(playlist.id).unwrap();
+        assert!(catalog.get_playlists().unwrap().is_empty());
+
+        let conn = catalog.conn().unwrap();
+        let items: i32 = conn
+            .query_row("SELECT COUNT(*) FROM playlist_items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(items, 0);
+    }
+
+    #[test]
+    fn test_search_music_tracks() {
+        let catalog = Catalog::open_in_memory().unwrap();
+        for (id, title) in [("1", "Battle Theme"), ("2", "Peaceful Harbor"), ("3", "Battle of Marathon")] {
+            catalog
+                .insert_music_track(&MusicTrack {
+                    id: id.to_string(),
+                    title: title.to_string(),
+                    file_path: format!("/tmp/{}.ogg", id),
+                    duration_secs: 0.0,
+                })
+                .unwrap();
+        }
+        let found = |query: &str| -> Vec<String> {
+            let mut ids: Vec<String> = catalog.search_music_tracks(query).unwrap().into_iter().map(|t| t.id).collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(catalog.get_audio_path("2").unwrap().as_deref(), Some("/tmp/2.ogg"));
+        assert_eq!(catalog.get_audio_path("missing").unwrap(), None);
+
+        assert_eq!(found("batt"), ["1", "3"]);
+        assert_eq!(found("battle mara"), ["3"]);
+        assert_eq!(found("harb"), ["2"]);
+        assert_eq!(found(""), ["1", "2", "3"]);
+
+        // Updates keep the index in sync
+        catalog.update_music_track_duration("2", 90.0).unwrap();
+        assert_eq!(found("peaceful"), ["2"]);
+    }
+
+    #[test]
+    fn test_is_favorite_migration() {
+        // Schema from before favorites were added (FTS was already present)
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE sounds (
+                id TEXT PRIMARY KEY,
+                event_name TEXT NOT NULL,
+                display_name TEXT NOT NULL,
+                category TEXT NOT NULL,
+                unit_type TEXT,
+                subcategory TEXT,
+                duration_ms INTEGER NOT NULL,
+                file_path TEXT NOT NULL,
+                tags TEXT,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE VIRTUAL TABLE sounds_fts USING fts5(
+                event_name, display_name, tags,
+                content='sounds',
+                content_rowid='rowid'
+            );
+            CREATE TRIGGER sounds_ai AFTER INSERT ON sounds BEGIN
+                INSERT INTO sounds_fts(rowid, event_name, display_name, tags)
+                VALUES (new.rowid, new.event_name, new.display_name, new.tags);
+            END;
+            INSERT INTO sounds (id, event_name, display_name, category, subcategory, duration_ms, file_path)
+            VALUES ('old', 'cmbt.old', 'Old', 'combat', 'old', 1000, '/tmp/old.ogg');",
+        )
+        .unwrap();
+
+        let catalog = Catalog::from_connection(conn).unwrap();
+
+        let sounds = search(&catalog, "", &SearchFilters::default(), SortOrder::Relevance, false).unwrap();
+        assert_eq!(ids(&sounds), ["old"]);
+        assert!(!sounds[0].is_favorite);
+        assert!(catalog.toggle_favorite("old").unwrap().is_favorite);
+    }
+
+    #[test]
+    fn test_connection_pool_allows_concurrent_use() {
+        let dir = std::env::temp_dir().join(format!("kithara-test-pool-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let catalog = Catalog::open(dir.join("catalog.db")).unwrap();
+        catalog.insert_sound(&test_sound("1", "ui.click", "ui", None)).unwrap();
+
+        // A checked-out connection doesn't block other calls, which open their own
+        let held = catalog.conn().unwrap();
+        let mode: String = held.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        assert_eq!(catalog.count_sounds().unwrap(), 1);
+        drop(held);
+
+        drop(catalog);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_optimize_shrinks_database_with_other_connections_open() {
+        let dir = std::env::temp_dir().join(format!("kithara-test-optimize-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("catalog.db");
+        let catalog = Catalog::open(db_path.clone()).unwrap();
+        let sounds: Vec<Sound> = (0..2000)
+            .map(|i| test_sound(&i.to_string(), &format!("vo.line_{}", i), "vocal", None))
+            .collect();
+        catalog.insert_sounds_batch(&sounds).unwrap();
+        catalog.clear_all().unwrap();
+
+        // The app's own pool stays open while the optimize runs
+        let held = catalog.conn().unwrap();
+        let size_before = database_size(&db_path);
+        Catalog::open(db_path.clone()).unwrap().optimize().unwrap();
+        assert!(database_size(&db_path) < size_before, "{} bytes before", size_before);
+
+        drop(held);
+        drop(catalog);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_tag_edits_are_not_lost() {
+        let dir = std::env::temp_dir().join(format!("kithara-test-tags-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let catalog = Catalog::open(dir.join("catalog.db")).unwrap();
+        catalog.insert_sound(&test_sound("1", "ui.click", "ui", None)).unwrap();
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let catalog = &catalog;
+                scope.spawn(move || catalog.add_tag("1", &format!("tag{}", i)).unwrap());
+            }
+        });
+        let tags = catalog.get_sounds_by_ids(&["1".to_string()]).unwrap().remove(0).tags;
+        assert_eq!((0..8).filter(|i| tags.contains(&format!("tag{}", i))).count(), 8, "{:?}", tags);
+
+        drop(catalog);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = std::env::temp_dir().join(format!("kithara-test-backup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let backup_path = dir.join("backup.db");
+
+        let catalog = seeded_catalog();
+        catalog.toggle_favorite("1").unwrap();
+        catalog.backup_to(&backup_path).unwrap();
+
+        catalog.clear_all().unwrap();
+        assert_eq!(catalog.count_sounds().unwrap(), 0);
+
+        catalog.restore_from(&backup_path).unwrap();
+        assert_eq!(catalog.count_sounds().unwrap(), 5);
+        assert_eq!(catalog.count_favorites().unwrap(), 1);
+        // FTS index comes back with the data
+        assert_eq!(ids(&search(&catalog, "attack", &SearchFilters::default(), SortOrder::Relevance, false).unwrap()), ["1", "2"]);
+
+        // A database without a sounds table is refused and leaves the catalog intact
+        let other_path = dir.join("other.db");
+        Connection::open(&other_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE metadata (key TEXT PRIMARY KEY, value TEXT NOT NULL);")
+            .unwrap();
+        assert!(catalog.restore_from(&other_path).unwrap_err().to_string().contains("sounds"));
+
+        std::fs::write(&other_path, b"not a database").unwrap();
+        assert!(catalog.restore_from(&other_path).is_err());
+        assert_eq!(catalog.count_sounds().unwrap(), 5);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_optimize_keeps_search_working() {
+        let catalog = seeded_catalog();
+        catalog.delete_sound("2").unwrap();
+        catalog.optimize().unwrap();
+
+        assert_eq!(ids(&search(&catalog, "attack", &SearchFilters::default(), SortOrder::Relevance, false).unwrap()), ["1"]);
+    }
+
+    #[test]
+    fn test_fuzzy_search_fallback() {
+        let catalog = seeded_catalog();
+        let search = |query, fuzzy| ids(&search(&catalog, query, &SearchFilters::default(), SortOrder::Relevance, fuzzy).unwrap()).join(",");
+
+        // Typos find nothing by prefix, but fuzzy matching recovers them
+        assert_eq!(search("atack", false), "");
+        assert_eq!(search("atack", true), "1,2");
+        assert_eq!(search("arhcer atack", true), "1");
+        // Exact matches never go through the fuzzy pass
+        assert_eq!(search("attack", true), "1,2");
+        assert_eq!(search("xylophone", true), "");
+
+        assert_eq!(edit_distance("slinger", "slingr"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_output_dir() {
+        let catalog = seeded_catalog();
+        assert_eq!(catalog.get_saved_output_dir().unwrap(), None);
+
+        catalog.save_output_dir(Some("/music/old-world")).unwrap();
+        assert_eq!(catalog.get_saved_output_dir().unwrap().as_deref(), Some("/music/old-world"));
+        catalog.save_output_dir(None).unwrap();
+        assert_eq!(catalog.get_saved_output_dir().unwrap(), None);
+    }
+
+    #[test]
+    fn test_last_session() {
+        let catalog = seeded_catalog();
+        assert_eq!(catalog.get_last_session().unwrap(), None);
+
+        let session = LastSession { sound_id: "3".into(), position_secs: 12.5 };
+        catalog.save_last_session(&session).unwrap();
+        assert_eq!(catalog.get_last_session().unwrap(), Some(session));
+    }
+
+    #[test]
+    fn test_search_highlighting() {
+        let catalog = seeded_catalog();
+        let markers = HighlightMarkers { open: "[".into(), close: "]".into() };
+        let search = |query, markers| {
+            catalog
+                .search_sounds(query, &SearchFilters::default(), SortOrder::Relevance, true, markers)
+                .unwrap()
+                .into_iter()
+                .map(|result| result.match_snippet.unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(search("archer att", Some(&markers)), ["cmbt [attack] [archer]"]);
+        // A dotted name is searched as a phrase, so the whole span is marked
+        let default_markers = HighlightMarkers::default();
+        assert_eq!(search("cmbt.attack.war", Some(&default_markers)), ["<mark>cmbt attack warrior</mark>"]);
+        // Tag-only matches, fuzzy matches and empty queries have nothing to highlight
+        assert_eq!(search("combat", Some(&markers)), ["", ""]);
+        assert_eq!(search("clik", Some(&markers)), [""]);
+        assert_eq!(search("", Some(&markers)).len(), 5);
+        assert!(search("", Some(&markers)).iter().all(String::is_empty));
+        assert_eq!(search("attack", None), ["", ""]);
+    }
+
+    #[test]
+    fn test_fts_query_syntax() {
+        assert_eq!(build_fts_query("  archer att  ").as_deref(), Some("\"archer\" \"att\"*"));
+        assert_eq!(build_fts_query("\"warrior attack\"").as_deref(), Some("\"warrior attack\""));
+        assert_eq!(build_fts_query("horse OR camel").as_deref(), Some("\"horse\" OR \"camel\"*"));
+        assert_eq!(build_fts_query("OR horse NOT").as_deref(), Some("\"OR\" \"horse\" \"NOT\"*"));
+        assert_eq!(build_fts_query("cmbt.attack \"arch").as_deref(), Some("\"cmbt.attack\" \"arch\"*"));
+        assert_eq!(build_fts_query("\""), None);
+        assert_eq!(build_fts_query("- :"), None);
+
+        let catalog = seeded_catalog();
+        let search = |query| ids(&search(&catalog, query, &SearchFilters::default(), SortOrder::Relevance, false).unwrap()).join(",");
+        assert_eq!(search("\"attack warrior\""), "2");
+        assert_eq!(search("\"warrior attack\""), "");
+        assert_eq!(search("warrior OR grunt"), "2,3,5");
+        assert_eq!(search("archer NOT female"), "1,3");
+        assert_eq!(search("horse OR camel"), "");
+        assert_eq!(search("\""), "1,2,3,4,5");
+        assert_eq!(search("attack \"arch"), "1");
+    }
+
+    #[test]
+    fn test_language_filter() {
+        let catalog = seeded_catalog();
+        for (id, language) in [("6", "fr"), ("7", "de")] {
+            let mut sound = test_sound(id, "vcl.grunt.warrior", "vocal", Some("Warrior"));
+            sound.language = Some(language.to_string());
+            catalog.insert_sound(&sound).unwrap();
+        }
+
+        assert_eq!(catalog.get_languages().unwrap(), ["de", "fr"]);
+        let filters = SearchFilters { language: Some("fr".into()), ..Default::default() };
+        assert_eq!(ids(&search(&catalog, "grunt", &filters, SortOrder::Relevance, false).unwrap()), ["6"]);
+    }
+}
@@ -1,32 +1,103 @@
-use crate::catalog::Catalog;
-use crate::extractor::{self, ExtractionManager};
-use crate::models::{Category, ExtractionState, ExtractionStatus, MusicTrack, PlaybackStatus, Sound, UnitType};
-use crate::player::PlayerState;
-use std::path::PathBuf;
+use crate::catalog::{self, Catalog};
+use crate::error::KitharaError;
+use crate::export;
+use crate::extractor::{self, metadata, ExtractionManager};
+use crate::maintenance;
+use crate::models::{
+    CatalogFormat, CatalogStats, CatalogVerification, Category, DependencyStatus, ExportSummary, ExtractionOptions,
+    ExtractionState, ExtractionStatus, FavoriteToggle, GamePathReport, HighlightMarkers, LastSession, MusicTrack,
+    OptimizeResult, PlaybackStatus, Playlist, RepeatMode, SearchFilters, SearchResult, Sound, SortOrder, Subcategory,
+    UnitType, ZipCompression,
+};
+use crate::player::{self, PlayerState};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_shell::ShellExt;
 
-/// Search for sounds matching the query and filters
+/// Similar sounds returned when the caller doesn't ask for a number
+const DEFAULT_SIMILAR_LIMIT: usize = 20;
+
+/// Search for sounds matching the query and filters.
+/// Multiple categories or unit types match any of them; pass one to filter by a single value.
+/// With `fuzzy`, a query with no matches falls back to typo-tolerant matching.
+/// Results matched by a text query carry a `matchSnippet` wrapped in `highlight` markers (`<mark>` by default).
 #[tauri::command]
 pub async fn search_sounds(
     query: String,
-    category: Option<String>,
-    unit_type: Option<String>,
+    filters: Option<SearchFilters>,
+    sort: Option<SortOrder>,
+    fuzzy: Option<bool>,
+    highlight: Option<HighlightMarkers>,
     catalog: State<'_, Catalog>,
-) -> Result<Vec<Sound>, String> {
-    catalog.search_sounds(&query, category.as_deref(), unit_type.as_deref())
+) -> Result<Vec<SearchResult>, KitharaError> {
+    let mut results = catalog.search_sounds(
+        &query,
+        &filters.unwrap_or_default(),
+        sort.unwrap_or_default(),
+        fuzzy.unwrap_or(false),
+        Some(&highlight.unwrap_or_default()),
+    )?;
+    for result in &mut results {
+        catalog::mark_availability(std::slice::from_mut(&mut result.sound));
+    }
+    Ok(results)
 }
 
 /// Get all available categories
 #[tauri::command]
-pub async fn get_categories(catalog: State<'_, Catalog>) -> Result<Vec<Category>, String> {
+pub async fn get_categories(catalog: State<'_, Catalog>) -> Result<Vec<Category>, KitharaError> {
     catalog.get_categories()
 }
 
-/// Get all available unit types
+/// Get the subcategories within a category, for drilling down from a category to its sounds
+#[tauri::command]
+pub async fn get_subcategories(
+    category: String,
+    catalog: State<'_, Catalog>,
+) -> Result<Vec<Subcategory>, KitharaError> {
+    catalog.get_subcategories(&category)
+}
+
+/// Get all available unit types.
+/// With `include_empty`, known units without any extracted sounds are included with a count of 0.
+#[tauri::command]
+pub async fn get_unit_types(
+    include_empty: Option<bool>,
+    catalog: State<'_, Catalog>,
+) -> Result<Vec<UnitType>, KitharaError> {
+    catalog.get_unit_types(include_empty.unwrap_or(false))
+}
+
+/// Get the sounds extracted from a soundbank, by its file name (e.g. "Audio_Animation.bnk")
+#[tauri::command]
+pub async fn get_sounds_by_bank(
+    bank_name: String,
+    catalog: State<'_, Catalog>,
+) -> Result<Vec<Sound>, KitharaError> {
+    let mut sounds = catalog.get_sounds_by_bank(&bank_name)?;
+    catalog::mark_availability(&mut sounds);
+    Ok(sounds)
+}
+
+/// Get the sounds most like a given one (shared unit, category, and tags), best match first
 #[tauri::command]
-pub async fn get_unit_types(catalog: State<'_, Catalog>) -> Result<Vec<UnitType>, String> {
-    catalog.get_unit_types()
+pub async fn get_similar_sounds(
+    sound_id: String,
+    limit: Option<usize>,
+    catalog: State<'_, Catalog>,
+) -> Result<Vec<Sound>, KitharaError> {
+    let mut sounds = catalog.get_similar(&sound_id, limit.unwrap_or(DEFAULT_SIMILAR_LIMIT))?;
+    catalog::mark_availability(&mut sounds);
+    Ok(sounds)
+}
+
+/// Get the languages of localized sounds, for the language filter
+#[tauri::command]
+pub async fn get_languages(catalog: State<'_, Catalog>) -> Result<Vec<String>, KitharaError> {
+    catalog.get_languages()
 }
 
 /// Toggle favorite status for a sound
@@ -34,20 +105,293 @@ pub async fn get_unit_types(catalog: State<'_, Catalog>) -> Result<Vec<UnitType>
 pub async fn toggle_favorite(
     sound_id: String,
     catalog: State<'_, Catalog>,
-) -> Result<bool, String> {
+) -> Result<FavoriteToggle, KitharaError> {
     catalog.toggle_favorite(&sound_id)
 }
 
+/// Delete a sound from the catalog and remove its audio file.
+/// The catalog entry is deleted even if the file can't be removed.
+#[tauri::command]
+pub async fn delete_sound(sound_id: String, catalog: State<'_, Catalog>) -> Result<(), KitharaError> {
+    let file_path = catalog
+        .delete_sound(&sound_id)?
+        .ok_or_else(|| KitharaError::NotFound(format!("Sound not found: {}", sound_id)))?;
+
+    if let Err(e) = std::fs::remove_file(&file_path) {
+        eprintln!("Failed to remove {}: {}", file_path, e);
+    }
+    Ok(())
+}
+
+/// Open the folder holding a sound's file in the OS file manager, with the file
+/// selected where the platform supports it
+#[tauri::command]
+pub async fn reveal_in_explorer(
+    app: AppHandle,
+    sound_id: String,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    let sound = catalog
+        .get_sounds_by_ids(std::slice::from_ref(&sound_id))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| KitharaError::NotFound(format!("Sound not found: {}", sound_id)))?;
+
+    let path = PathBuf::from(&sound.file_path);
+    if !path.exists() {
+        return Err(KitharaError::NotFound(format!(
+            "Audio file no longer exists: {}. Re-extract to restore it.",
+            sound.file_path
+        )));
+    }
+
+    let (program, args) = reveal_command(&path);
+    // Explorer exits non-zero even when it opens, so only a failure to launch counts
+    app.shell()
+        .command(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| KitharaError::Io(format!("Failed to open file manager: {}", e)))?;
+    Ok(())
+}
+
+/// Explorer and Finder can select the file; on Linux there's no common way to,
+/// so the default file manager just opens the folder
+fn reveal_command(path: &Path) -> (&'static str, Vec<String>) {
+    let path_str = path.to_string_lossy().to_string();
+    if cfg!(target_os = "windows") {
+        ("explorer", vec![format!("/select,{}", path_str)])
+    } else if cfg!(target_os = "macos") {
+        ("open", vec!["-R".to_string(), path_str])
+    } else {
+        let folder = path.parent().unwrap_or(path);
+        ("xdg-open", vec![folder.to_string_lossy().to_string()])
+    }
+}
+
+/// Add a user tag to a sound. Returns the sound's updated tags.
+#[tauri::command]
+pub async fn add_sound_tag(
+    sound_id: String,
+    tag: String,
+    catalog: State<'_, Catalog>,
+) -> Result<Vec<String>, KitharaError> {
+    catalog.add_tag(&sound_id, &tag)
+}
+
+/// Remove a tag from a sound. Returns the sound's updated tags.
+#[tauri::command]
+pub async fn remove_sound_tag(
+    sound_id: String,
+    tag: String,
+    catalog: State<'_, Catalog>,
+) -> Result<Vec<String>, KitharaError> {
+    catalog.remove_tag(&sound_id, &tag)
+}
+
+/// Set the per-sound gain trim in dB (clamped to ±24 dB), applied on next play
+#[tauri::command]
+pub async fn set_sound_gain(
+    sound_id: String,
+    gain_db: f64,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    if !gain_db.is_finite() {
+        return Err(KitharaError::InvalidInput(format!("Invalid gain: {}", gain_db)));
+    }
+    catalog.set_sound_gain(&sound_id, gain_db.clamp(-player::MAX_GAIN_DB, player::MAX_GAIN_DB))
+}
+
+/// Set the default gain in dB (clamped to ±24 dB) for every sound in a category.
+/// It combines with each sound's own gain trim.
+#[tauri::command]
+pub async fn set_category_gain(
+    category: String,
+    gain_db: f64,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    if !gain_db.is_finite() {
+        return Err(KitharaError::InvalidInput(format!("Invalid gain: {}", gain_db)));
+    }
+    catalog.set_category_gain(&category, gain_db.clamp(-player::MAX_GAIN_DB, player::MAX_GAIN_DB))
+}
+
+/// Get the default gain in dB of each category that has one set
+#[tauri::command]
+pub async fn get_category_gains(catalog: State<'_, Catalog>) -> Result<HashMap<String, f64>, KitharaError> {
+    catalog.get_category_gains()
+}
+
+/// Get aggregate catalog statistics in one call
+#[tauri::command]
+pub async fn get_catalog_stats(catalog: State<'_, Catalog>) -> Result<CatalogStats, KitharaError> {
+    catalog.get_stats()
+}
+
+/// Export the whole sound catalog as JSON or CSV to `dest_path`
+#[tauri::command]
+pub async fn export_catalog(
+    format: CatalogFormat,
+    dest_path: String,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    let contents = match format {
+        CatalogFormat::Json => catalog.export_json()?,
+        CatalogFormat::Csv => catalog.export_csv()?,
+    };
+    std::fs::write(&dest_path, contents)
+        .map_err(|e| KitharaError::Io(format!("Failed to write {}: {}", dest_path, e)))
+}
+
+/// Write a JSON soundboard config for the given sounds, with an empty `hotkey`
+/// per sound for external soundboard software. With `export_dir`, each entry also
+/// gets a path relative to it. Unknown IDs are skipped; returns the number written.
+#[tauri::command]
+pub async fn export_soundboard(
+    ids: Vec<String>,
+    dest: String,
+    export_dir: Option<String>,
+    catalog: State<'_, Catalog>,
+) -> Result<usize, KitharaError> {
+    if ids.is_empty() {
+        return Err(KitharaError::InvalidInput("No sounds to export".into()));
+    }
+
+    let sounds = catalog.get_sounds_by_ids(&ids)?;
+    export::export_soundboard_config(&sounds, Path::new(&dest), export_dir.as_deref().map(Path::new))?;
+    Ok(sounds.len())
+}
+
+/// Create an empty playlist
+#[tauri::command]
+pub async fn create_playlist(name: String, catalog: State<'_, Catalog>) -> Result<Playlist, KitharaError> {
+    catalog.create_playlist(&name)
+}
+
+/// Delete a playlist and its items
+#[tauri::command]
+pub async fn delete_playlist(playlist_id: i64, catalog: State<'_, Catalog>) -> Result<(), KitharaError> {
+    catalog.delete_playlist(playlist_id)
+}
+
+/// Get all playlists
+#[tauri::command]
+pub async fn get_playlists(catalog: State<'_, Catalog>) -> Result<Vec<Playlist>, KitharaError> {
+    catalog.get_playlists()
+}
+
+/// Add a sound to a playlist at the given position (appends when omitted)
+#[tauri::command]
+pub async fn add_to_playlist(
+    playlist_id: i64,
+    sound_id: String,
+    position: Option<u32>,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    catalog.add_to_playlist(playlist_id, &sound_id, position)
+}
+
+/// Remove a sound from a playlist
+#[tauri::command]
+pub async fn remove_from_playlist(
+    playlist_id: i64,
+    sound_id: String,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    catalog.remove_from_playlist(playlist_id, &sound_id)
+}
+
+/// Get the sounds in a playlist, in order
+#[tauri::command]
+pub async fn get_playlist_sounds(
+    playlist_id: i64,
+    catalog: State<'_, Catalog>,
+) -> Result<Vec<Sound>, KitharaError> {
+    let mut sounds = catalog.get_playlist_sounds(playlist_id)?;
+    catalog::mark_availability(&mut sounds);
+    Ok(sounds)
+}
+
+/// Favorite or unfavorite many sounds at once. Returns the number updated.
+#[tauri::command]
+pub async fn set_favorites_bulk(
+    sound_ids: Vec<String>,
+    favorite: bool,
+    catalog: State<'_, Catalog>,
+) -> Result<usize, KitharaError> {
+    catalog.set_favorites(&sound_ids, favorite)
+}
+
 /// Get the count of favorited sounds
 #[tauri::command]
-pub async fn get_favorites_count(catalog: State<'_, Catalog>) -> Result<u64, String> {
+pub async fn get_favorites_count(catalog: State<'_, Catalog>) -> Result<u64, KitharaError> {
     catalog.count_favorites()
 }
 
 /// Get all favorited sounds
 #[tauri::command]
-pub async fn get_favorites(catalog: State<'_, Catalog>) -> Result<Vec<Sound>, String> {
-    catalog.get_favorites()
+pub async fn get_favorites(catalog: State<'_, Catalog>) -> Result<Vec<Sound>, KitharaError> {
+    let mut sounds = catalog.get_favorites()?;
+    catalog::mark_availability(&mut sounds);
+    Ok(sounds)
+}
+
+/// Export all favorited sounds as a ZIP archive of OGG files.
+/// Emits `export-progress` events as files are added.
+#[tauri::command]
+pub async fn export_favorites_zip(
+    app: AppHandle,
+    dest_path: String,
+    compression: Option<ZipCompression>,
+    catalog: State<'_, Catalog>,
+) -> Result<ExportSummary, KitharaError> {
+    let sounds = catalog.get_favorites()?;
+    if sounds.is_empty() {
+        return Err(KitharaError::InvalidInput("No favorites to export".into()));
+    }
+
+    export::export_sounds_zip(
+        &sounds,
+        &[],
+        Path::new(&dest_path),
+        compression.unwrap_or_default(),
+        |progress| {
+            let _ = app.emit("export-progress", progress);
+        },
+    )
+}
+
+/// Export the given sounds (e.g. a playlist or selection) to a ZIP archive
+/// with a manifest, emitting `export-progress` events as files are added.
+/// Unknown IDs and missing files are skipped and listed in the manifest.
+#[tauri::command]
+pub async fn export_sounds(
+    app: AppHandle,
+    ids: Vec<String>,
+    dest_zip: String,
+    compression: Option<ZipCompression>,
+    catalog: State<'_, Catalog>,
+) -> Result<ExportSummary, KitharaError> {
+    if ids.is_empty() {
+        return Err(KitharaError::InvalidInput("No sounds to export".into()));
+    }
+
+    let sounds = catalog.get_sounds_by_ids(&ids)?;
+    let missing_ids: Vec<String> = ids
+        .into_iter()
+        .filter(|id| !sounds.iter().any(|s| &s.id == id))
+        .collect();
+
+    export::export_sounds_zip(
+        &sounds,
+        &missing_ids,
+        Path::new(&dest_zip),
+        compression.unwrap_or_default(),
+        |progress| {
+            let _ = app.emit("export-progress", progress);
+        },
+    )
 }
 
 /// Play a sound by its ID and file path
@@ -56,69 +400,279 @@ pub async fn play_sound(
     id: String,
     file_path: String,
     player: State<'_, PlayerState>,
-) -> Result<(), String> {
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
     let path = PathBuf::from(&file_path);
 
     // Validate file exists
     if !path.exists() {
-        return Err(format!("Audio file not found: {}", file_path));
+        return Err(KitharaError::NotFound(format!("Audio file not found: {}", file_path)));
     }
 
-    player.play(id, path)
+    let gain_db = catalog.get_playback_gain(&id)?.clamp(-player::MAX_GAIN_DB, player::MAX_GAIN_DB);
+    // Loops are made to repeat seamlessly, so keep them going until stopped
+    let looping = catalog.is_loop_sound(&id)?;
+    player.play(id, path, gain_db, looping)
+}
+
+/// How often an audition's temp file is checked for being done with
+const AUDITION_CLEANUP_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Convert a single WEM from the game folder to a temp OGG and play it, without
+/// extracting anything. The temp file is removed once playback of it stops.
+#[tauri::command]
+pub async fn audition_wem(
+    app: AppHandle,
+    wem_path: String,
+    player: State<'_, PlayerState>,
+) -> Result<(), KitharaError> {
+    let path = PathBuf::from(&wem_path);
+    if !path.exists() {
+        return Err(KitharaError::NotFound(format!("WEM file not found: {}", wem_path)));
+    }
+
+    let (id, ogg_path) = extractor::convert_for_audition(&app, &path).await?;
+    if let Err(e) = player.play(id.clone(), ogg_path.clone(), 0.0, false) {
+        let _ = std::fs::remove_file(&ogg_path);
+        return Err(e);
+    }
+
+    let player = Arc::clone(&player);
+    std::thread::spawn(move || {
+        // A finished track keeps its ID, so also check it is still playing
+        let is_auditioning = || {
+            player.get_status().is_ok_and(|status| {
+                status.current_sound_id.as_deref() == Some(id.as_str()) && (status.is_playing || status.is_paused)
+            })
+        };
+        while is_auditioning() {
+            std::thread::sleep(AUDITION_CLEANUP_INTERVAL);
+        }
+        let _ = std::fs::remove_file(&ogg_path);
+    });
+    Ok(())
+}
+
+/// Decode a sound ahead of time so playing it next starts without delay
+#[tauri::command]
+pub async fn preload_sound(file_path: String, player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    player.preload(PathBuf::from(file_path))
+}
+
+/// Play a sound layered on top of anything already playing
+#[tauri::command]
+pub async fn play_sound_layered(
+    id: String,
+    file_path: String,
+    player: State<'_, PlayerState>,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    let path = PathBuf::from(&file_path);
+
+    if !path.exists() {
+        return Err(KitharaError::NotFound(format!("Audio file not found: {}", file_path)));
+    }
+
+    let gain_db = catalog.get_playback_gain(&id)?.clamp(-player::MAX_GAIN_DB, player::MAX_GAIN_DB);
+    player.play_layered(id, path, gain_db)
+}
+
+/// Add a sound to the playback queue
+#[tauri::command]
+pub async fn enqueue_sound(
+    id: String,
+    file_path: String,
+    player: State<'_, PlayerState>,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    let path = PathBuf::from(&file_path);
+
+    if !path.exists() {
+        return Err(KitharaError::NotFound(format!("Audio file not found: {}", file_path)));
+    }
+
+    let gain_db = catalog.get_playback_gain(&id)?.clamp(-player::MAX_GAIN_DB, player::MAX_GAIN_DB);
+    player.enqueue(id, path, gain_db)
+}
+
+/// Remove all sounds from the playback queue
+#[tauri::command]
+pub async fn clear_queue(player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    player.clear_queue()
+}
+
+/// Skip to the next queued sound (stops if the queue is empty)
+#[tauri::command]
+pub async fn skip_next(player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    player.skip_next()
+}
+
+/// Set the repeat mode (off, one, all)
+#[tauri::command]
+pub async fn set_repeat_mode(mode: RepeatMode, player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    player.set_repeat_mode(mode)
 }
 
 /// Stop the currently playing sound
 #[tauri::command]
-pub async fn stop_sound(player: State<'_, PlayerState>) -> Result<(), String> {
+pub async fn stop_sound(player: State<'_, PlayerState>, catalog: State<'_, Catalog>) -> Result<(), KitharaError> {
+    save_last_session(&player, &catalog);
     player.stop()
 }
 
-/// Pause the currently playing sound
+/// Stop the currently playing sound without fading out
 #[tauri::command]
-pub async fn pause_sound(player: State<'_, PlayerState>) -> Result<(), String> {
+pub async fn stop_sound_immediate(
+    player: State<'_, PlayerState>,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    save_last_session(&player, &catalog);
+    player.stop_immediate()
+}
+
+/// Stop the main track and every layered sound
+#[tauri::command]
+pub async fn stop_all_sounds(player: State<'_, PlayerState>, catalog: State<'_, Catalog>) -> Result<(), KitharaError> {
+    save_last_session(&player, &catalog);
+    player.stop_all()
+}
+
+/// Returns the sound and position that were playing when playback last stopped
+/// or the app closed, so the UI can offer to resume
+#[tauri::command]
+pub async fn get_last_session(catalog: State<'_, Catalog>) -> Result<Option<LastSession>, KitharaError> {
+    catalog.get_last_session()
+}
+
+/// Saves the playing or paused sound as the last session. Auditioned WEMs are
+/// temporary files, so they're never saved. Failures are only logged, since
+/// this runs on the way to stopping or quitting.
+pub fn save_last_session(player: &PlayerState, catalog: &Catalog) {
+    let result = player.get_status().and_then(|status| match status.current_sound_id {
+        Some(sound_id)
+            if (status.is_playing || status.is_paused) && !sound_id.starts_with(extractor::AUDITION_ID_PREFIX) =>
+        {
+            catalog.save_last_session(&LastSession {
+                sound_id,
+                position_secs: status.position_secs,
+            })
+        }
+        _ => Ok(()),
+    });
+    if let Err(e) = result {
+        eprintln!("Warning: Failed to save last session: {}", e);
+    }
+}
+
+/// Pause the currently playing sound (no-op if nothing is playing)
+#[tauri::command]
+pub async fn pause_sound(player: State<'_, PlayerState>) -> Result<(), KitharaError> {
     player.pause()
 }
 
-/// Resume playback after pause
+/// Resume playback after pause (no-op if nothing is paused)
 #[tauri::command]
-pub async fn resume_sound(player: State<'_, PlayerState>) -> Result<(), String> {
+pub async fn resume_sound(player: State<'_, PlayerState>) -> Result<(), KitharaError> {
     player.resume()
 }
 
-/// Seek to a position in seconds
+/// Seek to a position in seconds.
+/// Negative positions seek to the start. The position is clamped to the track
+/// duration when known; Vorbis files may report no duration, in which case the
+/// clamp is skipped. Does nothing if no sound is loaded.
 #[tauri::command]
-pub async fn seek_sound(position_secs: f64, player: State<'_, PlayerState>) -> Result<(), String> {
-    player.seek(position_secs)
+pub async fn seek_sound(position_secs: f64, player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    player.seek(position_secs.max(0.0))
 }
 
-/// Set the playback volume (0.0 to 1.0)
+/// Seek forwards or backwards relative to the current position
 #[tauri::command]
-pub async fn set_volume(volume: f32, player: State<'_, PlayerState>) -> Result<(), String> {
-    player.set_volume(volume)
+pub async fn seek_relative(delta_secs: f64, player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    player.seek_relative(delta_secs)
 }
 
-/// Get the current playback status
+/// Set the playback speed (0.5 to 2.0). Pitch changes along with speed.
+#[tauri::command]
+pub async fn set_playback_speed(speed: f32, player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    if !(player::MIN_SPEED..=player::MAX_SPEED).contains(&speed) {
+        return Err(KitharaError::InvalidInput(format!(
+            "Playback speed must be between {} and {}",
+            player::MIN_SPEED,
+            player::MAX_SPEED
+        )));
+    }
+    player.set_speed(speed)
+}
+
+/// Set the playback volume (0.0 to 1.0) and remember it for the next launch
 #[tauri::command]
-pub async fn get_playback_status(player: State<'_, PlayerState>) -> Result<PlaybackStatus, String> {
-    let status = player.get_status()?;
-
-    Ok(PlaybackStatus {
-        is_playing: status.is_playing,
-        is_paused: status.is_paused,
-        current_sound_id: status.current_sound_id,
-        position_secs: status.position_secs,
-        duration_secs: status.duration_secs,
-        volume: status.volume,
-        sample_rate: status.sample_rate,
-        bitrate_kbps: status.bitrate_kbps,
+pub async fn set_volume(
+    volume: f32,
+    player: State<'_, PlayerState>,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    let volume = volume.clamp(0.0, 1.0);
+    player.set_volume(volume)?;
+    catalog.save_volume(volume)
+}
+
+/// Mute or unmute playback without losing the volume level. Returns true if now muted.
+#[tauri::command]
+pub async fn toggle_mute(player: State<'_, PlayerState>) -> Result<bool, KitharaError> {
+    player.toggle_mute()
+}
+
+/// Stop playback after the given number of minutes, replacing any running timer
+#[tauri::command]
+pub async fn start_sleep_timer(
+    app: AppHandle,
+    minutes: u32,
+    player: State<'_, PlayerState>,
+) -> Result<(), KitharaError> {
+    if minutes == 0 {
+        return Err(KitharaError::InvalidInput("Sleep timer must be at least 1 minute".into()));
+    }
+    // Stopping from the timer should remember the session just like stop_sound does
+    player.start_sleep_timer(Duration::from_secs(u64::from(minutes) * 60), move || {
+        save_last_session(&app.state::<PlayerState>(), &app.state::<Catalog>());
     })
 }
 
+/// Cancel the running sleep timer
+#[tauri::command]
+pub async fn cancel_sleep_timer(player: State<'_, PlayerState>) -> Result<(), KitharaError> {
+    player.cancel_sleep_timer()
+}
+
+/// List the names of the available audio output devices
+#[tauri::command]
+pub async fn list_output_devices() -> Result<Vec<String>, KitharaError> {
+    player::list_output_devices()
+}
+
+/// Switch audio output to the named device (or the system default when `None`)
+/// and remember the choice for the next launch
+#[tauri::command]
+pub async fn select_output_device(
+    name: Option<String>,
+    player: State<'_, PlayerState>,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    player.set_output_device(name.clone())?;
+    catalog.save_output_device(name.as_deref())
+}
+
+/// Get the current playback status
+#[tauri::command]
+pub async fn get_playback_status(player: State<'_, PlayerState>) -> Result<PlaybackStatus, KitharaError> {
+    Ok(player.get_status()?.into())
+}
+
 /// Get the current extraction status
 #[tauri::command]
 pub async fn get_extraction_status(
     manager: State<'_, Arc<ExtractionManager>>,
-) -> Result<ExtractionStatus, String> {
+) -> Result<ExtractionStatus, KitharaError> {
     Ok(manager.get_status())
 }
 
@@ -127,28 +681,20 @@ pub async fn get_extraction_status(
 pub async fn start_extraction(
     app: AppHandle,
     game_path: String,
-    include_music: bool,
+    options: ExtractionOptions,
     manager: State<'_, Arc<ExtractionManager>>,
-    _catalog: State<'_, Catalog>,
-) -> Result<(), String> {
-    // Validate game path
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
     let game_path = PathBuf::from(&game_path);
-    if !game_path.exists() {
-        return Err("Game path does not exist".into());
-    }
+    require_valid_game_path(&game_path)?;
+    catalog.save_last_game_path(&game_path.to_string_lossy())?;
 
-    // Check required files
-    let required_files = ["Events.xml"];
-    for file in required_files {
-        if !game_path.join(file).exists() {
-            return Err(format!("Required file not found: {}", file));
-        }
-    }
+    extractor::validate_options(&options)?;
 
     // Check if already in progress
     let status = manager.get_status();
     if matches!(status.state, ExtractionState::InProgress) {
-        return Err("Extraction already in progress".into());
+        return Err(KitharaError::InvalidInput("Extraction already in progress".into()));
     }
 
     // Reset state
@@ -160,7 +706,7 @@ pub async fn start_extraction(
     // Create a new catalog connection for the background task
     let db_path = crate::catalog::get_db_path()?;
     let catalog_for_task = Arc::new(
-        Catalog::open(db_path).map_err(|e| format!("Failed to open catalog: {}", e))?
+        Catalog::open(db_path).map_err(|e| KitharaError::Database(format!("Failed to open catalog: {}", e)))?
     );
 
     // Spawn extraction task
@@ -170,42 +716,45 @@ pub async fn start_extraction(
             game_path,
             manager_clone.clone(),
             catalog_for_task,
-            include_music,
+            options,
         )
         .await
         {
-            manager_clone.set_error(e);
+            manager_clone.set_error(e.to_string());
         }
     });
 
     Ok(())
 }
 
+/// Returns the options of an extraction that was interrupted before finishing,
+/// so the UI can offer to resume it.
+#[tauri::command]
+pub async fn get_pending_extraction(
+    catalog: State<'_, Catalog>,
+) -> Result<Option<ExtractionOptions>, KitharaError> {
+    catalog.get_pending_extraction()
+}
+
 /// Update the library by running extraction without clearing existing data.
 /// Skips already-converted files, only extracts new sounds.
 #[tauri::command]
 pub async fn update_library(
     app: AppHandle,
     game_path: String,
-    include_music: bool,
+    options: ExtractionOptions,
     manager: State<'_, Arc<ExtractionManager>>,
-    _catalog: State<'_, Catalog>,
-) -> Result<(), String> {
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
     let game_path = PathBuf::from(&game_path);
-    if !game_path.exists() {
-        return Err("Game path does not exist".into());
-    }
+    require_valid_game_path(&game_path)?;
+    catalog.save_last_game_path(&game_path.to_string_lossy())?;
 
-    let required_files = ["Events.xml"];
-    for file in required_files {
-        if !game_path.join(file).exists() {
-            return Err(format!("Required file not found: {}", file));
-        }
-    }
+    extractor::validate_options(&options)?;
 
     let status = manager.get_status();
     if matches!(status.state, ExtractionState::InProgress) {
-        return Err("Extraction already in progress".into());
+        return Err(KitharaError::InvalidInput("Extraction already in progress".into()));
     }
 
     manager.reset();
@@ -213,7 +762,7 @@ pub async fn update_library(
     let manager_clone = Arc::clone(&*manager);
     let db_path = crate::catalog::get_db_path()?;
     let catalog_for_task = Arc::new(
-        Catalog::open(db_path).map_err(|e| format!("Failed to open catalog: {}", e))?
+        Catalog::open(db_path).map_err(|e| KitharaError::Database(format!("Failed to open catalog: {}", e)))?
     );
 
     tauri::async_runtime::spawn(async move {
@@ -222,11 +771,11 @@ pub async fn update_library(
             game_path,
             manager_clone.clone(),
             catalog_for_task,
-            include_music,
+            options,
         )
         .await
         {
-            manager_clone.set_error(e);
+            manager_clone.set_error(e.to_string());
         }
     });
 
@@ -237,7 +786,7 @@ pub async fn update_library(
 #[tauri::command]
 pub async fn cancel_extraction(
     manager: State<'_, Arc<ExtractionManager>>,
-) -> Result<(), String> {
+) -> Result<(), KitharaError> {
     manager.request_cancel();
     Ok(())
 }
@@ -245,26 +794,118 @@ pub async fn cancel_extraction(
 /// Check if required audio dependencies are installed (macOS only)
 /// Returns a list of missing dependencies (empty if all are available)
 #[tauri::command]
-pub async fn check_audio_dependencies() -> Result<Vec<String>, String> {
+pub async fn check_audio_dependencies() -> Result<Vec<String>, KitharaError> {
     Ok(crate::extractor::converter::check_audio_dependencies().await)
 }
 
-/// Clear the cache (database records and sounds folder) for rebuilding
+/// Report which external audio tools are available, so the UI can show a
+/// setup check before extraction
+#[tauri::command]
+pub async fn check_dependencies(app: AppHandle) -> Result<DependencyStatus, KitharaError> {
+    Ok(crate::extractor::converter::check_dependencies(&app).await)
+}
+
+/// Back up the catalog database to a file. Safe to run while the app is in use.
+#[tauri::command]
+pub async fn backup_database(
+    dest_path: String,
+    catalog: State<'_, Catalog>,
+) -> Result<(), KitharaError> {
+    catalog.backup_to(Path::new(&dest_path))
+}
+
+/// Replace the catalog database with a backup made by `backup_database`.
+/// The file is checked to be a catalog before anything is overwritten.
+#[tauri::command]
+pub async fn restore_database(
+    src_path: String,
+    catalog: State<'_, Catalog>,
+    manager: State<'_, Arc<ExtractionManager>>,
+) -> Result<(), KitharaError> {
+    // Extraction writes through its own connection
+    if matches!(manager.get_status().state, ExtractionState::InProgress) {
+        return Err(KitharaError::InvalidInput("Cannot restore while extraction is in progress".into()));
+    }
+
+    catalog.restore_from(Path::new(&src_path))
+}
+
+/// Compact the catalog database and its search indexes.
+/// Runs on its own connection in a blocking task since VACUUM can take a while.
+#[tauri::command]
+pub async fn optimize_database(
+    manager: State<'_, Arc<ExtractionManager>>,
+) -> Result<OptimizeResult, KitharaError> {
+    if matches!(manager.get_status().state, ExtractionState::InProgress) {
+        return Err(KitharaError::InvalidInput("Cannot optimize while extraction is in progress".into()));
+    }
+
+    let db_path = crate::catalog::get_db_path()?;
+    let size_before = catalog::database_size(&db_path);
+
+    let task_path = db_path.clone();
+    tauri::async_runtime::spawn_blocking(move || Catalog::open(task_path)?.optimize())
+        .await
+        .map_err(|e| KitharaError::Database(format!("Optimize task failed: {}", e)))??;
+
+    Ok(OptimizeResult {
+        size_before,
+        size_after: catalog::database_size(&db_path),
+    })
+}
+
+/// Re-run the categorization heuristics over the existing catalog without
+/// re-extracting any audio. Returns the number of sounds whose classification changed.
+#[tauri::command]
+pub async fn recategorize_all(
+    catalog: State<'_, Catalog>,
+    manager: State<'_, Arc<ExtractionManager>>,
+) -> Result<usize, KitharaError> {
+    if matches!(manager.get_status().state, ExtractionState::InProgress) {
+        return Err(KitharaError::InvalidInput("Cannot recategorize while extraction is in progress".into()));
+    }
+    extractor::recategorize_all(&catalog)
+}
+
+/// Count sounds whose audio file is missing from disk, e.g. after files were
+/// deleted by hand. Checks run on their own connection in a blocking task.
+#[tauri::command]
+pub async fn verify_catalog() -> Result<CatalogVerification, KitharaError> {
+    let db_path = crate::catalog::get_db_path()?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let catalog = Catalog::open(db_path)?;
+        Ok(CatalogVerification {
+            sounds_checked: catalog.count_sounds()? as u32,
+            orphaned_sounds: catalog.find_orphaned_sounds()?.len() as u32,
+        })
+    })
+    .await
+    .map_err(|e| KitharaError::Database(format!("Verify task failed: {}", e)))?
+}
+
+/// Remove sounds whose audio file is missing from disk. Returns how many were removed.
+#[tauri::command]
+pub async fn prune_orphaned_sounds(catalog: State<'_, Catalog>) -> Result<usize, KitharaError> {
+    catalog.prune_orphaned_sounds()
+}
+
+/// Clear the cache (database records and sounds folder) for rebuilding.
+/// Emits `maintenance-progress` events while files are deleted.
 #[tauri::command]
 pub async fn clear_cache(
+    app: AppHandle,
     catalog: State<'_, Catalog>,
     manager: State<'_, Arc<ExtractionManager>>,
-) -> Result<(), String> {
+) -> Result<(), KitharaError> {
     // Clear all database records
     catalog.clear_all()?;
 
-    // Delete the sounds folder
-    let cache_dir = extractor::get_cache_dir()?;
-    let sounds_dir = cache_dir.join("sounds");
-    if sounds_dir.exists() {
-        std::fs::remove_dir_all(&sounds_dir)
-            .map_err(|e| format!("Failed to delete sounds folder: {}", e))?;
-    }
+    // Delete the sounds folder; in a custom output directory that's only the app's own subfolder
+    let sounds_dir = extractor::get_sounds_dir(&catalog)?;
+    maintenance::remove_dir_with_progress(&sounds_dir, |progress| {
+        let _ = app.emit("maintenance-progress", progress);
+    })
+    .map_err(|e| KitharaError::Io(format!("Failed to delete sounds folder: {}", e)))?;
 
     // Reset extraction state
     manager.reset();
@@ -272,23 +913,81 @@ pub async fn clear_cache(
     Ok(())
 }
 
-/// Detect the Old World game installation path
+/// Check a folder for the game's audio files, reporting each one found or missing
 #[tauri::command]
-pub async fn detect_game_path() -> Result<Option<String>, String> {
+pub async fn validate_game_path(path: String) -> Result<GamePathReport, KitharaError> {
+    Ok(metadata::check_game_path(Path::new(&path)))
+}
+
+/// Fails with every missing file listed at once if extraction can't run from `game_path`
+fn require_valid_game_path(game_path: &Path) -> Result<(), KitharaError> {
+    let report = metadata::check_game_path(game_path);
+    if report.is_valid {
+        Ok(())
+    } else {
+        Err(KitharaError::NotFound(format!(
+            "Not a valid game audio folder. Missing: {}",
+            report.missing.join(", ")
+        )))
+    }
+}
+
+/// Forget the saved output directory, so later extractions write to the app data directory.
+/// Sounds already extracted stay where they are.
+#[tauri::command]
+pub async fn clear_output_dir(catalog: State<'_, Catalog>) -> Result<(), KitharaError> {
+    catalog.save_output_dir(None)
+}
+
+/// Returns the game audio folder used last, if any
+#[tauri::command]
+pub async fn get_last_game_path(catalog: State<'_, Catalog>) -> Result<Option<String>, KitharaError> {
+    catalog.get_last_game_path()
+}
+
+/// Detect the Old World game installation path.
+/// A previously used folder wins if it's still valid, so non-standard installs stick.
+#[tauri::command]
+pub async fn detect_game_path(catalog: State<'_, Catalog>) -> Result<Option<String>, KitharaError> {
+    if let Some(saved) = catalog.get_last_game_path()? {
+        if metadata::check_game_path(Path::new(&saved)).is_valid {
+            return Ok(Some(saved));
+        }
+    }
+
     let possible_paths = get_possible_game_paths();
 
     for path in possible_paths {
         if path.exists() {
-            return Ok(Some(path.to_string_lossy().to_string()));
+            let path = path.to_string_lossy().to_string();
+            catalog.save_last_game_path(&path)?;
+            return Ok(Some(path));
         }
     }
 
     Ok(None)
 }
 
+/// Soundbank folder inside an Old World install, relative to a Steam library root
+#[cfg(target_os = "macos")]
+const STEAM_SOUNDBANK_SUBPATH: &str =
+    "steamapps/common/Old World/OldWorld.app/Contents/Resources/Data/StreamingAssets/Audio/GeneratedSoundBanks/Mac";
+#[cfg(target_os = "windows")]
+const STEAM_SOUNDBANK_SUBPATH: &str =
+    r"steamapps\common\Old World\OldWorld_Data\StreamingAssets\Audio\GeneratedSoundBanks\Windows";
+#[cfg(target_os = "linux")]
+const STEAM_SOUNDBANK_SUBPATH: &str =
+    "steamapps/common/Old World/OldWorld_Data/StreamingAssets/Audio/GeneratedSoundBanks/Linux";
+
 /// Get platform-specific possible game installation paths
 fn get_possible_game_paths() -> Vec<PathBuf> {
-    let mut paths = Vec::new();
+    // Every Steam library the user has set up, so installs on other drives are found
+    let mut paths: Vec<PathBuf> = get_steam_library_folders()
+        .into_iter()
+        .map(|library| library.join(STEAM_SOUNDBANK_SUBPATH))
+        .collect();
+
+    // Hard-coded locations as a fallback when Steam's library list can't be read
 
     #[cfg(target_os = "macos")]
     {
@@ -335,6 +1034,55 @@ fn get_possible_game_paths() -> Vec<PathBuf> {
     paths
 }
 
+/// Steam install folders for the current platform
+fn get_steam_install_dirs() -> Vec<PathBuf> {
+    let mut install_dirs = Vec::new();
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            install_dirs.push(home.join("Library/Application Support/Steam"));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        install_dirs.push(PathBuf::from(r"C:\Program Files (x86)\Steam"));
+        install_dirs.push(PathBuf::from(r"C:\Program Files\Steam"));
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            install_dirs.push(home.join(".steam/steam"));
+            install_dirs.push(home.join(".local/share/Steam"));
+        }
+    }
+
+    install_dirs
+}
+
+/// Library roots listed in each Steam install's `libraryfolders.vdf`
+fn get_steam_library_folders() -> Vec<PathBuf> {
+    let mut libraries: Vec<PathBuf> = Vec::new();
+    for steam_dir in get_steam_install_dirs() {
+        for vdf_path in [
+            steam_dir.join("steamapps").join("libraryfolders.vdf"),
+            steam_dir.join("config").join("libraryfolders.vdf"),
+        ] {
+            let Ok(content) = std::fs::read_to_string(&vdf_path) else {
+                continue;
+            };
+            for library in metadata::parse_steam_library_folders(&content) {
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
+        }
+    }
+    libraries
+}
+
 /// Helper module for getting home directory
 mod dirs {
     use std::path::PathBuf;
@@ -348,25 +1096,88 @@ mod dirs {
 
 /// Get all music tracks
 #[tauri::command]
-pub async fn get_music_tracks(catalog: State<'_, Catalog>) -> Result<Vec<MusicTrack>, String> {
+pub async fn get_music_tracks(catalog: State<'_, Catalog>) -> Result<Vec<MusicTrack>, KitharaError> {
     catalog.get_music_tracks()
 }
 
-/// Search music tracks by title
+/// Search music tracks by title (empty query returns all tracks)
 #[tauri::command]
 pub async fn search_music_tracks(
     query: String,
     catalog: State<'_, Catalog>,
-) -> Result<Vec<MusicTrack>, String> {
-    if query.trim().is_empty() {
-        catalog.get_music_tracks()
-    } else {
-        catalog.search_music_tracks(&query)
-    }
+) -> Result<Vec<MusicTrack>, KitharaError> {
+    catalog.search_music_tracks(&query)
 }
 
 /// Get count of music tracks
 #[tauri::command]
-pub async fn get_music_tracks_count(catalog: State<'_, Catalog>) -> Result<u64, String> {
+pub async fn get_music_tracks_count(catalog: State<'_, Catalog>) -> Result<u64, KitharaError> {
     catalog.count_music_tracks()
 }
+
+/// Play a music track. Uses the same player as sounds, so playback status,
+/// seeking, and queueing work the same way; music has no per-track gain.
+#[tauri::command]
+pub async fn play_music_track(
+    id: String,
+    file_path: String,
+    player: State<'_, PlayerState>,
+) -> Result<(), KitharaError> {
+    let path = PathBuf::from(&file_path);
+
+    if !path.exists() {
+        return Err(KitharaError::NotFound(format!("Audio file not found: {}", file_path)));
+    }
+
+    player.play(id, path, 0.0, false)
+}
+
+/// Shuffle music tracks (all, or those matching `query`) into the queue and start
+/// playing. With repeat-all on, the list is reshuffled when it runs out.
+/// `seed` reproduces a shuffle order; a time-based seed is used if omitted.
+/// Returns the number of tracks shuffled.
+#[tauri::command]
+pub async fn shuffle_music(
+    query: Option<String>,
+    seed: Option<u64>,
+    player: State<'_, PlayerState>,
+    catalog: State<'_, Catalog>,
+) -> Result<u32, KitharaError> {
+    let tracks = catalog.search_music_tracks(query.as_deref().unwrap_or(""))?;
+    if tracks.is_empty() {
+        return Err(KitharaError::NotFound("No music tracks to shuffle".into()));
+    }
+
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or(0)
+    });
+    let count = tracks.len() as u32;
+    let tracks = tracks
+        .into_iter()
+        .map(|track| (track.id, PathBuf::from(track.file_path), 0.0))
+        .collect();
+    player.shuffle(tracks, seed)?;
+    Ok(count)
+}
+
+/// Re-probe durations for music tracks stored with a zero duration.
+/// Returns the number of tracks that were updated.
+#[tauri::command]
+pub async fn backfill_music_durations(app: AppHandle, catalog: State<'_, Catalog>) -> Result<u32, KitharaError> {
+    let tracks = catalog.get_music_tracks_missing_duration()?;
+    let mut updated = 0;
+
+    for track in tracks {
+        let path = Path::new(&track.file_path);
+        let duration_secs = extractor::converter::get_audio_duration_or_estimate(&app, path).await;
+        if duration_secs > 0.0 {
+            catalog.update_music_track_duration(&track.id, duration_secs)?;
+            updated += 1;
+        }
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,278 @@
+//! Audio decoding for playback.
+//!
+//! OGG files are decoded with symphonia directly rather than through rodio's
+//! `Decoder`: it knows the file's length, so it can report the total duration
+//! and seek by timestamp. Other formats go through rodio's `Decoder`.
+
+use crate::error::KitharaError;
+use rodio::decoder::DecoderError;
+use rodio::source::SeekError;
+use rodio::{Decoder, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{self, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::default::formats::OggReader;
+
+/// A decoded audio file, ready to append to a sink
+/// Both decoders are large, so they're boxed to keep moving the source cheap.
+pub enum AudioSource {
+    Ogg(Box<OggSource>),
+    Other(Box<Decoder<BufReader<File>>>),
+}
+
+/// Opens and decodes an audio file. Errors name the file and why it couldn't be played.
+pub fn open(path: &Path) -> Result<AudioSource, KitharaError> {
+    let file = File::open(path).map_err(|e| KitharaError::Io(format!("Failed to open {}: {}", path.display(), e)))?;
+
+    let is_ogg = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ogg"));
+    if is_ogg {
+        return OggSource::new(file)
+            .map(|source| AudioSource::Ogg(Box::new(source)))
+            .map_err(|e| KitharaError::Decode(format!("Failed to decode {}: {}", path.display(), e)));
+    }
+
+    Decoder::new(BufReader::new(file))
+        .map(|decoder| AudioSource::Other(Box::new(decoder)))
+        .map_err(|e| match e {
+            DecoderError::UnrecognizedFormat => {
+                KitharaError::Decode(format!("Failed to decode {}: not a recognized audio format", path.display()))
+            }
+            e => KitharaError::Decode(format!("Failed to decode {}: {}", path.display(), e)),
+        })
+}
+
+impl Iterator for AudioSource {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        match self {
+            AudioSource::Ogg(source) => source.next(),
+            AudioSource::Other(source) => source.next(),
+        }
+    }
+}
+
+impl Source for AudioSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        match self {
+            AudioSource::Ogg(source) => source.current_frame_len(),
+            AudioSource::Other(source) => source.current_frame_len(),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        match self {
+            AudioSource::Ogg(source) => source.channels(),
+            AudioSource::Other(source) => source.channels(),
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        match self {
+            AudioSource::Ogg(source) => source.sample_rate(),
+            AudioSource::Other(source) => source.sample_rate(),
+        }
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        match self {
+            AudioSource::Ogg(source) => source.total_duration(),
+            AudioSource::Other(source) => source.total_duration(),
+        }
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        match self {
+            AudioSource::Ogg(source) => source.try_seek(pos),
+            AudioSource::Other(source) => source.try_seek(pos),
+        }
+    }
+}
+
+/// An OGG/Vorbis file decoded one packet at a time with symphonia
+pub struct OggSource {
+    format: OggReader,
+    decoder: Box<dyn codecs::Decoder>,
+    track_id: u32,
+    channels: u16,
+    sample_rate: u32,
+    total_duration: Option<Duration>,
+    /// Samples of the last decoded packet, interleaved
+    buffer: Vec<i16>,
+    offset: usize,
+}
+
+impl OggSource {
+    fn new(file: File) -> Result<Self, SymphoniaError> {
+        // A `File` reports its length, which the OGG reader needs to find the
+        // stream's duration and to bisect when seeking
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+        let format = OggReader::try_new(stream, &FormatOptions::default())?;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or(SymphoniaError::Unsupported("no audio track"))?;
+        let params = &track.codec_params;
+        let decoder = symphonia::default::get_codecs().make(params, &DecoderOptions::default())?;
+        let total_duration = params
+            .time_base
+            .zip(params.n_frames)
+            .map(|(base, frames)| {
+                let time = base.calc_time(frames);
+                Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac)
+            });
+
+        let mut source = Self {
+            track_id: track.id,
+            channels: params.channels.map_or(0, |c| c.count() as u16),
+            sample_rate: params.sample_rate.unwrap_or(0),
+            total_duration,
+            format,
+            decoder,
+            buffer: Vec::new(),
+            offset: 0,
+        };
+        // Fill the buffer up front so the channel count and rate are known
+        source.decode_next_packet();
+        Ok(source)
+    }
+
+    /// Decodes the next packet of the track into the buffer. Returns false at the
+    /// end of the stream; packets that fail to decode are skipped.
+    fn decode_next_packet(&mut self) -> bool {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(_) => return false,
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            match self.decoder.decode(&packet) {
+                Ok(decoded) => {
+                    let spec = *decoded.spec();
+                    let mut samples = SampleBuffer::<i16>::new(decoded.capacity() as u64, spec);
+                    samples.copy_interleaved_ref(decoded);
+                    self.channels = spec.channels.count() as u16;
+                    self.sample_rate = spec.rate;
+                    self.buffer.clear();
+                    self.buffer.extend_from_slice(samples.samples());
+                    self.offset = 0;
+                    return true;
+                }
+                Err(SymphoniaError::DecodeError(_)) => continue,
+                Err(_) => return false,
+            }
+        }
+    }
+}
+
+impl Iterator for OggSource {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        while self.offset >= self.buffer.len() {
+            if !self.decode_next_packet() {
+                return None;
+            }
+        }
+        let sample = self.buffer[self.offset];
+        self.offset += 1;
+        Some(sample)
+    }
+}
+
+impl Source for OggSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.buffer.len() - self.offset)
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let seeked = self
+            .format
+            .seek(
+                SeekMode::Accurate,
+                SeekTo::Time {
+                    time: pos.as_secs_f64().into(),
+                    track_id: Some(self.track_id),
+                },
+            )
+            .map_err(|e| SeekError::Other(Box::new(e)))?;
+        self.decoder.reset();
+
+        // The reader lands on the packet holding the target; skip up to the exact frame
+        let mut skip_frames = seeked.required_ts.saturating_sub(seeked.actual_ts) as usize;
+        while self.decode_next_packet() {
+            let frames = self.buffer.len() / self.channels.max(1) as usize;
+            if skip_frames < frames {
+                self.offset = skip_frames * self.channels as usize;
+                return Ok(());
+            }
+            skip_frames -= frames;
+        }
+        // Seeking past the end leaves nothing to play
+        self.buffer.clear();
+        self.offset = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_sound(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/test-sounds").join(name)
+    }
+
+    #[test]
+    fn test_decodes_ogg_with_duration_and_seeking() {
+        let mut source = open(&test_sound("test-short.ogg")).unwrap();
+        assert!(matches!(source, AudioSource::Ogg(_)));
+        assert!(source.channels() > 0);
+        assert!(source.sample_rate() > 0);
+
+        let duration = source.total_duration().unwrap();
+        assert!((duration.as_secs_f64() - 1.0).abs() < 0.05, "duration was {:?}", duration);
+
+        // Seeking halfway leaves about half the samples to play
+        source.try_seek(duration / 2).unwrap();
+        let frames_left = source.by_ref().count() / source.channels() as usize;
+        let secs_left = frames_left as f64 / source.sample_rate() as f64;
+        assert!((secs_left - duration.as_secs_f64() / 2.0).abs() < 0.05, "{}s left after seek", secs_left);
+    }
+
+    #[test]
+    fn test_rejects_files_that_are_not_audio() {
+        let dir = std::env::temp_dir().join("kithara_decoder_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("not-audio.ogg");
+        std::fs::write(&path, b"not an ogg file").unwrap();
+
+        let error = open(&path).err().unwrap();
+        assert!(matches!(error, KitharaError::Decode(_)), "{:?}", error);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+//! Error type returned by the catalog, player, extractor and Tauri commands.
+//! Serializes as `{ kind, message }` so the frontend can tell failures apart.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+#[cfg(test)]
+use ts_rs::TS;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KitharaError {
+    /// A file or directory couldn't be read, written or removed
+    Io(String),
+    /// A catalog query, transaction or migration failed
+    Database(String),
+    /// A soundbank, XML file or audio stream couldn't be parsed or decoded
+    Decode(String),
+    /// vgmstream or ffmpeg failed or isn't installed
+    Conversion(String),
+    /// The audio output or player thread failed
+    Playback(String),
+    /// A sound, file or game folder doesn't exist
+    NotFound(String),
+    /// An argument or option was rejected before any work was done
+    InvalidInput(String),
+    /// The user cancelled the operation
+    Cancelled,
+}
+
+/// The variant of a `KitharaError`, sent to the frontend as `kind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    Io,
+    Database,
+    Decode,
+    Conversion,
+    Playback,
+    NotFound,
+    InvalidInput,
+    Cancelled,
+}
+
+impl KitharaError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            KitharaError::Io(_) => ErrorKind::Io,
+            KitharaError::Database(_) => ErrorKind::Database,
+            KitharaError::Decode(_) => ErrorKind::Decode,
+            KitharaError::Conversion(_) => ErrorKind::Conversion,
+            KitharaError::Playback(_) => ErrorKind::Playback,
+            KitharaError::NotFound(_) => ErrorKind::NotFound,
+            KitharaError::InvalidInput(_) => ErrorKind::InvalidInput,
+            KitharaError::Cancelled => ErrorKind::Cancelled,
+        }
+    }
+}
+
+impl fmt::Display for KitharaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KitharaError::Io(message)
+            | KitharaError::Database(message)
+            | KitharaError::Decode(message)
+            | KitharaError::Conversion(message)
+            | KitharaError::Playback(message)
+            | KitharaError::NotFound(message)
+            | KitharaError::InvalidInput(message) => f.write_str(message),
+            KitharaError::Cancelled => f.write_str("Extraction cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for KitharaError {}
+
+impl Serialize for KitharaError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("KitharaError", 2)?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_bindings() {
+        ErrorKind::export_all().expect("Failed to export ErrorKind");
+    }
+
+    #[test]
+    fn test_serializes_kind_and_message() {
+        let error = KitharaError::NotFound("Audio file not found: a.ogg".into());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "notFound", "message": "Audio file not found: a.ogg" })
+        );
+        assert_eq!(
+            serde_json::to_value(KitharaError::Cancelled).unwrap(),
+            serde_json::json!({ "kind": "cancelled", "message": "Extraction cancelled" })
+        );
+    }
+}
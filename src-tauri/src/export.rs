@@ -0,0 +1,313 @@
+//! Export of extracted sounds to ZIP archives and soundboard configs.
+
+use crate::error::KitharaError;
+use crate::models::{ExportProgress, ExportSummary, Sound, ZipCompression};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Name of the metadata file written at the root of every export
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Contents of `manifest.json`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest<'a> {
+    sounds: Vec<ManifestEntry<'a>>,
+    errors: Vec<ManifestError>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry<'a> {
+    /// Path of the audio file inside the archive
+    file: String,
+    id: &'a str,
+    event_name: &'a str,
+    display_name: &'a str,
+    category: &'a str,
+    unit_type: Option<&'a str>,
+    duration: f64,
+    tags: &'a [String],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestError {
+    id: String,
+    error: String,
+}
+
+/// Writes the given sounds into a ZIP archive at `dest_path`, named after
+/// their display names, along with a `manifest.json` describing them.
+/// Calls `on_progress` after each file is added. Sounds whose audio file is
+/// missing on disk, and any `missing_ids` the caller couldn't find in the
+/// catalog, are skipped and listed in the manifest's `errors` and the summary.
+pub fn export_sounds_zip<F>(
+    sounds: &[Sound],
+    missing_ids: &[String],
+    dest_path: &Path,
+    compression: ZipCompression,
+    mut on_progress: F,
+) -> Result<ExportSummary, KitharaError>
+where
+    F: FnMut(&ExportProgress),
+{
+    let file = File::create(dest_path)
+        .map_err(|e| KitharaError::Io(format!("Failed to create {}: {}", dest_path.display(), e)))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+
+    let method = match compression {
+        ZipCompression::Stored => CompressionMethod::Stored,
+        ZipCompression::Deflated => CompressionMethod::Deflated,
+    };
+    let options = SimpleFileOptions::default().compression_method(method);
+
+    let files_total = sounds.len() as u32;
+    let mut file_count = 0;
+    let mut bytes_added: u64 = 0;
+    let mut skipped: Vec<String> = missing_ids.to_vec();
+    let mut manifest = Manifest {
+        sounds: Vec::new(),
+        errors: missing_ids
+            .iter()
+            .map(|id| ManifestError {
+                id: id.clone(),
+                error: "Sound not found in catalog".to_string(),
+            })
+            .collect(),
+    };
+    let mut used_names = HashSet::new();
+
+    for (index, sound) in sounds.iter().enumerate() {
+        let source_path = Path::new(&sound.file_path);
+        if !source_path.is_file() {
+            skipped.push(sound.id.clone());
+            manifest.errors.push(ManifestError {
+                id: sound.id.clone(),
+                error: format!("Audio file not found: {}", sound.file_path),
+            });
+            continue;
+        }
+        let entry_name = entry_name_for(sound, source_path, &mut used_names);
+
+        let source = File::open(source_path)
+            .map_err(|e| KitharaError::Io(format!("Failed to open {}: {}", source_path.display(), e)))?;
+
+        zip.start_file(entry_name.as_str(), options)
+            .map_err(|e| KitharaError::Io(format!("Failed to add {} to archive: {}", entry_name, e)))?;
+        bytes_added += std::io::copy(&mut BufReader::new(source), &mut zip)
+            .map_err(|e| KitharaError::Io(format!("Failed to write {} to archive: {}", entry_name, e)))?;
+        file_count += 1;
+
+        manifest.sounds.push(ManifestEntry {
+            file: entry_name.clone(),
+            id: &sound.id,
+            event_name: &sound.event_name,
+            display_name: &sound.display_name,
+            category: &sound.category,
+            unit_type: sound.unit_type.as_deref(),
+            duration: sound.duration,
+            tags: &sound.tags,
+        });
+
+        on_progress(&ExportProgress {
+            files_done: index as u32 + 1,
+            files_total,
+            current_file: Some(entry_name),
+            bytes_written: bytes_added,
+        });
+    }
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| KitharaError::Io(format!("Failed to serialize manifest: {}", e)))?;
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| KitharaError::Io(format!("Failed to add manifest to archive: {}", e)))?;
+    zip.write_all(&manifest_json)
+        .map_err(|e| KitharaError::Io(format!("Failed to write manifest: {}", e)))?;
+
+    zip.finish()
+        .map_err(|e| KitharaError::Io(format!("Failed to finalize archive: {}", e)))?;
+
+    let bytes_written = std::fs::metadata(dest_path)
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    Ok(ExportSummary {
+        file_count,
+        bytes_written,
+        skipped,
+    })
+}
+
+/// Version of the soundboard config schema, bumped on breaking changes
+const SOUNDBOARD_CONFIG_VERSION: u32 = 1;
+
+/// Soundboard config written by `export_soundboard_config`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SoundboardConfig<'a> {
+    version: u32,
+    sounds: Vec<SoundboardEntry<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SoundboardEntry<'a> {
+    id: &'a str,
+    name: &'a str,
+    category: &'a str,
+    /// Absolute path of the audio file
+    path: &'a str,
+    /// Path relative to the export directory, when one was given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relative_path: Option<String>,
+    /// Left empty for the user to fill in
+    hotkey: Option<String>,
+}
+
+/// Writes a JSON soundboard config for the given sounds to `dest_path`, for
+/// mapping them to hotkeys in external soundboard software:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "sounds": [
+///     {
+///       "id": "123456",
+///       "name": "Combat Archer Attack",
+///       "category": "combat",
+///       "path": "/home/me/.local/share/kithara/sounds/combat/123456_attack.ogg",
+///       "relativePath": "sounds/combat/123456_attack.ogg",
+///       "hotkey": null
+///     }
+///   ]
+/// }
+/// ```
+///
+/// `relativePath` is only written when `export_dir` is given, relative to it
+/// (with `..` segments if needed). `hotkey` is always null for the user to fill in.
+pub fn export_soundboard_config(
+    sounds: &[Sound],
+    dest_path: &Path,
+    export_dir: Option<&Path>,
+) -> Result<(), KitharaError> {
+    let config = SoundboardConfig {
+        version: SOUNDBOARD_CONFIG_VERSION,
+        sounds: sounds
+            .iter()
+            .map(|sound| SoundboardEntry {
+                id: &sound.id,
+                name: &sound.display_name,
+                category: &sound.category,
+                path: &sound.file_path,
+                relative_path: export_dir
+                    .and_then(|dir| relative_path(dir, Path::new(&sound.file_path)))
+                    .map(|path| path.to_string_lossy().replace('\\', "/")),
+                hotkey: None,
+            })
+            .collect(),
+    };
+
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| KitharaError::Io(format!("Failed to serialize soundboard config: {}", e)))?;
+    std::fs::write(dest_path, json)
+        .map_err(|e| KitharaError::Io(format!("Failed to write {}: {}", dest_path.display(), e)))
+}
+
+/// Path to `target` from `base`, both absolute. None if they share no root
+/// (e.g. different Windows drives).
+fn relative_path(base: &Path, target: &Path) -> Option<PathBuf> {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return None;
+    }
+
+    let mut path: PathBuf = base[common..].iter().map(|_| Component::ParentDir).collect();
+    path.extend(&target[common..]);
+    Some(path)
+}
+
+/// Builds a unique archive entry name from the sound's display name, keeping
+/// the source file's extension. Characters that aren't valid in file names on
+/// common platforms are replaced, and duplicates get a " (n)" suffix.
+fn entry_name_for(sound: &Sound, source_path: &Path, used: &mut HashSet<String>) -> String {
+    let sanitized: String = sound
+        .display_name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let stem = match sanitized.trim() {
+        "" => sound.id.as_str(),
+        trimmed => trimmed,
+    };
+    let extension = source_path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut name = format!("{}{}", stem, extension);
+    let mut suffix = 2;
+    while name == MANIFEST_NAME || !used.insert(name.to_lowercase()) {
+        name = format!("{} ({}){}", stem, suffix, extension);
+        suffix += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sound(id: &str, display_name: &str) -> Sound {
+        Sound {
+            id: id.to_string(),
+            event_name: String::new(),
+            display_name: display_name.to_string(),
+            category: String::new(),
+            unit_type: None,
+            subcategory: String::new(),
+            duration: 0.0,
+            file_path: String::new(),
+            tags: Vec::new(),
+            is_favorite: false,
+            gender: None,
+            language: None,
+            source_bank: None,
+            gain_db: 0.0,
+            is_loop: false,
+            short_name: None,
+            available: true,
+        }
+    }
+
+    #[test]
+    fn test_entry_name_for() {
+        let mut used = HashSet::new();
+        let ogg = Path::new("/cache/123.ogg");
+
+        assert_eq!(entry_name_for(&sound("1", "Archer: Attack"), ogg, &mut used), "Archer_ Attack.ogg");
+        assert_eq!(entry_name_for(&sound("2", "Archer: Attack"), ogg, &mut used), "Archer_ Attack (2).ogg");
+        assert_eq!(entry_name_for(&sound("3", "archer: attack"), ogg, &mut used), "archer_ attack (3).ogg");
+        assert_eq!(entry_name_for(&sound("4", "  "), ogg, &mut used), "4.ogg");
+        assert_eq!(entry_name_for(&sound("5", "manifest"), Path::new("manifest.json"), &mut used), "manifest (2).json");
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let target = Path::new("/data/kithara/sounds/combat/1.ogg");
+        assert_eq!(relative_path(Path::new("/data/kithara"), target), Some(PathBuf::from("sounds/combat/1.ogg")));
+        assert_eq!(relative_path(Path::new("/data/boards/mine"), target), Some(PathBuf::from("../../kithara/sounds/combat/1.ogg")));
+        assert_eq!(relative_path(Path::new("relative"), target), None);
+    }
+}
@@ -1,9 +1,13 @@
 //! Wwise BNK soundbank parser.
-//! Parses BKHD, DIDX, and DATA sections to extract embedded WEM audio.
+//! Parses BKHD, DIDX, and DATA sections to extract embedded WEM audio,
+//! STID for human-readable bank names, and the HIRC section to link
+//! WEM files to the events that play them.
 
+use crate::error::KitharaError;
 use byteorder::{LittleEndian, ReadBytesExt};
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
 /// Represents an embedded WEM file within a BNK
@@ -14,108 +18,280 @@ pub struct WemEntry {
     pub size: u32,        // Size of WEM data
     pub bnk_path: PathBuf, // Source BNK file
     pub data_offset: u64,  // Absolute offset of DATA section in BNK
+    pub data_size: u32,    // Size of DATA section, bounding offset + size
+    pub bank_name: Option<String>, // Bank name from STID, if the bank lists its own
 }
 
+/// Contents of a parsed BNK file
+#[derive(Debug, Clone, Default)]
+pub struct BnkContents {
+    /// Bank ID from the BKHD header
+    pub bank_id: Option<u32>,
+    pub entries: Vec<WemEntry>,
+    /// Bank ID -> name, from the STID section
+    pub bank_names: HashMap<u32, String>,
+}
+
+impl BnkContents {
+    /// This bank's own name, if STID lists it
+    pub fn bank_name(&self) -> Option<&str> {
+        self.bank_id
+            .and_then(|id| self.bank_names.get(&id))
+            .map(String::as_str)
+    }
+}
+
+/// Range of BKHD bank versions whose DIDX/DATA layout we understand
+/// (roughly Wwise 2011 through 2023)
+const MIN_BANK_VERSION: u32 = 45;
+const MAX_BANK_VERSION: u32 = 160;
+
+/// Largest single WEM we'll extract; guards against corrupt DIDX sizes
+const MAX_WEM_SIZE: u32 = 512 * 1024 * 1024;
+
+/// Size of one DIDX entry: file_id, offset, size (u32 each)
+const DIDX_ENTRY_SIZE: u32 = 12;
+
+/// Most pad bytes skipped looking for the next chunk. Some banks align chunks
+/// to 4 or 8 bytes without counting the padding in the chunk size.
+const MAX_CHUNK_PADDING: usize = 7;
+
 /// Chunk header in BNK file (4-byte magic + 4-byte size)
 struct ChunkHeader {
     magic: [u8; 4],
     size: u32,
 }
 
-/// Parse a BNK file and return all embedded WEM entries
-pub fn parse_bnk(bnk_path: &Path) -> Result<Vec<WemEntry>, String> {
+/// Parse a BNK file and return its embedded WEM entries and bank names
+pub fn parse_bnk(bnk_path: &Path) -> Result<BnkContents, KitharaError> {
     let file = File::open(bnk_path)
-        .map_err(|e| format!("Failed to open BNK {}: {}", bnk_path.display(), e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to open BNK {}: {}", bnk_path.display(), e)))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| KitharaError::Io(format!("Failed to read BNK size {}: {}", bnk_path.display(), e)))?
+        .len();
     let mut reader = BufReader::new(file);
 
-    let mut entries = Vec::new();
+    let mut contents = BnkContents::default();
     let mut didx_entries: Vec<(u32, u32, u32)> = Vec::new(); // (id, offset, size)
     let mut data_section_offset: u64 = 0;
+    let mut data_section_size: u32 = 0;
+    let mut version: Option<u32> = None;
 
-    // Parse chunks until EOF
-    loop {
-        let chunk = match read_chunk_header(&mut reader) {
-            Ok(c) => c,
-            Err(_) => break, // EOF or read error - done parsing
-        };
+    // Parse chunks until EOF, or until nothing that looks like a chunk follows
+    let mut next_chunk: u64 = 0;
+    while let Some(chunk) = read_chunk_header(&mut reader, next_chunk) {
 
         let chunk_start = reader.stream_position().unwrap_or(0);
         let magic_str = std::str::from_utf8(&chunk.magic).unwrap_or("????");
 
         match magic_str {
             "BKHD" => {
-                // Bank header - skip (contains version, bank ID, etc.)
+                // Bank header - version first, then bank ID, language, etc.
+                let bank_version = reader
+                    .read_u32::<LittleEndian>()
+                    .map_err(|e| KitharaError::Decode(format!("Failed to read BKHD version: {}", e)))?;
+                if !(MIN_BANK_VERSION..=MAX_BANK_VERSION).contains(&bank_version) {
+                    return Err(KitharaError::Decode(format!(
+                        "Unsupported BNK version {} in {} (supported: {}-{})",
+                        bank_version,
+                        bnk_path.display(),
+                        MIN_BANK_VERSION,
+                        MAX_BANK_VERSION
+                    )));
+                }
+                version = Some(bank_version);
+                contents.bank_id = reader.read_u32::<LittleEndian>().ok();
             }
             "DIDX" => {
+                let Some(bank_version) = version else {
+                    return Err(KitharaError::Decode(format!(
+                        "DIDX section before BKHD header in {}",
+                        bnk_path.display()
+                    )));
+                };
+                if chunk.size % DIDX_ENTRY_SIZE != 0 {
+                    return Err(KitharaError::Decode(format!(
+                        "Malformed DIDX section in {} (bank version {}): size {} is not a multiple of {}",
+                        bnk_path.display(),
+                        bank_version,
+                        chunk.size,
+                        DIDX_ENTRY_SIZE
+                    )));
+                }
+
                 // Data index - array of {file_id: u32, offset: u32, size: u32}
-                let entry_count = chunk.size / 12;
+                let entry_count = chunk.size / DIDX_ENTRY_SIZE;
                 for _ in 0..entry_count {
                     let file_id = reader
                         .read_u32::<LittleEndian>()
-                        .map_err(|e| format!("Failed to read DIDX file_id: {}", e))?;
+                        .map_err(|e| KitharaError::Decode(format!("Failed to read DIDX file_id: {}", e)))?;
                     let offset = reader
                         .read_u32::<LittleEndian>()
-                        .map_err(|e| format!("Failed to read DIDX offset: {}", e))?;
+                        .map_err(|e| KitharaError::Decode(format!("Failed to read DIDX offset: {}", e)))?;
                     let size = reader
                         .read_u32::<LittleEndian>()
-                        .map_err(|e| format!("Failed to read DIDX size: {}", e))?;
+                        .map_err(|e| KitharaError::Decode(format!("Failed to read DIDX size: {}", e)))?;
                     didx_entries.push((file_id, offset, size));
                 }
             }
             "DATA" => {
                 // Store the absolute offset of the DATA section content
                 data_section_offset = reader.stream_position().unwrap_or(0);
+                data_section_size = chunk.size;
+            }
+            "STID" => {
+                let data = read_chunk_body(&mut reader, chunk.size, chunk_start, file_len, "STID")?;
+                contents.bank_names = parse_stid(&data)
+                    .map_err(|e| KitharaError::Decode(format!("{} in {}", e, bnk_path.display())))?;
             }
             _ => {
-                // Skip unknown chunks (HIRC, STID, ENVS, etc.)
+                // Skip unknown chunks (HIRC, ENVS, etc.)
             }
         }
 
-        // Seek to next chunk
-        if let Err(e) = reader.seek(SeekFrom::Start(chunk_start + chunk.size as u64)) {
-            // If seek fails, we've probably hit EOF
-            if e.kind() != std::io::ErrorKind::UnexpectedEof {
-                return Err(format!("Failed to seek to next chunk: {}", e));
-            }
-            break;
-        }
+        next_chunk = chunk_start + chunk.size as u64;
     }
 
     // Verify we found both DIDX and DATA sections
     if data_section_offset == 0 && !didx_entries.is_empty() {
-        return Err("Found DIDX but no DATA section in BNK".to_string());
+        return Err(KitharaError::Decode(format!("Found DIDX but no DATA section in {}", bnk_path.display())));
+    }
+
+    // Verify the DATA section actually holds everything DIDX points into,
+    // so a truncated bank fails here rather than partway through extraction
+    let data_available = file_len.saturating_sub(data_section_offset).min(data_section_size as u64);
+    if let Some((file_id, offset, size)) = didx_entries
+        .iter()
+        .max_by_key(|(_, offset, size)| *offset as u64 + *size as u64)
+    {
+        let required = *offset as u64 + *size as u64;
+        if required > data_available {
+            return Err(KitharaError::Decode(format!(
+                "Truncated DATA section in {}: WEM {} needs {} bytes but only {} are present",
+                bnk_path.display(),
+                file_id,
+                required,
+                data_available
+            )));
+        }
     }
 
     // Build WemEntry list
+    let bank_name = contents.bank_name().map(str::to_string);
     for (file_id, offset, size) in didx_entries {
-        entries.push(WemEntry {
+        contents.entries.push(WemEntry {
             file_id,
             offset,
             size,
             bnk_path: bnk_path.to_path_buf(),
             data_offset: data_section_offset,
+            data_size: data_section_size,
+            bank_name: bank_name.clone(),
         });
     }
 
-    Ok(entries)
+    Ok(contents)
 }
 
-/// Read chunk header (4-byte magic + 4-byte little-endian size)
-fn read_chunk_header(reader: &mut BufReader<File>) -> Result<ChunkHeader, String> {
-    let mut magic = [0u8; 4];
-    reader
-        .read_exact(&mut magic)
-        .map_err(|e| format!("Failed to read chunk magic: {}", e))?;
-    let size = reader
+/// Parse STID section contents: a string type, an entry count, then
+/// {bank_id: u32, name_len: u8, name} per bank
+pub fn parse_stid(data: &[u8]) -> Result<HashMap<u32, String>, KitharaError> {
+    let mut cursor = Cursor::new(data);
+    cursor
+        .read_u32::<LittleEndian>()
+        .map_err(|e| KitharaError::Decode(format!("Failed to read STID string type: {}", e)))?;
+    let count = cursor
         .read_u32::<LittleEndian>()
-        .map_err(|e| format!("Failed to read chunk size: {}", e))?;
-    Ok(ChunkHeader { magic, size })
+        .map_err(|e| KitharaError::Decode(format!("Failed to read STID entry count: {}", e)))?;
+
+    let mut names = HashMap::new();
+    for _ in 0..count {
+        let bank_id = cursor
+            .read_u32::<LittleEndian>()
+            .map_err(|e| KitharaError::Decode(format!("Failed to read STID bank ID: {}", e)))?;
+        let len = cursor
+            .read_u8()
+            .map_err(|e| KitharaError::Decode(format!("Failed to read STID name length: {}", e)))?;
+        let mut name = vec![0u8; len as usize];
+        cursor
+            .read_exact(&mut name)
+            .map_err(|e| KitharaError::Decode(format!("STID name for bank {} overruns the section: {}", bank_id, e)))?;
+        names.insert(bank_id, String::from_utf8_lossy(&name).into_owned());
+    }
+
+    Ok(names)
+}
+
+/// Read the chunk header (4-byte magic + 4-byte little-endian size) at `pos`,
+/// skipping up to `MAX_CHUNK_PADDING` pad bytes before it. Leaves the reader at
+/// the chunk body. None at EOF or when no plausible magic is found.
+fn read_chunk_header(reader: &mut BufReader<File>, pos: u64) -> Option<ChunkHeader> {
+    reader.seek(SeekFrom::Start(pos)).ok()?;
+    let mut buf = Vec::with_capacity(MAX_CHUNK_PADDING + 8);
+    reader
+        .by_ref()
+        .take((MAX_CHUNK_PADDING + 8) as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+
+    let padding = (0..=MAX_CHUNK_PADDING)
+        .take_while(|&skip| skip + 8 <= buf.len())
+        .find(|&skip| is_chunk_magic(&buf[skip..skip + 4]))?;
+    let magic = buf[padding..padding + 4].try_into().ok()?;
+    let size = u32::from_le_bytes(buf[padding + 4..padding + 8].try_into().ok()?);
+    reader.seek(SeekFrom::Start(pos + padding as u64 + 8)).ok()?;
+    Some(ChunkHeader { magic, size })
+}
+
+/// Chunk magics are four uppercase ASCII letters or digits (BKHD, DIDX, HIRC, ...)
+fn is_chunk_magic(bytes: &[u8]) -> bool {
+    bytes.iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+/// Read the body of the chunk starting at `chunk_start`. A size running past the
+/// end of the file is rejected before allocating, so a corrupt header can't
+/// ask for gigabytes.
+fn read_chunk_body(
+    reader: &mut BufReader<File>,
+    size: u32,
+    chunk_start: u64,
+    file_len: u64,
+    section: &str,
+) -> Result<Vec<u8>, KitharaError> {
+    let available = file_len.saturating_sub(chunk_start);
+    if size as u64 > available {
+        return Err(KitharaError::Decode(format!(
+            "{} section claims {} bytes but only {} remain in the file",
+            section, size, available
+        )));
+    }
+
+    let mut data = vec![0u8; size as usize];
+    reader
+        .read_exact(&mut data)
+        .map_err(|e| KitharaError::Decode(format!("Failed to read {} section: {}", section, e)))?;
+    Ok(data)
 }
 
 /// Extract WEM bytes from BNK to a file
-pub fn extract_wem_bytes(entry: &WemEntry, output_path: &Path) -> Result<(), String> {
+pub fn extract_wem_bytes(entry: &WemEntry, output_path: &Path) -> Result<(), KitharaError> {
+    // Reject entries that point outside the DATA section before touching the file
+    if entry.offset as u64 + entry.size as u64 > entry.data_size as u64 {
+        return Err(KitharaError::Decode(format!(
+            "WEM {} lies outside the DATA section (offset {} + size {} > {})",
+            entry.file_id, entry.offset, entry.size, entry.data_size
+        )));
+    }
+    if entry.size > MAX_WEM_SIZE {
+        return Err(KitharaError::Decode(format!(
+            "WEM {} is implausibly large ({} bytes)",
+            entry.file_id, entry.size
+        )));
+    }
+
     let file = File::open(&entry.bnk_path)
-        .map_err(|e| format!("Failed to open BNK {}: {}", entry.bnk_path.display(), e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to open BNK {}: {}", entry.bnk_path.display(), e)))?;
     let mut reader = BufReader::new(file);
 
     // Calculate absolute position of the WEM data
@@ -124,24 +300,250 @@ pub fn extract_wem_bytes(entry: &WemEntry, output_path: &Path) -> Result<(), Str
     // Seek to the WEM data
     reader
         .seek(SeekFrom::Start(absolute_offset))
-        .map_err(|e| format!("Failed to seek to WEM data at offset {}: {}", absolute_offset, e))?;
-
-    // Read WEM bytes
-    let mut buffer = vec![0u8; entry.size as usize];
-    reader
-        .read_exact(&mut buffer)
-        .map_err(|e| format!("Failed to read {} bytes of WEM data: {}", entry.size, e))?;
+        .map_err(|e| KitharaError::Decode(format!("Failed to seek to WEM data at offset {}: {}", absolute_offset, e)))?;
 
-    // Write to output file
+    // Copy WEM bytes straight to the output file
     let mut output = File::create(output_path)
-        .map_err(|e| format!("Failed to create output file {}: {}", output_path.display(), e))?;
-    output
-        .write_all(&buffer)
-        .map_err(|e| format!("Failed to write WEM data: {}", e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to create output file {}: {}", output_path.display(), e)))?;
+    let copied = std::io::copy(&mut reader.take(entry.size as u64), &mut output)
+        .map_err(|e| KitharaError::Io(format!("Failed to write WEM data: {}", e)))?;
+
+    if copied != entry.size as u64 {
+        return Err(KitharaError::Decode(format!(
+            "WEM {} is truncated: read {} of {} bytes",
+            entry.file_id, copied, entry.size
+        )));
+    }
 
     Ok(())
 }
 
+// ============================================================================
+// HIRC (object hierarchy)
+// ============================================================================
+
+/// HIRC object types we follow from events down to sounds
+const HIRC_SOUND: u8 = 2;
+const HIRC_ACTION: u8 = 3;
+const HIRC_EVENT: u8 = 4;
+
+/// Objects parsed from a HIRC section
+#[derive(Debug, Default)]
+pub struct HircIndex {
+    /// Sound object ID -> (source WEM file ID, direct parent object ID)
+    sounds: HashMap<u32, (u32, u32)>,
+    /// Action ID -> target object ID
+    actions: HashMap<u32, u32>,
+    /// Event ID -> action IDs
+    events: HashMap<u32, Vec<u32>>,
+}
+
+impl HircIndex {
+    /// Map each WEM file ID to the first event whose actions target its sound,
+    /// either directly or through the sound's direct parent container.
+    pub fn file_events(&self) -> HashMap<u32, u32> {
+        // Object ID -> event that targets it
+        let mut targets: HashMap<u32, u32> = HashMap::new();
+        let mut event_ids: Vec<&u32> = self.events.keys().collect();
+        event_ids.sort();
+        for event_id in event_ids {
+            for action_id in &self.events[event_id] {
+                if let Some(&target) = self.actions.get(action_id) {
+                    targets.entry(target).or_insert(*event_id);
+                }
+            }
+        }
+
+        let mut sound_ids: Vec<&u32> = self.sounds.keys().collect();
+        sound_ids.sort();
+        let mut file_events = HashMap::new();
+        for sound_id in sound_ids {
+            let (file_id, parent_id) = self.sounds[sound_id];
+            let event = targets
+                .get(sound_id)
+                .or_else(|| (parent_id != 0).then(|| targets.get(&parent_id)).flatten());
+            if let Some(&event_id) = event {
+                file_events.entry(file_id).or_insert(event_id);
+            }
+        }
+
+        file_events
+    }
+}
+
+/// Parse the HIRC section of a BNK file and map WEM file IDs to event IDs.
+/// Returns an empty map if the bank has no HIRC section.
+pub fn parse_file_events(bnk_path: &Path) -> Result<HashMap<u32, u32>, KitharaError> {
+    let file = File::open(bnk_path)
+        .map_err(|e| KitharaError::Io(format!("Failed to open BNK {}: {}", bnk_path.display(), e)))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| KitharaError::Io(format!("Failed to read BNK size {}: {}", bnk_path.display(), e)))?
+        .len();
+    let mut reader = BufReader::new(file);
+    let mut version = 0;
+
+    let mut next_chunk: u64 = 0;
+    while let Some(chunk) = read_chunk_header(&mut reader, next_chunk) {
+        let chunk_start = reader.stream_position().unwrap_or(0);
+
+        match &chunk.magic {
+            b"BKHD" => {
+                version = reader
+                    .read_u32::<LittleEndian>()
+                    .map_err(|e| KitharaError::Decode(format!("Failed to read bank version: {}", e)))?;
+            }
+            b"HIRC" => {
+                let data = read_chunk_body(&mut reader, chunk.size, chunk_start, file_len, "HIRC")?;
+                return Ok(parse_hirc(&data, version)?.file_events());
+            }
+            _ => {}
+        }
+
+        next_chunk = chunk_start + chunk.size as u64;
+    }
+
+    Ok(HashMap::new())
+}
+
+/// Parse HIRC section contents. Object types other than sounds, actions and
+/// events are skipped; sounds whose parent can't be read keep a parent of 0.
+pub fn parse_hirc(data: &[u8], version: u32) -> Result<HircIndex, KitharaError> {
+    let mut cursor = Cursor::new(data);
+    let count = cursor
+        .read_u32::<LittleEndian>()
+        .map_err(|e| KitharaError::Decode(format!("Failed to read HIRC object count: {}", e)))?;
+
+    let mut index = HircIndex::default();
+    for _ in 0..count {
+        let Ok(object_type) = cursor.read_u8() else { break };
+        let Ok(size) = cursor.read_u32::<LittleEndian>() else { break };
+        let start = cursor.position() as usize;
+        let end = start + size as usize;
+        let Some(body) = data.get(start..end) else {
+            return Err(KitharaError::Decode(format!("HIRC object at offset {} overruns the section", start)));
+        };
+
+        let mut body = Cursor::new(body);
+        match object_type {
+            HIRC_SOUND => {
+                if let Some((id, file_id, parent_id)) = read_sound(&mut body, version) {
+                    index.sounds.insert(id, (file_id, parent_id));
+                }
+            }
+            HIRC_ACTION => {
+                if let Some((id, target)) = read_action(&mut body) {
+                    index.actions.insert(id, target);
+                }
+            }
+            HIRC_EVENT => {
+                if let Some((id, actions)) = read_event(&mut body, version) {
+                    index.events.insert(id, actions);
+                }
+            }
+            _ => {}
+        }
+
+        cursor.set_position(end as u64);
+    }
+
+    Ok(index)
+}
+
+/// Read a Sound object: its ID, source file ID, and direct parent ID
+fn read_sound(body: &mut Cursor<&[u8]>, version: u32) -> Option<(u32, u32, u32)> {
+    let id = body.read_u32::<LittleEndian>().ok()?;
+    let plugin_id = body.read_u32::<LittleEndian>().ok()?;
+
+    let file_id = if version <= 88 {
+        // Older banks: u32 stream type, u32 file ID, u32 source ID
+        body.read_u32::<LittleEndian>().ok()?;
+        let file_id = body.read_u32::<LittleEndian>().ok()?;
+        body.read_u32::<LittleEndian>().ok()?;
+        file_id
+    } else {
+        // u8 stream type, u32 source ID, u32 in-memory size, u8 source bits
+        body.read_u8().ok()?;
+        let file_id = body.read_u32::<LittleEndian>().ok()?;
+        body.read_u32::<LittleEndian>().ok()?;
+        body.read_u8().ok()?;
+        file_id
+    };
+
+    // Parent lookup is best-effort; without it the sound can still match
+    // events that target it directly.
+    let parent_id = read_parent_id(body, plugin_id, version).unwrap_or(0);
+    Some((id, file_id, parent_id))
+}
+
+/// Skip a Sound's plugin params and initial FX to read its DirectParentID
+fn read_parent_id(body: &mut Cursor<&[u8]>, plugin_id: u32, version: u32) -> Option<u32> {
+    // Source plugins carry an inline parameter block
+    if plugin_id & 0x0F == 2 {
+        let size = body.read_u32::<LittleEndian>().ok()?;
+        body.seek(SeekFrom::Current(size as i64)).ok()?;
+    }
+
+    // NodeInitialFxParams: override flag, FX count, bypass bits, then 7 bytes per FX
+    body.read_u8().ok()?;
+    let fx_count = body.read_u8().ok()?;
+    if fx_count > 0 {
+        body.read_u8().ok()?;
+        body.seek(SeekFrom::Current(fx_count as i64 * 7)).ok()?;
+    }
+
+    // NodeInitialMetadataParams (newer banks): override flag, then 6 bytes per entry
+    if version > 136 {
+        body.read_u8().ok()?;
+        let metadata_count = body.read_u8().ok()?;
+        body.seek(SeekFrom::Current(metadata_count as i64 * 6)).ok()?;
+    }
+
+    if version > 122 {
+        // bOverrideAttachmentParams
+        body.read_u8().ok()?;
+    }
+
+    // OverrideBusId, then DirectParentID
+    body.read_u32::<LittleEndian>().ok()?;
+    body.read_u32::<LittleEndian>().ok()
+}
+
+/// Read an Action object: its ID and target object ID
+fn read_action(body: &mut Cursor<&[u8]>) -> Option<(u32, u32)> {
+    let id = body.read_u32::<LittleEndian>().ok()?;
+    body.read_u16::<LittleEndian>().ok()?; // action type
+    let target = body.read_u32::<LittleEndian>().ok()?;
+    Some((id, target))
+}
+
+/// Read an Event object: its ID and action IDs
+fn read_event(body: &mut Cursor<&[u8]>, version: u32) -> Option<(u32, Vec<u32>)> {
+    let id = body.read_u32::<LittleEndian>().ok()?;
+    let count = if version <= 122 {
+        body.read_u32::<LittleEndian>().ok()?
+    } else {
+        read_var_u32(body)?
+    };
+    let actions = (0..count)
+        .map(|_| body.read_u32::<LittleEndian>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some((id, actions))
+}
+
+/// Read a 7-bit variable-length integer (high bit set means more bytes follow)
+fn read_var_u32(body: &mut Cursor<&[u8]>) -> Option<u32> {
+    let mut value: u32 = 0;
+    for _ in 0..5 {
+        let byte = body.read_u8().ok()?;
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +560,230 @@ mod tests {
         assert_eq!(&magic, b"BKHD");
         assert_eq!(size, 16);
     }
+
+    /// Appends one HIRC object (type, size, body) to `out`
+    fn push_object(out: &mut Vec<u8>, object_type: u8, body: &[u8]) {
+        out.push(object_type);
+        out.extend((body.len() as u32).to_le_bytes());
+        out.extend(body);
+    }
+
+    fn sound_body(id: u32, file_id: u32, parent_id: u32) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend(id.to_le_bytes());
+        body.extend(0x0004_0001u32.to_le_bytes()); // Vorbis codec
+        body.push(0); // stream type
+        body.extend(file_id.to_le_bytes());
+        body.extend(1000u32.to_le_bytes()); // in-memory size
+        body.push(0); // source bits
+        body.extend([0, 0]); // no FX
+        body.push(0); // override attachment params
+        body.extend(0u32.to_le_bytes()); // override bus
+        body.extend(parent_id.to_le_bytes());
+        body
+    }
+
+    #[test]
+    fn test_hirc_maps_files_to_events() {
+        let mut data = Vec::new();
+        data.extend(5u32.to_le_bytes());
+        // Sound 10 plays file 500 directly; sound 11 (file 501) sits under container 99
+        push_object(&mut data, HIRC_SOUND, &sound_body(10, 500, 0));
+        push_object(&mut data, HIRC_SOUND, &sound_body(11, 501, 99));
+
+        let mut action = Vec::new();
+        action.extend(20u32.to_le_bytes());
+        action.extend(0x0403u16.to_le_bytes()); // Play
+        action.extend(10u32.to_le_bytes());
+        push_object(&mut data, HIRC_ACTION, &action);
+
+        let mut container_action = Vec::new();
+        container_action.extend(21u32.to_le_bytes());
+        container_action.extend(0x0403u16.to_le_bytes());
+        container_action.extend(99u32.to_le_bytes());
+        push_object(&mut data, HIRC_ACTION, &container_action);
+
+        let mut event = Vec::new();
+        event.extend(30u32.to_le_bytes());
+        event.push(2); // var-length action count
+        event.extend(20u32.to_le_bytes());
+        event.extend(21u32.to_le_bytes());
+        push_object(&mut data, HIRC_EVENT, &event);
+
+        let index = parse_hirc(&data, 134).unwrap();
+        let file_events = index.file_events();
+        assert_eq!(file_events.get(&500), Some(&30));
+        assert_eq!(file_events.get(&501), Some(&30));
+    }
+
+    /// Writes a BNK made of the given (magic, body) chunks to a temp file
+    fn write_bnk(name: &str, chunks: &[(&[u8; 4], Vec<u8>)]) -> PathBuf {
+        let mut bytes = Vec::new();
+        for (magic, body) in chunks {
+            bytes.extend(*magic);
+            bytes.extend((body.len() as u32).to_le_bytes());
+            bytes.extend(body);
+        }
+        let path = std::env::temp_dir().join(format!("kithara-test-{}-{}.bnk", std::process::id(), name));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn bkhd(version: u32) -> Vec<u8> {
+        let mut body = version.to_le_bytes().to_vec();
+        body.extend(1234u32.to_le_bytes()); // bank ID
+        body
+    }
+
+    #[test]
+    fn test_parse_bnk_rejects_unsupported_version() {
+        let path = write_bnk("old-version", &[(b"BKHD", bkhd(26)), (b"DIDX", vec![0; 12])]);
+        let err = parse_bnk(&path).unwrap_err().to_string();
+        std::fs::remove_file(&path).ok();
+        assert!(err.contains("Unsupported BNK version 26"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_bnk_rejects_misaligned_didx() {
+        let path = write_bnk("bad-didx", &[(b"BKHD", bkhd(134)), (b"DIDX", vec![0; 14])]);
+        let err = parse_bnk(&path).unwrap_err().to_string();
+        std::fs::remove_file(&path).ok();
+        assert!(err.contains("bank version 134"), "{}", err);
+        assert!(err.contains("size 14"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_bnk_rejects_truncated_data() {
+        let mut didx = 500u32.to_le_bytes().to_vec();
+        didx.extend(0u32.to_le_bytes());
+        didx.extend(64u32.to_le_bytes());
+
+        // DATA declares fewer bytes than DIDX references
+        let path = write_bnk("empty-data", &[(b"BKHD", bkhd(134)), (b"DIDX", didx.clone()), (b"DATA", Vec::new())]);
+        let err = parse_bnk(&path).unwrap_err().to_string();
+        std::fs::remove_file(&path).ok();
+        assert!(err.contains("Truncated DATA section"), "{}", err);
+        assert!(err.contains("kithara-test-"), "{}", err);
+        assert!(err.contains("WEM 500 needs 64 bytes but only 0"), "{}", err);
+
+        // DATA declares enough, but the file ends early
+        let path = write_bnk("cut-data", &[(b"BKHD", bkhd(134)), (b"DIDX", didx), (b"DATA", vec![0; 64])]);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 16]).unwrap();
+        let err = parse_bnk(&path).unwrap_err().to_string();
+        std::fs::remove_file(&path).ok();
+        assert!(err.contains("WEM 500 needs 64 bytes but only 48"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_bnk_skips_chunk_padding() {
+        let mut didx = 500u32.to_le_bytes().to_vec();
+        didx.extend(0u32.to_le_bytes());
+        didx.extend(4u32.to_le_bytes());
+
+        // Pad bytes the chunk sizes don't count, including a partial run after the final DATA
+        let mut bytes = Vec::new();
+        for (magic, body, padding) in [(b"BKHD", bkhd(134), 7), (b"DIDX", didx, 4), (b"DATA", vec![1, 2, 3, 4], 3)] {
+            bytes.extend(magic);
+            bytes.extend((body.len() as u32).to_le_bytes());
+            bytes.extend(body);
+            bytes.extend(vec![0; padding]);
+        }
+        bytes.truncate(bytes.len() - 1);
+        let path = std::env::temp_dir().join(format!("kithara-test-{}-padded.bnk", std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        let contents = parse_bnk(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(contents.bank_id, Some(1234));
+        assert_eq!(contents.entries.len(), 1);
+        assert_eq!(contents.entries[0].file_id, 500);
+        assert_eq!(contents.entries[0].data_size, 4);
+    }
+
+    #[test]
+    fn test_extract_wem_bytes_rejects_out_of_bounds_entry() {
+        let entry = WemEntry {
+            file_id: 777,
+            offset: 900,
+            size: 200,
+            bnk_path: PathBuf::from("/nonexistent.bnk"),
+            data_offset: 64,
+            data_size: 1000,
+            bank_name: None,
+        };
+        let err = extract_wem_bytes(&entry, Path::new("/nonexistent.wem")).unwrap_err().to_string();
+        assert!(err.contains("WEM 777 lies outside the DATA section"), "{}", err);
+    }
+
+    fn stid(banks: &[(u32, &str)]) -> Vec<u8> {
+        let mut body = 1u32.to_le_bytes().to_vec(); // UTF-8 strings
+        body.extend((banks.len() as u32).to_le_bytes());
+        for (id, name) in banks {
+            body.extend(id.to_le_bytes());
+            body.push(name.len() as u8);
+            body.extend(name.as_bytes());
+        }
+        body
+    }
+
+    #[test]
+    fn test_parse_bnk_reads_bank_names() {
+        let mut didx = 500u32.to_le_bytes().to_vec();
+        didx.extend(0u32.to_le_bytes());
+        didx.extend(4u32.to_le_bytes());
+        let path = write_bnk(
+            "stid",
+            &[
+                (b"BKHD", bkhd(134)),
+                (b"DIDX", didx),
+                (b"DATA", vec![1, 2, 3, 4]),
+                (b"STID", stid(&[(1234, "Units_Archer"), (99, "Init")])),
+            ],
+        );
+        let contents = parse_bnk(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(contents.bank_id, Some(1234));
+        assert_eq!(contents.bank_names.get(&99).map(String::as_str), Some("Init"));
+        assert_eq!(contents.bank_name(), Some("Units_Archer"));
+        assert_eq!(contents.entries.len(), 1);
+        assert_eq!(contents.entries[0].bank_name.as_deref(), Some("Units_Archer"));
+
+        let mut truncated = stid(&[(1234, "Units_Archer")]);
+        truncated.truncate(truncated.len() - 3);
+        let err = parse_stid(&truncated).unwrap_err().to_string();
+        assert!(err.contains("bank 1234"), "{}", err);
+    }
+
+    #[test]
+    fn test_oversized_chunks_are_rejected() {
+        // Chunk sizes near u32::MAX must fail cleanly instead of allocating them
+        let oversize = |name: &str, magic: &[u8; 4]| {
+            let path = write_bnk(name, &[(b"BKHD", bkhd(134)), (magic, vec![0; 8])]);
+            let mut bytes = std::fs::read(&path).unwrap();
+            let size_at = bytes.len() - 12;
+            bytes[size_at..size_at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            std::fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        let path = oversize("huge-stid", b"STID");
+        let err = parse_bnk(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(err, KitharaError::Decode(_)), "{:?}", err);
+        assert!(err.to_string().contains("STID section claims 4294967295 bytes but only 8 remain"), "{}", err);
+
+        let path = oversize("huge-hirc", b"HIRC");
+        let err = parse_file_events(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(err.to_string().contains("HIRC section claims 4294967295 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_read_var_u32() {
+        assert_eq!(read_var_u32(&mut Cursor::new(&[0x05u8][..])), Some(5));
+        assert_eq!(read_var_u32(&mut Cursor::new(&[0x81u8, 0x00][..])), Some(128));
+        assert_eq!(read_var_u32(&mut Cursor::new(&[0x80u8][..])), None);
+    }
 }
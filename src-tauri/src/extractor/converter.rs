@@ -1,9 +1,12 @@
-//! Audio conversion pipeline: WEM -> WAV -> OGG
+//! Audio conversion pipeline: WEM -> WAV -> OGG/FLAC/MP3
 //! Uses vgmstream-cli and ffmpeg.
 //! - macOS: System binaries via Homebrew (brew install vgmstream ffmpeg)
 //! - Linux: Sidecar for vgmstream-cli, system ffmpeg (apt dependency)
 //! - Windows: Bundled resources (exe + DLLs)
 
+use crate::error::KitharaError;
+use crate::models::{DependencyStatus, OutputFormat};
+use std::io::Read;
 use std::path::Path;
 use tauri::AppHandle;
 
@@ -19,20 +22,64 @@ use std::os::windows::process::CommandExt;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-/// Convert WEM file to OGG via two-step pipeline
-pub async fn convert_wem_to_ogg(
+/// Loudness normalization target: -16 LUFS integrated, -1.5 dBTP true peak (EBU R128)
+const LOUDNORM_FILTER: &str = "loudnorm=I=-16:TP=-1.5:LRA=11";
+
+/// loudnorm upsamples to 192 kHz internally, so normalized output is resampled to this
+const NORMALIZED_SAMPLE_RATE: &str = "48000";
+
+/// Level below which leading/trailing audio counts as silence when trimming
+const SILENCE_THRESHOLD: &str = "-50dB";
+
+/// Encoding settings applied after vgmstream decodes a WEM
+#[derive(Debug, Clone, Default)]
+pub struct EncodeOptions {
+    pub format: OutputFormat,
+    /// Normalize loudness with ffmpeg's loudnorm filter
+    pub normalize: bool,
+    /// Strip silence from both ends with ffmpeg's silenceremove filter
+    pub trim_silence: bool,
+}
+
+impl EncodeOptions {
+    /// ffmpeg `-af` filters, in the order they're applied
+    fn audio_filters(&self) -> Vec<String> {
+        let mut filters = Vec::new();
+        if self.trim_silence {
+            // silenceremove only trims the start reliably, so trim, reverse, trim, reverse
+            let trim_start = format!("silenceremove=start_periods=1:start_threshold={}", SILENCE_THRESHOLD);
+            filters.extend([trim_start.clone(), "areverse".into(), trim_start, "areverse".into()]);
+        }
+        if self.normalize {
+            filters.push(LOUDNORM_FILTER.to_string());
+        }
+        filters
+    }
+}
+
+/// Convert WEM file to the requested format via two-step pipeline.
+/// Plain WAV output is written by vgmstream directly and skips ffmpeg;
+/// WAV with filters gets a separate ffmpeg pass.
+/// `tags` are written as ffmpeg `-metadata` key/value pairs (e.g. title, album).
+pub async fn convert_wem(
     app: &AppHandle,
     wem_path: &Path,
-    ogg_path: &Path,
-) -> Result<(), String> {
+    output_path: &Path,
+    encode: &EncodeOptions,
+    tags: &[(&str, String)],
+) -> Result<(), KitharaError> {
+    if encode.format == OutputFormat::Wav && encode.audio_filters().is_empty() {
+        return convert_wem_to_wav(app, wem_path, output_path).await;
+    }
+
     // Create intermediate WAV path
     let wav_path = wem_path.with_extension("wav");
 
     // Step 1: WEM -> WAV using vgmstream-cli
     convert_wem_to_wav(app, wem_path, &wav_path).await?;
 
-    // Step 2: WAV -> OGG using ffmpeg
-    let result = convert_wav_to_ogg(app, &wav_path, ogg_path).await;
+    // Step 2: WAV -> target format using ffmpeg
+    let result = convert_wav(app, &wav_path, output_path, encode, tags).await;
 
     // Cleanup intermediate WAV regardless of result
     let _ = std::fs::remove_file(&wav_path);
@@ -40,6 +87,125 @@ pub async fn convert_wem_to_ogg(
     result
 }
 
+/// ffmpeg encoder arguments for the given output format
+fn ffmpeg_codec_args(format: OutputFormat) -> Vec<String> {
+    match format {
+        OutputFormat::OggVorbis { quality } => vec![
+            "-c:a".into(),
+            "libvorbis".into(),
+            "-q:a".into(),
+            quality.min(10).to_string(),
+        ],
+        OutputFormat::Flac => vec!["-c:a".into(), "flac".into()],
+        OutputFormat::Mp3 { bitrate_kbps } => vec![
+            "-c:a".into(),
+            "libmp3lame".into(),
+            "-b:a".into(),
+            format!("{}k", bitrate_kbps.clamp(32, 320)),
+        ],
+        OutputFormat::Wav => vec!["-c:a".into(), "pcm_s16le".into()],
+    }
+}
+
+/// Full ffmpeg argument list for encoding `wav_str` into `output_str`
+fn ffmpeg_args(
+    wav_str: &str,
+    output_str: &str,
+    encode: &EncodeOptions,
+    tags: &[(&str, String)],
+) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into(), "-i".into(), wav_str.into()];
+    let filters = encode.audio_filters();
+    if !filters.is_empty() {
+        args.extend(["-af".into(), filters.join(",")]);
+    }
+    if encode.normalize {
+        args.extend(["-ar".into(), NORMALIZED_SAMPLE_RATE.into()]);
+    }
+    args.extend(ffmpeg_codec_args(encode.format));
+    // Each tag is its own argument, so titles need no quoting or escaping
+    for (key, value) in tags {
+        args.extend(["-metadata".into(), format!("{}={}", key, value)]);
+    }
+    args.extend(["-loglevel".into(), "error".into(), output_str.into()]);
+    args
+}
+
+/// Extra vgmstream-cli argument sets tried in order. The first (empty) set is the
+/// default invocation; the rest recover WEMs that fail with default options.
+const VGMSTREAM_ARG_SETS: &[&[&str]] = &[
+    &[],
+    // Ignore loop points (some looped sources fail to render)
+    &["-i"],
+    // Force the first subsong for multi-stream files
+    &["-s", "1"],
+    &["-i", "-s", "1"],
+];
+
+/// Smallest WAV vgmstream can write: a canonical 44-byte header plus at least one sample
+const MIN_WAV_BYTES: u64 = 46;
+
+/// Whether `header` begins with a `RIFF....WAVE` file header
+fn is_riff_wave(header: &[u8]) -> bool {
+    header.len() >= 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WAVE"
+}
+
+/// Rejects empty or non-RIFF vgmstream output, which would otherwise encode to a broken file
+fn validate_wav(wem_path: &Path, wav_path: &Path) -> Result<(), KitharaError> {
+    let file_id = wem_path.file_stem().unwrap_or_default().to_string_lossy();
+    let len = std::fs::metadata(wav_path)
+        .map_err(|e| KitharaError::Conversion(format!("Failed to read WAV for WEM {}: {}", file_id, e)))?
+        .len();
+    if len < MIN_WAV_BYTES {
+        return Err(KitharaError::Conversion(format!("vgmstream-cli wrote a {} byte WAV for WEM {}", len, file_id)));
+    }
+
+    let mut header = [0u8; 12];
+    std::fs::File::open(wav_path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|e| KitharaError::Conversion(format!("Failed to read WAV for WEM {}: {}", file_id, e)))?;
+    if !is_riff_wave(&header) {
+        return Err(KitharaError::Conversion(format!(
+            "vgmstream-cli wrote an invalid WAV (no RIFF/WAVE header) for WEM {}",
+            file_id
+        )));
+    }
+    Ok(())
+}
+
+/// Convert WEM to WAV with vgmstream-cli, retrying with alternate options on failure
+async fn convert_wem_to_wav(
+    app: &AppHandle,
+    wem_path: &Path,
+    wav_path: &Path,
+) -> Result<(), KitharaError> {
+    let mut first_error = None;
+
+    for extra_args in VGMSTREAM_ARG_SETS {
+        let result = run_vgmstream(app, wem_path, wav_path, extra_args)
+            .await
+            .and_then(|()| validate_wav(wem_path, wav_path));
+        match result {
+            Ok(()) => {
+                if first_error.is_some() {
+                    println!(
+                        "vgmstream-cli succeeded for {} with options {:?}",
+                        wem_path.display(),
+                        extra_args
+                    );
+                }
+                return Ok(());
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(wav_path);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    Err(first_error.unwrap_or_else(|| KitharaError::Conversion("vgmstream-cli failed".into())))
+}
+
 // ============================================================================
 // macOS implementation: system binaries via Homebrew
 // ============================================================================
@@ -50,86 +216,89 @@ const HOMEBREW_VGMSTREAM: &str = "/opt/homebrew/bin/vgmstream-cli";
 const HOMEBREW_FFMPEG: &str = "/opt/homebrew/bin/ffmpeg";
 
 #[cfg(target_os = "macos")]
-async fn convert_wem_to_wav(
+async fn run_vgmstream(
     _app: &AppHandle,
     wem_path: &Path,
     wav_path: &Path,
-) -> Result<(), String> {
+    extra_args: &[&str],
+) -> Result<(), KitharaError> {
     let wem_str = wem_path
         .to_str()
-        .ok_or_else(|| "Invalid WEM path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WEM path".into()))?;
     let wav_str = wav_path
         .to_str()
-        .ok_or_else(|| "Invalid WAV path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WAV path".into()))?;
 
     let output = tokio::process::Command::new(HOMEBREW_VGMSTREAM)
+        .args(extra_args)
         .args(["-o", wav_str, wem_str])
         .output()
         .await
-        .map_err(|e| format!("Failed to run vgmstream-cli. Please install it with: brew install vgmstream\nError: {}", e))?;
+        .map_err(|e| {
+            KitharaError::Conversion(format!(
+                "Failed to run vgmstream-cli. Please install it with: brew install vgmstream\nError: {}",
+                e
+            ))
+        })?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "vgmstream-cli failed (exit {}): {} {}",
             output.status.code().unwrap_or(-1),
             stderr,
             stdout
-        ));
+        )));
     }
 
     if !wav_path.exists() {
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "vgmstream-cli did not create output file: {}",
             wav_str
-        ));
+        )));
     }
 
     Ok(())
 }
 
 #[cfg(target_os = "macos")]
-async fn convert_wav_to_ogg(
+async fn convert_wav(
     _app: &AppHandle,
     wav_path: &Path,
-    ogg_path: &Path,
-) -> Result<(), String> {
+    output_path: &Path,
+    encode: &EncodeOptions,
+    tags: &[(&str, String)],
+) -> Result<(), KitharaError> {
     let wav_str = wav_path
         .to_str()
-        .ok_or_else(|| "Invalid WAV path".to_string())?;
-    let ogg_str = ogg_path
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WAV path".into()))?;
+    let output_str = output_path
         .to_str()
-        .ok_or_else(|| "Invalid OGG path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid output path".into()))?;
 
     let output = tokio::process::Command::new(HOMEBREW_FFMPEG)
-        .args([
-            "-y",
-            "-i",
-            wav_str,
-            "-c:a",
-            "libvorbis",
-            "-q:a",
-            "4",
-            "-loglevel",
-            "error",
-            ogg_str,
-        ])
+        .args(ffmpeg_args(wav_str, output_str, encode, tags))
         .output()
         .await
-        .map_err(|e| format!("Failed to run ffmpeg. Please install it with: brew install ffmpeg\nError: {}", e))?;
+        .map_err(|e| {
+            KitharaError::Conversion(format!(
+                "Failed to run ffmpeg. Please install it with: brew install ffmpeg\nError: {}",
+                e
+            ))
+        })?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "ffmpeg failed (exit {}): {}",
             output.status.code().unwrap_or(-1),
             stderr
-        ));
+        )));
     }
 
-    if !ogg_path.exists() {
-        return Err(format!("ffmpeg did not create output file: {}", ogg_str));
+    if !output_path.exists() {
+        return Err(KitharaError::Conversion(format!("ffmpeg did not create output file: {}", output_str)));
     }
 
     Ok(())
@@ -140,89 +309,82 @@ async fn convert_wav_to_ogg(
 // ============================================================================
 
 #[cfg(target_os = "linux")]
-async fn convert_wem_to_wav(
+async fn run_vgmstream(
     app: &AppHandle,
     wem_path: &Path,
     wav_path: &Path,
-) -> Result<(), String> {
+    extra_args: &[&str],
+) -> Result<(), KitharaError> {
     let wem_str = wem_path
         .to_str()
-        .ok_or_else(|| "Invalid WEM path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WEM path".into()))?;
     let wav_str = wav_path
         .to_str()
-        .ok_or_else(|| "Invalid WAV path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WAV path".into()))?;
 
     let output = app
         .shell()
         .sidecar("vgmstream-cli")
-        .map_err(|e| format!("Failed to get vgmstream-cli sidecar: {}", e))?
+        .map_err(|e| KitharaError::Conversion(format!("Failed to get vgmstream-cli sidecar: {}", e)))?
+        .args(extra_args)
         .args(["-o", wav_str, wem_str])
         .output()
         .await
-        .map_err(|e| format!("Failed to run vgmstream-cli: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to run vgmstream-cli: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "vgmstream-cli failed (exit {}): {} {}",
             output.status.code().unwrap_or(-1),
             stderr,
             stdout
-        ));
+        )));
     }
 
     if !wav_path.exists() {
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "vgmstream-cli did not create output file: {}",
             wav_str
-        ));
+        )));
     }
 
     Ok(())
 }
 
 #[cfg(target_os = "linux")]
-async fn convert_wav_to_ogg(
+async fn convert_wav(
     _app: &AppHandle,
     wav_path: &Path,
-    ogg_path: &Path,
-) -> Result<(), String> {
+    output_path: &Path,
+    encode: &EncodeOptions,
+    tags: &[(&str, String)],
+) -> Result<(), KitharaError> {
     let wav_str = wav_path
         .to_str()
-        .ok_or_else(|| "Invalid WAV path".to_string())?;
-    let ogg_str = ogg_path
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WAV path".into()))?;
+    let output_str = output_path
         .to_str()
-        .ok_or_else(|| "Invalid OGG path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid output path".into()))?;
 
     let output = tokio::process::Command::new("ffmpeg")
-        .args([
-            "-y",
-            "-i",
-            wav_str,
-            "-c:a",
-            "libvorbis",
-            "-q:a",
-            "4",
-            "-loglevel",
-            "error",
-            ogg_str,
-        ])
+        .args(ffmpeg_args(wav_str, output_str, encode, tags))
         .output()
         .await
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to run ffmpeg: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "ffmpeg failed (exit {}): {}",
             output.status.code().unwrap_or(-1),
             stderr
-        ));
+        )));
     }
 
-    if !ogg_path.exists() {
-        return Err(format!("ffmpeg did not create output file: {}", ogg_str));
+    if !output_path.exists() {
+        return Err(KitharaError::Conversion(format!("ffmpeg did not create output file: {}", output_str)));
     }
 
     Ok(())
@@ -233,15 +395,16 @@ async fn convert_wav_to_ogg(
 // ============================================================================
 
 #[cfg(target_os = "windows")]
-async fn convert_wem_to_wav(
+async fn run_vgmstream(
     app: &AppHandle,
     wem_path: &Path,
     wav_path: &Path,
-) -> Result<(), String> {
+    extra_args: &[&str],
+) -> Result<(), KitharaError> {
     let resource_dir = app
         .path()
         .resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to get resource dir: {}", e)))?;
 
     let vgmstream_exe = resource_dir
         .join("resources-win")
@@ -249,57 +412,60 @@ async fn convert_wem_to_wav(
         .join("vgmstream-cli.exe");
 
     if !vgmstream_exe.exists() {
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "vgmstream-cli.exe not found at: {}",
             vgmstream_exe.display()
-        ));
+        )));
     }
 
     let wem_str = wem_path
         .to_str()
-        .ok_or_else(|| "Invalid WEM path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WEM path".into()))?;
     let wav_str = wav_path
         .to_str()
-        .ok_or_else(|| "Invalid WAV path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WAV path".into()))?;
 
     let output = tokio::process::Command::new(&vgmstream_exe)
+        .args(extra_args)
         .args(["-o", wav_str, wem_str])
         .creation_flags(CREATE_NO_WINDOW)
         .output()
         .await
-        .map_err(|e| format!("Failed to run vgmstream-cli: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to run vgmstream-cli: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "vgmstream-cli failed (exit {}): {} {}",
             output.status.code().unwrap_or(-1),
             stderr,
             stdout
-        ));
+        )));
     }
 
     if !wav_path.exists() {
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "vgmstream-cli did not create output file: {}",
             wav_str
-        ));
+        )));
     }
 
     Ok(())
 }
 
 #[cfg(target_os = "windows")]
-async fn convert_wav_to_ogg(
+async fn convert_wav(
     app: &AppHandle,
     wav_path: &Path,
-    ogg_path: &Path,
-) -> Result<(), String> {
+    output_path: &Path,
+    encode: &EncodeOptions,
+    tags: &[(&str, String)],
+) -> Result<(), KitharaError> {
     let resource_dir = app
         .path()
         .resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to get resource dir: {}", e)))?;
 
     let ffmpeg_exe = resource_dir
         .join("resources-win")
@@ -307,48 +473,37 @@ async fn convert_wav_to_ogg(
         .join("ffmpeg.exe");
 
     if !ffmpeg_exe.exists() {
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "ffmpeg.exe not found at: {}",
             ffmpeg_exe.display()
-        ));
+        )));
     }
 
     let wav_str = wav_path
         .to_str()
-        .ok_or_else(|| "Invalid WAV path".to_string())?;
-    let ogg_str = ogg_path
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid WAV path".into()))?;
+    let output_str = output_path
         .to_str()
-        .ok_or_else(|| "Invalid OGG path".to_string())?;
+        .ok_or_else(|| KitharaError::InvalidInput("Invalid output path".into()))?;
 
     let output = tokio::process::Command::new(&ffmpeg_exe)
-        .args([
-            "-y",
-            "-i",
-            wav_str,
-            "-c:a",
-            "libvorbis",
-            "-q:a",
-            "4",
-            "-loglevel",
-            "error",
-            ogg_str,
-        ])
+        .args(ffmpeg_args(wav_str, output_str, encode, tags))
         .creation_flags(CREATE_NO_WINDOW)
         .output()
         .await
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to run ffmpeg: {}", e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!(
+        return Err(KitharaError::Conversion(format!(
             "ffmpeg failed (exit {}): {}",
             output.status.code().unwrap_or(-1),
             stderr
-        ));
+        )));
     }
 
-    if !ogg_path.exists() {
-        return Err(format!("ffmpeg did not create output file: {}", ogg_str));
+    if !output_path.exists() {
+        return Err(KitharaError::Conversion(format!("ffmpeg did not create output file: {}", output_str)));
     }
 
     Ok(())
@@ -385,99 +540,249 @@ pub async fn check_audio_dependencies() -> Vec<String> {
     Vec::new()
 }
 
-// ============================================================================
-// Duration detection using ffprobe
-// ============================================================================
+/// Probe for vgmstream-cli and ffmpeg where this platform expects them,
+/// running `ffmpeg -version` to confirm it actually starts.
+pub async fn check_dependencies(app: &AppHandle) -> DependencyStatus {
+    let vgmstream = vgmstream_available(app);
+    let ffmpeg = ffmpeg_available(app).await;
+
+    let mut missing = Vec::new();
+    if !vgmstream {
+        missing.push("vgmstream".to_string());
+    }
+    if !ffmpeg {
+        missing.push("ffmpeg".to_string());
+    }
+
+    DependencyStatus {
+        vgmstream,
+        ffmpeg,
+        missing,
+    }
+}
+
+/// A single actionable error describing missing tools, or None if all are present
+pub fn missing_dependencies_error(status: &DependencyStatus) -> Option<String> {
+    if status.missing.is_empty() {
+        return None;
+    }
+
+    let hint = if cfg!(target_os = "macos") {
+        format!("Install with: brew install {}", status.missing.join(" "))
+    } else if cfg!(target_os = "linux") && status.vgmstream {
+        "Install ffmpeg with your package manager (e.g. sudo apt install ffmpeg)".to_string()
+    } else {
+        "The bundled audio tools are missing; please reinstall Kithara".to_string()
+    };
+
+    Some(format!(
+        "Audio extraction requires {}, which could not be found. {}",
+        status.missing.join(" and "),
+        hint
+    ))
+}
 
 #[cfg(target_os = "macos")]
-const HOMEBREW_FFPROBE: &str = "/opt/homebrew/bin/ffprobe";
+fn vgmstream_available(_app: &AppHandle) -> bool {
+    Path::new(HOMEBREW_VGMSTREAM).exists()
+}
+
+#[cfg(target_os = "linux")]
+fn vgmstream_available(app: &AppHandle) -> bool {
+    app.shell().sidecar("vgmstream-cli").is_ok()
+}
+
+#[cfg(target_os = "windows")]
+fn vgmstream_available(app: &AppHandle) -> bool {
+    app.path()
+        .resource_dir()
+        .map(|dir| dir.join("resources-win").join("vgmstream").join("vgmstream-cli.exe").exists())
+        .unwrap_or(false)
+}
 
-/// Get the duration of an audio file in seconds using ffprobe
 #[cfg(target_os = "macos")]
-pub async fn get_audio_duration(audio_path: &Path) -> Result<f64, String> {
-    let path_str = audio_path
-        .to_str()
-        .ok_or_else(|| "Invalid audio path".to_string())?;
+async fn ffmpeg_available(_app: &AppHandle) -> bool {
+    tokio::process::Command::new(HOMEBREW_FFMPEG)
+        .arg("-version")
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
 
-    let output = tokio::process::Command::new(HOMEBREW_FFPROBE)
-        .args([
-            "-v", "error",
-            "-show_entries", "format=duration",
-            "-of", "default=noprint_wrappers=1:nokey=1",
-            path_str,
-        ])
+#[cfg(target_os = "linux")]
+async fn ffmpeg_available(_app: &AppHandle) -> bool {
+    tokio::process::Command::new("ffmpeg")
+        .arg("-version")
         .output()
         .await
-        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(target_os = "windows")]
+async fn ffmpeg_available(app: &AppHandle) -> bool {
+    let Ok(resource_dir) = app.path().resource_dir() else {
+        return false;
+    };
+    let ffmpeg_exe = resource_dir.join("resources-win").join("ffmpeg").join("ffmpeg.exe");
+
+    tokio::process::Command::new(&ffmpeg_exe)
+        .arg("-version")
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+// ============================================================================
+// Duration detection using ffprobe
+// ============================================================================
+
+/// ffprobe arguments that print only the container duration in seconds
+const FFPROBE_DURATION_ARGS: &[&str] = &[
+    "-v", "error",
+    "-show_entries", "format=duration",
+    "-of", "default=noprint_wrappers=1:nokey=1",
+];
 
+/// Reads the duration from ffprobe's output, failing with the reason if there is none
+/// (e.g. ffprobe errored, or printed "N/A" for a file it couldn't time).
+fn parse_ffprobe_duration(audio_path: &Path, output: &std::process::Output) -> Result<f64, KitharaError> {
     if !output.status.success() {
-        return Err("ffprobe failed to get duration".to_string());
+        return Err(KitharaError::Conversion(format!(
+            "ffprobe failed on {} (exit {}): {}",
+            audio_path.display(),
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
     }
 
-    let duration_str = String::from_utf8_lossy(&output.stdout);
-    duration_str
-        .trim()
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = stdout.trim();
+    value
         .parse::<f64>()
-        .map_err(|e| format!("Failed to parse duration: {}", e))
+        .ok()
+        .filter(|duration| duration.is_finite() && *duration >= 0.0)
+        .ok_or_else(|| {
+            KitharaError::Conversion(format!(
+                "ffprobe reported no duration for {} (got {:?})",
+                audio_path.display(),
+                value
+            ))
+        })
 }
 
-/// Get the duration of an audio file in seconds using ffprobe (Linux)
-#[cfg(target_os = "linux")]
-pub async fn get_audio_duration(audio_path: &Path) -> Result<f64, String> {
-    let path_str = audio_path
-        .to_str()
-        .ok_or_else(|| "Invalid audio path".to_string())?;
+#[cfg(target_os = "macos")]
+const HOMEBREW_FFPROBE: &str = "/opt/homebrew/bin/ffprobe";
 
-    let output = tokio::process::Command::new("ffprobe")
-        .args([
-            "-v", "error",
-            "-show_entries", "format=duration",
-            "-of", "default=noprint_wrappers=1:nokey=1",
-            path_str,
-        ])
+/// Get the duration of an audio file in seconds using Homebrew's ffprobe
+#[cfg(target_os = "macos")]
+pub async fn get_audio_duration(_app: &AppHandle, audio_path: &Path) -> Result<f64, KitharaError> {
+    let output = tokio::process::Command::new(HOMEBREW_FFPROBE)
+        .args(FFPROBE_DURATION_ARGS)
+        .arg(audio_path)
         .output()
         .await
-        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to run ffprobe: {}", e)))?;
 
-    if !output.status.success() {
-        return Err("ffprobe failed to get duration".to_string());
-    }
+    parse_ffprobe_duration(audio_path, &output)
+}
 
-    let duration_str = String::from_utf8_lossy(&output.stdout);
-    duration_str
-        .trim()
-        .parse::<f64>()
-        .map_err(|e| format!("Failed to parse duration: {}", e))
+/// Get the duration of an audio file in seconds using the system ffprobe (Linux),
+/// which comes with the ffmpeg package
+#[cfg(target_os = "linux")]
+pub async fn get_audio_duration(_app: &AppHandle, audio_path: &Path) -> Result<f64, KitharaError> {
+    let output = tokio::process::Command::new("ffprobe")
+        .args(FFPROBE_DURATION_ARGS)
+        .arg(audio_path)
+        .output()
+        .await
+        .map_err(|e| KitharaError::Conversion(format!("Failed to run ffprobe: {}", e)))?;
+
+    parse_ffprobe_duration(audio_path, &output)
 }
 
-/// Get the duration of an audio file in seconds using ffprobe (Windows)
+/// Get the duration of an audio file in seconds using the ffprobe bundled next to ffmpeg (Windows)
 #[cfg(target_os = "windows")]
-pub async fn get_audio_duration(audio_path: &Path) -> Result<f64, String> {
-    // On Windows, ffprobe should be bundled alongside ffmpeg
-    let path_str = audio_path
-        .to_str()
-        .ok_or_else(|| "Invalid audio path".to_string())?;
+pub async fn get_audio_duration(app: &AppHandle, audio_path: &Path) -> Result<f64, KitharaError> {
+    let resource_dir = app
+        .path()
+        .resource_dir()
+        .map_err(|e| KitharaError::Conversion(format!("Failed to get resource dir: {}", e)))?;
 
-    // Try system ffprobe first
-    let output = tokio::process::Command::new("ffprobe")
-        .args([
-            "-v", "error",
-            "-show_entries", "format=duration",
-            "-of", "default=noprint_wrappers=1:nokey=1",
-            path_str,
-        ])
+    let ffprobe_exe = resource_dir
+        .join("resources-win")
+        .join("ffmpeg")
+        .join("ffprobe.exe");
+
+    if !ffprobe_exe.exists() {
+        return Err(KitharaError::Conversion(format!(
+            "ffprobe.exe not found at: {}",
+            ffprobe_exe.display()
+        )));
+    }
+
+    let output = tokio::process::Command::new(&ffprobe_exe)
+        .args(FFPROBE_DURATION_ARGS)
+        .arg(audio_path)
         .creation_flags(CREATE_NO_WINDOW)
         .output()
         .await
-        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+        .map_err(|e| KitharaError::Conversion(format!("Failed to run ffprobe: {}", e)))?;
 
-    if !output.status.success() {
-        return Err("ffprobe failed to get duration".to_string());
+    parse_ffprobe_duration(audio_path, &output)
+}
+
+// ============================================================================
+// Duration fallback
+// ============================================================================
+
+/// Nominal bitrate of libvorbis at `-q:a 4`, used to estimate durations
+const VORBIS_Q4_NOMINAL_KBPS: f64 = 128.0;
+
+/// Get the duration of an audio file, falling back to an estimate from file size
+/// when ffprobe is unavailable or fails. Returns 0.0 only if neither works.
+pub async fn get_audio_duration_or_estimate(app: &AppHandle, audio_path: &Path) -> f64 {
+    match get_audio_duration(app, audio_path).await {
+        Ok(duration) if duration > 0.0 => duration,
+        result => {
+            if let Err(e) = result {
+                eprintln!("Warning: {}", e);
+            }
+            if is_ogg(audio_path) {
+                estimate_ogg_duration(audio_path).unwrap_or(0.0)
+            } else {
+                0.0
+            }
+        }
     }
+}
 
-    let duration_str = String::from_utf8_lossy(&output.stdout);
-    duration_str
-        .trim()
-        .parse::<f64>()
-        .map_err(|e| format!("Failed to parse duration: {}", e))
+fn is_ogg(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("ogg"))
+}
+
+/// Estimate the duration of an OGG file we encoded, from its size and the
+/// nominal bitrate of the quality setting used during conversion.
+pub fn estimate_ogg_duration(audio_path: &Path) -> Option<f64> {
+    let file_size = std::fs::metadata(audio_path).ok()?.len();
+    if file_size == 0 {
+        return None;
+    }
+    Some((file_size as f64 * 8.0) / (VORBIS_Q4_NOMINAL_KBPS * 1000.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_riff_wave() {
+        assert!(is_riff_wave(b"RIFF\x24\x08\x00\x00WAVEfmt "));
+        assert!(!is_riff_wave(b"RIFX\x24\x08\x00\x00WAVEfmt "));
+        assert!(!is_riff_wave(b"RIFF\x24\x08\x00\x00AVI LIST"));
+        assert!(!is_riff_wave(b"RIFF"));
+        assert!(!is_riff_wave(b""));
+    }
 }
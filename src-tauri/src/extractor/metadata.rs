@@ -1,10 +1,21 @@
 //! Wwise metadata XML parsers.
 //! Parses soundbank XML files to map WEM file IDs to names and metadata.
 
+use crate::error::KitharaError;
+use crate::models::{GameFileCheck, GamePathReport};
 use quick_xml::events::Event as XmlEvent;
 use quick_xml::Reader;
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Abbreviations used for units in short names, mapped to the canonical unit name.
+/// Users can add their own in `unit_aliases.json` (see `load_unit_aliases`).
+pub const UNIT_ALIASES: &[(&str, &str)] = &[
+    ("hrs", "Horse"),
+    ("catp", "Cataphract"),
+    ("xbow", "Crossbowman"),
+    ("eleph", "Elephant"),
+];
 
 /// Parsed WEM file info from soundbank XML IncludedMemoryFiles
 #[derive(Debug, Clone)]
@@ -21,10 +32,50 @@ pub struct StreamedFileInfo {
     pub short_name: String,
 }
 
+/// Locale codes that mark localized voice lines, matched as whole path or name segments
+const LANGUAGE_CODES: &[&str] = &["en", "fr", "de", "es", "it", "ru", "pl", "ja", "ko", "zh", "pt"];
+
+/// Wwise language folder names (e.g. "English(US)"), mapped to locale codes
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("spanish", "es"),
+    ("italian", "it"),
+    ("russian", "ru"),
+    ("polish", "pl"),
+    ("japanese", "ja"),
+    ("korean", "ko"),
+    ("chinese", "zh"),
+    ("portuguese", "pt"),
+];
+
+/// Classification of a sound parsed from its short_name and, when known, its event
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedName {
+    pub category: String,
+    pub unit_type: Option<String>,
+    pub subcategory: String,
+    /// Female voice variant (see `detect_gender`)
+    pub is_female: bool,
+    /// Locale of a localized voice line, e.g. "fr"
+    pub language: Option<String>,
+    /// Seamless loop (see `detect_loop`)
+    pub is_loop: bool,
+}
+
+/// Parsed event info from Events.xml
+#[derive(Debug, Clone)]
+pub struct EventInfo {
+    pub id: u32,
+    pub name: String,
+    pub object_path: String, // e.g. \Events\Animation\units\Archer\Play_Archer_Attack
+}
+
 /// Parse SoundbanksInfo.xml to get streamed music file mappings
-pub fn parse_soundbanks_info_xml(path: &Path) -> Result<HashMap<u32, StreamedFileInfo>, String> {
+pub fn parse_soundbanks_info_xml(path: &Path) -> Result<HashMap<u32, StreamedFileInfo>, KitharaError> {
     let content = std::fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read SoundbanksInfo.xml: {}", e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to read SoundbanksInfo.xml: {}", e)))?;
 
     let mut reader = Reader::from_str(&content);
     reader.config_mut().trim_text(true);
@@ -73,7 +124,7 @@ pub fn parse_soundbanks_info_xml(path: &Path) -> Result<HashMap<u32, StreamedFil
                 }
             }
             Ok(XmlEvent::Eof) => break,
-            Err(e) => return Err(format!("XML parse error: {}", e)),
+            Err(e) => return Err(KitharaError::Decode(format!("XML parse error: {}", e))),
             _ => {}
         }
         buf.clear();
@@ -84,11 +135,11 @@ pub fn parse_soundbanks_info_xml(path: &Path) -> Result<HashMap<u32, StreamedFil
 
 /// Discover soundbank pairs by scanning for Audio_*.xml files containing IncludedMemoryFiles.
 /// Returns Vec of (xml_filename, bnk_filename) pairs.
-pub fn discover_soundbanks(game_dir: &Path) -> Result<Vec<(String, String)>, String> {
+pub fn discover_soundbanks(game_dir: &Path) -> Result<Vec<(String, String)>, KitharaError> {
     let mut pairs = Vec::new();
 
     let entries = std::fs::read_dir(game_dir)
-        .map_err(|e| format!("Failed to read game directory: {}", e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to read game directory: {}", e)))?;
 
     for entry in entries.flatten() {
         let file_name = entry.file_name().to_string_lossy().to_string();
@@ -104,7 +155,7 @@ pub fn discover_soundbanks(game_dir: &Path) -> Result<Vec<(String, String)>, Str
 
         let xml_path = game_dir.join(&file_name);
         let content = std::fs::read_to_string(&xml_path)
-            .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+            .map_err(|e| KitharaError::Io(format!("Failed to read {}: {}", file_name, e)))?;
 
         if content.contains("IncludedMemoryFiles") {
             pairs.push((file_name, bnk_name));
@@ -115,12 +166,106 @@ pub fn discover_soundbanks(game_dir: &Path) -> Result<Vec<(String, String)>, Str
     Ok(pairs)
 }
 
+/// Check a folder for everything extraction reads, so a wrong folder can be
+/// reported file by file instead of failing partway through.
+/// Valid means Events.xml plus audio: a soundbank, or streamed WEMs listed in SoundbanksInfo.xml.
+pub fn check_game_path(game_dir: &Path) -> GamePathReport {
+    let check = |name: &str, required: bool| GameFileCheck {
+        name: name.to_string(),
+        found: game_dir.join(name).is_file(),
+        required,
+    };
+
+    let mut files = vec![
+        check("Events.xml", true),
+        check("SoundbanksInfo.xml", false),
+        check("Audio_Animation.xml", false),
+        check("Audio_Animation.bnk", false),
+    ];
+
+    // Every soundbank XML should have its BNK next to it
+    let mut xml_names = Vec::new();
+    let mut streamed_wem_count = 0;
+    if let Ok(entries) = std::fs::read_dir(game_dir) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.starts_with("Audio_") && file_name.ends_with(".xml") {
+                xml_names.push(file_name);
+            } else if file_name.to_lowercase().ends_with(".wem") {
+                streamed_wem_count += 1;
+            }
+        }
+    }
+    xml_names.sort();
+    let mut soundbank_count = 0;
+    for xml_name in xml_names {
+        let bnk = check(&xml_name.replace(".xml", ".bnk"), false);
+        if bnk.found {
+            soundbank_count += 1;
+        }
+        // The animation bank is always listed above
+        if xml_name != "Audio_Animation.xml" {
+            files.push(check(&xml_name, false));
+            files.push(bnk);
+        }
+    }
+
+    let path_exists = game_dir.is_dir();
+    let mut missing = Vec::new();
+    if !path_exists {
+        missing.push(format!("folder {}", game_dir.display()));
+    } else {
+        missing.extend(files.iter().filter(|f| f.required && !f.found).map(|f| f.name.clone()));
+        let has_streamed = streamed_wem_count > 0 && files.iter().any(|f| f.name == "SoundbanksInfo.xml" && f.found);
+        if soundbank_count == 0 && !has_streamed {
+            missing.push("soundbanks (Audio_*.bnk) or streamed .wem files with SoundbanksInfo.xml".to_string());
+        }
+    }
+
+    GamePathReport {
+        path_exists,
+        files,
+        soundbank_count,
+        streamed_wem_count,
+        is_valid: missing.is_empty(),
+        missing,
+    }
+}
+
+/// Parse Steam's `libraryfolders.vdf` into the library root folders it lists.
+/// Handles the current format (`"path" "D:\\SteamLibrary"` inside numbered blocks)
+/// and the older one where numbered keys map straight to a path.
+pub fn parse_steam_library_folders(content: &str) -> Vec<PathBuf> {
+    let mut libraries: Vec<PathBuf> = Vec::new();
+
+    for line in content.lines() {
+        // A key/value line is exactly two quoted strings
+        let parts: Vec<&str> = line.split('"').collect();
+        if parts.len() != 5 {
+            continue;
+        }
+        let (key, value) = (parts[1], parts[3]);
+        let is_path = key.eq_ignore_ascii_case("path")
+            || (key.chars().all(|c| c.is_ascii_digit()) && (value.contains('/') || value.contains('\\')));
+        if !is_path {
+            continue;
+        }
+
+        let library = PathBuf::from(value.replace("\\\\", "\\"));
+        if !libraries.contains(&library) {
+            libraries.push(library);
+        }
+    }
+
+    libraries
+}
+
 /// Parse Event ObjectPath attributes from a soundbank XML to extract unit names.
 /// Looks for paths like `\Events\Animation\units\Archer\...` and extracts "Archer".
 /// Returns a sorted, deduplicated Vec of unit name strings.
-pub fn parse_event_unit_names(path: &Path) -> Result<Vec<String>, String> {
+pub fn parse_event_unit_names(path: &Path) -> Result<Vec<String>, KitharaError> {
     let content = std::fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read XML for event parsing: {}", e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to read XML for event parsing: {}", e)))?;
 
     let mut reader = Reader::from_str(&content);
     reader.config_mut().trim_text(true);
@@ -157,7 +302,7 @@ pub fn parse_event_unit_names(path: &Path) -> Result<Vec<String>, String> {
                 }
             }
             Ok(XmlEvent::Eof) => break,
-            Err(e) => return Err(format!("XML parse error: {}", e)),
+            Err(e) => return Err(KitharaError::Decode(format!("XML parse error: {}", e))),
             _ => {}
         }
         buf.clear();
@@ -169,9 +314,9 @@ pub fn parse_event_unit_names(path: &Path) -> Result<Vec<String>, String> {
 }
 
 /// Parse soundbank XML (Audio_Animation.xml, etc.) to get WEM file ID -> metadata mapping
-pub fn parse_soundbank_xml(path: &Path) -> Result<HashMap<u32, WwiseFileInfo>, String> {
+pub fn parse_soundbank_xml(path: &Path) -> Result<HashMap<u32, WwiseFileInfo>, KitharaError> {
     let content = std::fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read soundbank XML: {}", e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to read soundbank XML: {}", e)))?;
 
     let mut reader = Reader::from_str(&content);
     reader.config_mut().trim_text(true);
@@ -235,7 +380,7 @@ pub fn parse_soundbank_xml(path: &Path) -> Result<HashMap<u32, WwiseFileInfo>, S
                 }
             }
             Ok(XmlEvent::Eof) => break,
-            Err(e) => return Err(format!("XML parse error: {}", e)),
+            Err(e) => return Err(KitharaError::Decode(format!("XML parse error: {}", e))),
             _ => {}
         }
         buf.clear();
@@ -244,14 +389,99 @@ pub fn parse_soundbank_xml(path: &Path) -> Result<HashMap<u32, WwiseFileInfo>, S
     Ok(files)
 }
 
+/// Parse Events.xml to get event ID -> name and object path
+pub fn parse_events_xml(path: &Path) -> Result<HashMap<u32, EventInfo>, KitharaError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| KitharaError::Io(format!("Failed to read Events.xml: {}", e)))?;
+
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut events = HashMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(XmlEvent::Start(e)) | Ok(XmlEvent::Empty(e)) if e.name().as_ref() == b"Event" => {
+                let mut event = EventInfo {
+                    id: 0,
+                    name: String::new(),
+                    object_path: String::new(),
+                };
+
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"Id" => event.id = parse_attr_u32(&attr.value),
+                        b"Name" => event.name = String::from_utf8_lossy(&attr.value).to_string(),
+                        b"ObjectPath" => {
+                            event.object_path = String::from_utf8_lossy(&attr.value).to_string()
+                        }
+                        _ => {}
+                    }
+                }
+
+                if event.id > 0 && !event.name.is_empty() {
+                    events.insert(event.id, event);
+                }
+            }
+            Ok(XmlEvent::Eof) => break,
+            Err(e) => return Err(KitharaError::Decode(format!("XML parse error: {}", e))),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(events)
+}
+
 fn parse_attr_u32(value: &[u8]) -> u32 {
     String::from_utf8_lossy(value).parse().unwrap_or(0)
 }
 
+/// Gets the path of the user's unit alias file in the app config directory.
+pub fn get_unit_aliases_path() -> Result<PathBuf, KitharaError> {
+    let project_dirs = directories::ProjectDirs::from("com", "kithara", "app")
+        .ok_or_else(|| KitharaError::Io("Could not determine application config directory".into()))?;
+    Ok(project_dirs.config_dir().join("unit_aliases.json"))
+}
+
+/// Load the unit alias table: user aliases from a JSON object such as
+/// `{"hrs": "Horse"}` first, so they override the built-in `UNIT_ALIASES`.
+/// A missing file just means no user aliases.
+pub fn load_unit_aliases(path: &Path) -> Result<Vec<(String, String)>, KitharaError> {
+    let mut aliases: Vec<(String, String)> = if path.exists() {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| KitharaError::Io(format!("Failed to read unit aliases: {}", e)))?;
+        let user: HashMap<String, String> = serde_json::from_str(&json)
+            .map_err(|e| KitharaError::Decode(format!("Failed to parse unit aliases: {}", e)))?;
+        let mut user: Vec<(String, String)> = user.into_iter().collect();
+        user.sort();
+        user
+    } else {
+        Vec::new()
+    };
+
+    aliases.extend(builtin_unit_aliases());
+    Ok(aliases)
+}
+
+/// The built-in `UNIT_ALIASES` as owned pairs.
+pub fn builtin_unit_aliases() -> Vec<(String, String)> {
+    UNIT_ALIASES
+        .iter()
+        .map(|(alias, unit)| (alias.to_string(), unit.to_string()))
+        .collect()
+}
+
 /// Parse short_name from soundbank XML to extract metadata.
-/// `known_units` is derived dynamically from Event ObjectPaths at extraction time.
+/// `known_units` is derived dynamically from Event ObjectPaths at extraction time;
+/// `aliases` catches abbreviated unit names (see `load_unit_aliases`).
 /// Format: "cmbt.rng.slinger.short.00.MSTR.wav" or "mv.obj.arrowRattle.MSTR.09.wav"
-pub fn parse_short_name(short_name: &str, known_units: &[String]) -> (String, Option<String>, String) {
+pub fn parse_short_name(
+    short_name: &str,
+    known_units: &[String],
+    aliases: &[(String, String)],
+) -> ParsedName {
     // Remove file extension
     let name = short_name.trim_end_matches(".wav").trim_end_matches(".WAV");
 
@@ -259,26 +489,7 @@ pub fn parse_short_name(short_name: &str, known_units: &[String]) -> (String, Op
     let parts: Vec<&str> = name.split('.').collect();
     let name_lower = name.to_lowercase();
 
-    // Determine category from prefix
-    let category = if name_lower.starts_with("cmbt") {
-        "combat"
-    } else if name_lower.starts_with("mv") || name_lower.contains("step") || name_lower.contains("hoof") {
-        "movement"
-    } else if name_lower.starts_with("vcl") || name_lower.contains("grunt") || name_lower.contains("vocal") {
-        "vocal"
-    } else if name_lower.contains("bodyfall") || name_lower.contains("death") {
-        "death"
-    } else if name_lower.contains("weapon") || name_lower.contains("arrow") || name_lower.contains("bow") {
-        "weapon"
-    } else if name_lower.contains("impact") {
-        "impact"
-    } else if name_lower.starts_with("ui") {
-        "ui"
-    } else if name_lower.contains("ambience") || name_lower.contains("ambient") {
-        "ambience"
-    } else {
-        "other"
-    };
+    let category = categorize(&name_lower);
 
     // Look for unit type
     let mut unit_type: Option<String> = None;
@@ -288,6 +499,9 @@ pub fn parse_short_name(short_name: &str, known_units: &[String]) -> (String, Op
             break;
         }
     }
+    if unit_type.is_none() {
+        unit_type = find_aliased_unit(&name_lower, known_units, aliases);
+    }
 
     // Build subcategory from meaningful parts
     let subcategory = parts
@@ -304,7 +518,147 @@ pub fn parse_short_name(short_name: &str, known_units: &[String]) -> (String, Op
         .collect::<Vec<_>>()
         .join("_");
 
-    (category.to_string(), unit_type, subcategory)
+    ParsedName {
+        category: category.to_string(),
+        unit_type,
+        subcategory,
+        is_female: detect_gender(short_name).is_some(),
+        language: detect_language(short_name),
+        is_loop: detect_loop(short_name),
+    }
+}
+
+/// Detect a locale from a WEM path or short_name, e.g. "Voices\\French(France)\\vcl.wav"
+/// or "vcl.attack.fr.00.wav". Returns a lowercase code such as "fr".
+pub fn detect_language(name: &str) -> Option<String> {
+    name.split(['\\', '/', '.', '_', '-']).find_map(|segment| {
+        // "English(US)" -> "english"
+        let segment = segment.split('(').next().unwrap_or(segment).to_lowercase();
+        LANGUAGE_CODES
+            .iter()
+            .find(|code| **code == segment)
+            .copied()
+            .or_else(|| {
+                LANGUAGE_NAMES
+                    .iter()
+                    .find(|(language, _)| *language == segment)
+                    .map(|(_, code)| *code)
+            })
+            .map(str::to_string)
+    })
+}
+
+/// Find a unit whose alias is one of the `.`/`_`-separated segments of a name.
+/// Aliases must match a whole segment, so short ones don't fire inside other words.
+fn find_aliased_unit(name: &str, known_units: &[String], aliases: &[(String, String)]) -> Option<String> {
+    name.split(['.', '_']).find_map(|segment| {
+        aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(segment))
+            .map(|(_, unit)| canonical_unit(unit, known_units))
+    })
+}
+
+/// Use the discovered spelling of a unit name when there is one.
+fn canonical_unit(unit: &str, known_units: &[String]) -> String {
+    known_units
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(unit))
+        .cloned()
+        .unwrap_or_else(|| unit.to_string())
+}
+
+/// Determine a category from a lowercased sound or event name
+fn categorize(name_lower: &str) -> &'static str {
+    if name_lower.starts_with("cmbt") {
+        "combat"
+    } else if name_lower.starts_with("mv") || name_lower.contains("step") || name_lower.contains("hoof") {
+        "movement"
+    } else if name_lower.starts_with("vcl") || name_lower.contains("grunt") || name_lower.contains("vocal") {
+        "vocal"
+    } else if name_lower.contains("bodyfall") || name_lower.contains("death") {
+        "death"
+    } else if name_lower.contains("weapon") || name_lower.contains("arrow") || name_lower.contains("bow") {
+        "weapon"
+    } else if name_lower.contains("impact") {
+        "impact"
+    } else if name_lower.starts_with("ui") {
+        "ui"
+    } else if name_lower.contains("ambience") || name_lower.contains("ambient") {
+        "ambience"
+    } else {
+        "other"
+    }
+}
+
+/// Classify a sound using the event that plays it, falling back to the
+/// short_name heuristics for anything the event doesn't settle.
+/// Units come from the event's ObjectPath (`\Events\...\units\{Unit}\...`)
+/// or name; the category comes from the event name, e.g. "Play_cmbt_archer_attack".
+/// A female variant is flagged by either the event name or the short_name.
+pub fn parse_event_name(
+    event: &EventInfo,
+    short_name: &str,
+    known_units: &[String],
+    aliases: &[(String, String)],
+) -> ParsedName {
+    let parsed = parse_short_name(short_name, known_units, aliases);
+
+    let event_name = event.name.to_lowercase();
+    let event_name = event_name.strip_prefix("play_").unwrap_or(&event_name);
+    let event_category = categorize(event_name);
+    let category = if event_category == "other" {
+        parsed.category
+    } else {
+        event_category.to_string()
+    };
+
+    let path_unit = event
+        .object_path
+        .split('\\')
+        .skip_while(|s| *s != "units")
+        .nth(1)
+        .and_then(|unit| {
+            known_units
+                .iter()
+                .find(|k| k.eq_ignore_ascii_case(unit))
+                .cloned()
+                .or_else(|| find_aliased_unit(unit, known_units, aliases))
+        });
+    let name_unit = known_units
+        .iter()
+        .find(|k| event_name.contains(&k.to_lowercase()))
+        .cloned()
+        .or_else(|| find_aliased_unit(event_name, known_units, aliases));
+    ParsedName {
+        category,
+        unit_type: path_unit.or(name_unit).or(parsed.unit_type),
+        is_female: parsed.is_female || detect_gender(&event.name).is_some(),
+        is_loop: parsed.is_loop || detect_loop(&event.name),
+        ..parsed
+    }
+}
+
+/// Detect a sound designed to loop seamlessly from a `loop` or `lp` segment,
+/// e.g. "amb.forest.loop.MSTR.wav" or "Play_siege_engine_lp"
+pub fn detect_loop(short_name: &str) -> bool {
+    short_name
+        .split(['.', '_'])
+        .any(|part| part.eq_ignore_ascii_case("loop") || part.eq_ignore_ascii_case("lp"))
+}
+
+/// Detect a gender variant from a soundbank short_name.
+/// Female voice lines carry a `Female_` prefix or a `female` segment,
+/// e.g. "Female_vcl.grunt.01.wav" or "vcl.female.attack.00.wav".
+pub fn detect_gender(short_name: &str) -> Option<String> {
+    let is_female = short_name
+        .split(['.', '_'])
+        .any(|part| part.eq_ignore_ascii_case("female"));
+    if is_female {
+        Some("female".to_string())
+    } else {
+        None
+    }
 }
 
 /// Format display name from soundbank short_name
@@ -447,3 +801,164 @@ pub fn format_streamed_music_title(short_name: &str) -> String {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_name() {
+        let units = vec!["Archer".to_string(), "Warrior".to_string()];
+        assert_eq!(
+            parse_short_name("cmbt.rng.slinger.short.00.MSTR.wav", &units, &[]),
+            ParsedName {
+                category: "combat".to_string(),
+                unit_type: None,
+                subcategory: "cmbt_rng_slinger".to_string(),
+                is_female: false,
+                language: None,
+                is_loop: false,
+            }
+        );
+
+        let event = EventInfo {
+            id: 1,
+            name: "Play_cmbt_Attack".to_string(),
+            object_path: "\\Events\\Animation\\units\\Archer\\Play_cmbt_Attack".to_string(),
+        };
+
+        // The event supplies both category and unit where the short_name has neither
+        let parsed = parse_event_name(&event, "swoosh.03.wav", &units, &[]);
+        assert_eq!(parsed.category, "combat");
+        assert_eq!(parsed.unit_type.as_deref(), Some("Archer"));
+
+        // An uninformative event falls back to the short_name
+        let vague = EventInfo {
+            id: 2,
+            name: "Play_misc".to_string(),
+            object_path: String::new(),
+        };
+        let parsed = parse_event_name(&vague, "vcl.grunt.warrior.00.wav", &units, &[]);
+        assert_eq!(parsed.category, "vocal");
+        assert_eq!(parsed.unit_type.as_deref(), Some("Warrior"));
+
+        // A female variant can be flagged by the event name alone
+        let female = EventInfo {
+            id: 3,
+            name: "Play_Female_vcl_Attack".to_string(),
+            object_path: String::new(),
+        };
+        assert!(parse_event_name(&female, "vcl.attack.00.wav", &units, &[]).is_female);
+        assert!(parse_event_name(&vague, "vcl.female.attack.00.wav", &units, &[]).is_female);
+        assert!(!parse_event_name(&vague, "vcl.attack.00.wav", &units, &[]).is_female);
+    }
+
+    #[test]
+    fn test_unit_aliases() {
+        let units = vec!["Archer".to_string(), "CATAPHRACT".to_string()];
+        let aliases = builtin_unit_aliases();
+
+        // Aliased segments resolve to the canonical name, using the discovered spelling
+        assert_eq!(parse_short_name("mv.hrs.gallop.00.wav", &units, &aliases).unit_type.as_deref(), Some("Horse"));
+        assert_eq!(parse_short_name("cmbt.catp.charge.01.wav", &units, &aliases).unit_type.as_deref(), Some("CATAPHRACT"));
+        // Known unit names win, and aliases don't match inside other words
+        assert_eq!(parse_short_name("cmbt.archer.hrs.wav", &units, &aliases).unit_type.as_deref(), Some("Archer"));
+        assert_eq!(parse_short_name("mv.hrsx.wav", &units, &aliases).unit_type, None);
+
+        let event = EventInfo {
+            id: 1,
+            name: "Play_cmbt_Attack".to_string(),
+            object_path: "\\Events\\Animation\\units\\catp\\Play_cmbt_Attack".to_string(),
+        };
+        assert_eq!(parse_event_name(&event, "swoosh.03.wav", &units, &aliases).unit_type.as_deref(), Some("CATAPHRACT"));
+
+        // User aliases come first and can override built-ins
+        let path = std::env::temp_dir().join(format!("kithara-test-aliases-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"hrs": "Horseman", "slg": "Slinger"}"#).unwrap();
+        let aliases = load_unit_aliases(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(parse_short_name("mv.hrs.gallop.00.wav", &units, &aliases).unit_type.as_deref(), Some("Horseman"));
+        assert_eq!(parse_short_name("cmbt.slg.throw.wav", &units, &aliases).unit_type.as_deref(), Some("Slinger"));
+    }
+
+    #[test]
+    fn test_check_game_path() {
+        let dir = std::env::temp_dir().join(format!("kithara-test-game-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let report = check_game_path(&dir);
+        assert!(report.path_exists);
+        assert!(!report.is_valid);
+        assert_eq!(report.missing.len(), 2);
+        assert_eq!(report.missing[0], "Events.xml");
+
+        for name in ["Events.xml", "Audio_Animation.xml", "Audio_Animation.bnk", "Audio_UI.xml"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let report = check_game_path(&dir);
+        assert!(report.is_valid, "{:?}", report.missing);
+        assert_eq!(report.soundbank_count, 1);
+        let ui_bank = report.files.iter().find(|f| f.name == "Audio_UI.bnk").unwrap();
+        assert!(!ui_bank.found);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(!check_game_path(&dir).path_exists);
+    }
+
+    #[test]
+    fn test_parse_steam_library_folders() {
+        let vdf = r#""libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"apps"
+		{
+			"597180"		"4213812025"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+	}
+}"#;
+        let libraries = parse_steam_library_folders(vdf);
+        assert_eq!(
+            libraries,
+            vec![PathBuf::from(r"C:\Program Files (x86)\Steam"), PathBuf::from("/mnt/games/SteamLibrary")]
+        );
+
+        // Older format maps numbered keys straight to paths
+        let old = "\"LibraryFolders\"\n{\n\t\"TimeNextStatsReport\"\t\"1234\"\n\t\"1\"\t\"D:\\\\Games\\\\Steam\"\n}";
+        assert_eq!(parse_steam_library_folders(old), vec![PathBuf::from(r"D:\Games\Steam")]);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("Voices\\French(France)\\vcl.attack.00.wav").as_deref(), Some("fr"));
+        assert_eq!(detect_language("SFX/de/vcl.attack.wem").as_deref(), Some("de"));
+        assert_eq!(detect_language("vcl.attack.en_us.00.wav").as_deref(), Some("en"));
+        assert_eq!(parse_short_name("vcl.grunt.es.01.wav", &[], &[]).language.as_deref(), Some("es"));
+        // Codes must be whole segments
+        assert_eq!(detect_language("SFX\\cmbt.defend.wav"), None);
+    }
+
+    #[test]
+    fn test_detect_gender() {
+        assert_eq!(detect_gender("Female_vcl.grunt.01.wav").as_deref(), Some("female"));
+        assert_eq!(detect_gender("vcl.female.attack.00.MSTR.wav").as_deref(), Some("female"));
+        assert_eq!(detect_gender("vcl.grunt.archer.00.wav"), None);
+        // "female" must be a whole segment
+        assert_eq!(detect_gender("vcl.females.wav"), None);
+    }
+
+    #[test]
+    fn test_detect_loop() {
+        assert!(detect_loop("amb.forest.loop.MSTR.wav"));
+        assert!(detect_loop("mv.siege_ram_LP.00.wav"));
+        assert!(!detect_loop("cmbt.rng.slinger.short.00.MSTR.wav"));
+        // Only whole segments count
+        assert!(!detect_loop("ui.help.wav"));
+        assert!(!detect_loop("amb.loophole.wav"));
+    }
+}
@@ -6,15 +6,28 @@ pub mod converter;
 pub mod metadata;
 
 use crate::catalog::Catalog;
-use crate::models::{ExtractionState, ExtractionStatus, MusicTrack, Sound};
-use std::path::PathBuf;
+use crate::error::KitharaError;
+use crate::models::{
+    ExtractionError, ExtractionOptions, ExtractionPlan, ExtractionState, ExtractionStatus, ExtractionSummary,
+    MusicTrack, Sound,
+};
+use converter::EncodeOptions;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::AppHandle;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
 
 /// Thread-safe extraction state for Tauri managed state
 pub struct ExtractionManager {
     status: Mutex<ExtractionStatus>,
     cancel_flag: Mutex<bool>,
+    summary: Mutex<ExtractionSummary>,
+    plan: Mutex<ExtractionPlan>,
 }
 
 impl ExtractionManager {
@@ -22,6 +35,8 @@ impl ExtractionManager {
         Self {
             status: Mutex::new(ExtractionStatus::default()),
             cancel_flag: Mutex::new(false),
+            summary: Mutex::new(ExtractionSummary::default()),
+            plan: Mutex::new(ExtractionPlan::default()),
         }
     }
 
@@ -47,6 +62,92 @@ impl ExtractionManager {
         status.error = Some(error);
     }
 
+    pub fn update_counts(
+        &self,
+        processed: u32,
+        total: u32,
+        successful: u32,
+        skipped: u32,
+        eta_secs: Option<f64>,
+    ) {
+        let mut status = self.status.lock().unwrap();
+        status.processed = processed;
+        status.total = total;
+        status.successful = successful;
+        status.skipped = skipped;
+        status.eta_secs = eta_secs;
+    }
+
+    pub fn set_warnings(&self, warnings: Vec<String>) {
+        self.status.lock().unwrap().warnings = warnings;
+    }
+
+    /// Adjusts the tallies reported when the run completes
+    pub fn update_summary(&self, update: impl FnOnce(&mut ExtractionSummary)) {
+        update(&mut self.summary.lock().unwrap());
+    }
+
+    /// Counts a newly converted sound or music track toward the summary
+    pub fn record_extracted(&self, is_music: bool) {
+        self.update_summary(|summary| {
+            if is_music {
+                summary.music_extracted += 1;
+            } else {
+                summary.sounds_extracted += 1;
+            }
+        });
+    }
+
+    /// Records an entry that failed to extract and tells the frontend which one
+    pub fn record_failure(&self, app: &AppHandle, file_id: u32, short_name: &str, error: String) {
+        eprintln!("Failed to extract {} ({}): {}", short_name, file_id, error);
+        let failure = ExtractionError {
+            file_id,
+            short_name: short_name.to_string(),
+            error,
+        };
+        let _ = app.emit("extraction-error", &failure);
+        self.update_summary(|summary| {
+            summary.failed += 1;
+            summary.failures.push(failure);
+        });
+    }
+
+    pub fn get_summary(&self) -> ExtractionSummary {
+        self.summary.lock().unwrap().clone()
+    }
+
+    /// Adjusts the dry-run plan
+    pub fn update_plan(&self, update: impl FnOnce(&mut ExtractionPlan)) {
+        update(&mut self.plan.lock().unwrap());
+    }
+
+    /// Counts an entry a dry run would convert. `sound` is its classification, None for music.
+    pub fn record_planned(&self, sound: Option<&metadata::ParsedName>, source_bytes: u64) {
+        self.update_plan(|plan| {
+            plan.source_bytes += source_bytes;
+            let Some(sound) = sound else {
+                plan.music_tracks += 1;
+                return;
+            };
+            plan.sounds += 1;
+            *plan.categories.entry(sound.category.clone()).or_default() += 1;
+            if let Some(unit) = &sound.unit_type {
+                *plan.unit_types.entry(unit.clone()).or_default() += 1;
+            }
+        });
+    }
+
+    /// The dry-run plan, with the exclusion tallies shared with the summary
+    pub fn get_plan(&self) -> ExtractionPlan {
+        let summary = self.get_summary();
+        ExtractionPlan {
+            excluded: summary.excluded,
+            skipped_no_metadata: summary.skipped_no_metadata,
+            ..self.plan.lock().unwrap().clone()
+        }
+    }
+
     pub fn request_cancel(&self) {
         *self.cancel_flag.lock().unwrap() = true;
     }
@@ -58,6 +159,8 @@ impl ExtractionManager {
     pub fn reset(&self) {
         *self.status.lock().unwrap() = ExtractionStatus::default();
         *self.cancel_flag.lock().unwrap() = false;
+        *self.summary.lock().unwrap() = ExtractionSummary::default();
+        *self.plan.lock().unwrap() = ExtractionPlan::default();
     }
 }
 
@@ -67,32 +170,159 @@ impl Default for ExtractionManager {
     }
 }
 
-/// Get the cache directory for storing extracted sounds
-pub fn get_cache_dir() -> Result<PathBuf, String> {
-    let proj_dirs = directories::ProjectDirs::from("com", "kithara", "app")
-        .ok_or_else(|| "Failed to determine cache directory".to_string())?;
-    Ok(proj_dirs.data_dir().to_path_buf())
+fn project_dirs() -> Result<directories::ProjectDirs, KitharaError> {
+    directories::ProjectDirs::from("com", "kithara", "app")
+        .ok_or_else(|| KitharaError::Io("Failed to determine app directories".into()))
+}
+
+/// Get the data directory for storing extracted sounds
+pub fn get_data_dir() -> Result<PathBuf, KitharaError> {
+    Ok(project_dirs()?.data_dir().to_path_buf())
+}
+
+/// Get the working directory for temporary WEM/WAV files. It lives under the
+/// platform cache directory so cache cleaners can reclaim it and backups skip it.
+pub fn get_temp_dir() -> Result<PathBuf, KitharaError> {
+    Ok(project_dirs()?.cache_dir().join("temp"))
+}
+
+/// Folder created inside a user-chosen output directory to hold extracted sounds.
+/// Clearing the cache deletes it, so it must never be a folder with the user's own files.
+const OUTPUT_SUBDIR: &str = "kithara-sounds";
+
+/// The directory a run with `options` writes sounds to, without saving its choice
+fn output_sounds_dir(catalog: &Catalog, options: &ExtractionOptions) -> Result<PathBuf, KitharaError> {
+    match &options.output_dir {
+        Some(dir) => Ok(Path::new(dir).join(OUTPUT_SUBDIR)),
+        None => get_sounds_dir(catalog),
+    }
+}
+
+/// Get the directory extracted sounds are written to: a `kithara-sounds` folder in
+/// the user's chosen output directory if one was saved, else `sounds` in the data directory
+pub fn get_sounds_dir(catalog: &Catalog) -> Result<PathBuf, KitharaError> {
+    match catalog.get_saved_output_dir()? {
+        Some(dir) => Ok(Path::new(&dir).join(OUTPUT_SUBDIR)),
+        None => Ok(get_data_dir()?.join("sounds")),
+    }
+}
+
+/// Prefix of the sound IDs auditioned WEMs play under; they aren't in the catalog
+pub const AUDITION_ID_PREFIX: &str = "audition:";
+
+/// Numbers each audition so overlapping previews never share a temp file
+static AUDITION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Converts a single WEM to OGG in the temp directory for a quick listen without
+/// extracting. Returns the sound ID to play it under and the OGG path, which the
+/// caller removes once playback is done.
+pub async fn convert_for_audition(app: &AppHandle, wem_path: &Path) -> Result<(String, PathBuf), KitharaError> {
+    let temp_dir = get_temp_dir()?;
+    std::fs::create_dir_all(&temp_dir).map_err(|e| KitharaError::Io(format!("Failed to create temp dir: {}", e)))?;
+
+    // Work on a copy so the intermediate WAV isn't written into the game folder
+    let n = AUDITION_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let temp_wem = temp_dir.join(format!("audition-{}.wem", n));
+    let output_path = temp_wem.with_extension("ogg");
+    std::fs::copy(wem_path, &temp_wem).map_err(|e| KitharaError::Io(format!("Failed to copy WEM: {}", e)))?;
+
+    let encode = EncodeOptions::default();
+    let title = wem_path.file_stem().unwrap_or_default().to_string_lossy();
+    let result = converter::convert_wem(app, &temp_wem, &output_path, &encode, &embedded_tags(&title, &[])).await;
+    let _ = std::fs::remove_file(&temp_wem);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&output_path);
+        return Err(e);
+    }
+
+    Ok((format!("{}{}", AUDITION_ID_PREFIX, n), output_path))
 }
 
-/// Main extraction entry point
+/// Main extraction entry point. Emits "extraction-complete" with a summary of
+/// what was done, including when the run stops on an error. A dry run also
+/// emits "extraction-plan" with what a real run would convert.
 pub async fn run_extraction(
     app: AppHandle,
     game_path: PathBuf,
     manager: Arc<ExtractionManager>,
     catalog: Arc<Catalog>,
-    include_music: bool,
-) -> Result<(), String> {
+    options: ExtractionOptions,
+) -> Result<(), KitharaError> {
+    let started = Instant::now();
+    let dry_run = options.dry_run;
+    let result = extract_all(app.clone(), game_path, Arc::clone(&manager), catalog, options).await;
+
+    let summary = ExtractionSummary {
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        error: result.as_ref().err().map(ToString::to_string),
+        ..manager.get_summary()
+    };
+    if dry_run && result.is_ok() {
+        let _ = app.emit("extraction-plan", manager.get_plan());
+    }
+    let _ = app.emit("extraction-complete", summary);
+    result
+}
+
+/// Runs every extraction step, tallying results on `manager` as it goes
+async fn extract_all(
+    app: AppHandle,
+    game_path: PathBuf,
+    manager: Arc<ExtractionManager>,
+    catalog: Arc<Catalog>,
+    options: ExtractionOptions,
+) -> Result<(), KitharaError> {
+    // Fail fast rather than erroring on every file deep into the run.
+    // A dry run converts nothing, so it can preview without the tools.
+    if !options.dry_run {
+        let dependencies = converter::check_dependencies(&app).await;
+        if let Some(error) = converter::missing_dependencies_error(&dependencies) {
+            return Err(KitharaError::Conversion(error));
+        }
+    }
+
+    // Resuming reuses the interrupted run's options. Converted sounds are skipped
+    // by the incremental check below, so only the remaining entries are redone.
+    let options = if options.resume {
+        match catalog.get_pending_extraction()? {
+            Some(pending) => ExtractionOptions {
+                force: false,
+                resume: true,
+                dry_run: options.dry_run,
+                ..pending
+            },
+            None => ExtractionOptions {
+                force: false,
+                ..options
+            },
+        }
+    } else {
+        options
+    };
+    // A dry run leaves no trace: nothing to resume and no remembered output directory
+    // or concurrency limit
+    let dry_run = options.dry_run;
+    if !dry_run {
+        catalog.set_pending_extraction(Some(&options))?;
+        if let Some(dir) = &options.output_dir {
+            catalog.save_output_dir(Some(dir))?;
+        }
+    }
+
+    let include_music = options.include_music;
     manager.update_status(
         ExtractionState::InProgress,
         0.0,
-        Some("Parsing metadata...".into()),
+        Some(if options.resume {
+            "Resuming extraction...".into()
+        } else {
+            "Parsing metadata...".into()
+        }),
     );
 
     // Step 1: Discover soundbanks and parse XML metadata
+    // No soundbanks is not fatal: audio may be shipped as loose WEM files (see Step 2)
     let soundbank_pairs = metadata::discover_soundbanks(&game_path)?;
-    if soundbank_pairs.is_empty() {
-        return Err("No soundbanks with embedded audio found in game directory".into());
-    }
     println!(
         "Discovered {} soundbanks: {:?}",
         soundbank_pairs.len(),
@@ -143,6 +373,14 @@ pub async fn run_extraction(
         Vec::new()
     };
 
+    if !known_units.is_empty() {
+        if let Err(e) = catalog.set_known_units(&known_units) {
+            eprintln!("Warning: Failed to store known units: {}", e);
+        }
+    }
+
+    let unit_aliases = unit_aliases();
+
     // Progress allocation depends on whether music is included
     let bnk_start = 0.10;
     let bnk_end = if include_music { 0.50 } else { 0.95 };
@@ -155,13 +393,24 @@ pub async fn run_extraction(
         Some("Parsing soundbanks...".into()),
     );
 
+    // Event names let the HIRC event -> file links classify sounds more reliably
+    // than short_name alone
+    let events = match metadata::parse_events_xml(&game_path.join("Events.xml")) {
+        Ok(events) => events,
+        Err(e) => {
+            println!("Warning: Failed to parse Events.xml: {}. Falling back to file names.", e);
+            HashMap::new()
+        }
+    };
+
     // Step 2: Parse BNK files and extract WEM data
     let bnk_files: Vec<&str> = soundbank_pairs.iter().map(|(_, b)| b.as_str()).collect();
 
     let mut all_wem_entries = Vec::new();
+    let mut file_events: HashMap<u32, u32> = HashMap::new();
     for bnk_name in &bnk_files {
         if manager.is_cancelled() {
-            return Err("Extraction cancelled".into());
+            return Err(KitharaError::Cancelled);
         }
 
         let bnk_path = game_path.join(bnk_name);
@@ -171,13 +420,62 @@ pub async fn run_extraction(
         }
 
         println!("Parsing {}...", bnk_name);
-        let entries = bnk_parser::parse_bnk(&bnk_path)?;
-        println!("  Found {} WEM entries", entries.len());
-        all_wem_entries.extend(entries);
+        let contents = bnk_parser::parse_bnk(&bnk_path)?;
+        match contents.bank_name() {
+            Some(name) => println!("  Found {} WEM entries in bank {}", contents.entries.len(), name),
+            None => println!("  Found {} WEM entries", contents.entries.len()),
+        }
+        all_wem_entries.extend(contents.entries);
+
+        match bnk_parser::parse_file_events(&bnk_path) {
+            Ok(mapping) => {
+                println!("  Linked {} WEM files to events", mapping.len());
+                for (file_id, event_id) in mapping {
+                    file_events.entry(file_id).or_insert(event_id);
+                }
+            }
+            Err(e) => println!("  Warning: Failed to parse HIRC: {}", e),
+        }
     }
 
     if all_wem_entries.is_empty() {
-        return Err("No audio files found in soundbanks".into());
+        // Some Wwise setups stream everything as loose {file_id}.wem files that are
+        // only referenced from SoundbanksInfo.xml
+        if !game_path.join("SoundbanksInfo.xml").exists() {
+            return Err(KitharaError::NotFound("No audio files found in soundbanks".into()));
+        }
+        println!("No embedded audio found; converting loose WEM files instead");
+
+        extract_loose_wems(
+            &app,
+            &game_path,
+            &catalog,
+            &manager,
+            &options,
+            LooseWemKind::Sounds {
+                known_units: &known_units,
+                aliases: &unit_aliases,
+            },
+            (bnk_start, 1.0),
+        )
+        .await?;
+
+        if !dry_run {
+            if let Err(e) = catalog.set_pending_extraction(None) {
+                eprintln!("Warning: Failed to clear pending extraction: {}", e);
+            }
+        }
+        manager.update_status(ExtractionState::Complete, 1.0, None);
+        return Ok(());
+    }
+
+    // The same file ID can be embedded in several banks; convert it once
+    let mut seen_ids = HashSet::new();
+    let before_dedupe = all_wem_entries.len();
+    all_wem_entries.retain(|entry| seen_ids.insert(entry.file_id));
+    let duplicates = before_dedupe - all_wem_entries.len();
+    if duplicates > 0 {
+        println!("Collapsed {} duplicate WEM entries", duplicates);
     }
 
     println!("Total WEM entries: {}", all_wem_entries.len());
@@ -189,146 +487,187 @@ pub async fn run_extraction(
     );
 
     // Step 3: Setup directories
-    let cache_dir = get_cache_dir()?;
-    let temp_dir = cache_dir.join("temp");
-    let sounds_dir = cache_dir.join("sounds");
-
-    std::fs::create_dir_all(&temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
-    std::fs::create_dir_all(&sounds_dir)
-        .map_err(|e| format!("Failed to create sounds dir: {}", e))?;
+    let temp_dir = get_temp_dir()?;
+    let sounds_dir = output_sounds_dir(&catalog, &options)?;
+
+    if !dry_run {
+        // Older versions kept the working directory under the data directory
+        let _ = std::fs::remove_dir_all(get_data_dir()?.join("temp"));
+        std::fs::create_dir_all(&temp_dir).map_err(|e| KitharaError::Io(format!("Failed to create temp dir: {}", e)))?;
+        std::fs::create_dir_all(&sounds_dir)
+            .map_err(|e| KitharaError::Io(format!("Failed to create sounds dir: {}", e)))?;
+    }
 
-    // Step 4: Extract and convert each WEM file
-    let total = all_wem_entries.len();
-    let mut processed = 0;
-    let mut successful = 0;
+    // Step 4: Extract and convert WEM files, several at a time. Each conversion
+    // spends most of its time in vgmstream/ffmpeg subprocesses.
+    let progress = Arc::new(ConversionProgress::new(
+        Arc::clone(&manager),
+        all_wem_entries.len(),
+        (bnk_start, bnk_end),
+    ));
     let mut skipped_no_metadata = 0;
 
+    let concurrency = match options.max_concurrency {
+        Some(limit) => {
+            if !dry_run {
+                if let Err(e) = catalog.save_max_concurrency(limit) {
+                    eprintln!("Warning: Failed to save concurrency limit: {}", e);
+                }
+            }
+            limit
+        }
+        None => catalog
+            .get_saved_max_concurrency()
+            .ok()
+            .flatten()
+            .unwrap_or_else(default_concurrency),
+    };
+    println!("Converting with up to {} concurrent jobs", concurrency);
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let encode = encode_options(&options);
+    // Hashes of converted WEM payloads, to catch identical audio under different IDs
+    let seen_payloads = options
+        .dedupe_payloads
+        .then(|| Arc::new(Mutex::new(HashSet::new())));
+    let batch = Arc::new(SoundBatch::new(app.clone(), Arc::clone(&catalog), Arc::clone(&manager)));
+    let mut tasks = Vec::new();
+
     for entry in all_wem_entries {
         if manager.is_cancelled() {
-            // Cleanup temp files
-            let _ = std::fs::remove_dir_all(&temp_dir);
-            return Err("Extraction cancelled".into());
+            break;
         }
 
         // Try to find matching file metadata from soundbank XML
-        let file_info = file_metadata.get(&entry.file_id);
-        if file_info.is_none() {
+        let Some(file_info) = file_metadata.get(&entry.file_id) else {
             // Skip files without metadata (shouldn't happen often)
             skipped_no_metadata += 1;
-            processed += 1;
+            manager.update_summary(|summary| summary.skipped_no_metadata += 1);
+            progress.skip();
             continue;
-        }
-        let file_info = file_info.unwrap();
+        };
 
         // Skip sounds matching exclusion patterns
         if is_excluded(&file_info.short_name, include_music) {
-            processed += 1;
+            manager.update_summary(|summary| summary.excluded += 1);
+            progress.skip();
             continue;
         }
 
         // Check if this is a music file (by name convention or source bank)
         let is_music = is_music_file(&file_info.short_name) || music_file_ids.contains(&entry.file_id);
+        if !is_music && !options.include_sounds {
+            progress.skip();
+            continue;
+        }
+
+        // Classify from the event that plays this file when known, else from its name
+        let classification = match file_events.get(&entry.file_id).and_then(|id| events.get(id)) {
+            Some(event) => {
+                metadata::parse_event_name(event, &file_info.short_name, &known_units, &unit_aliases)
+            }
+            None => metadata::parse_short_name(&file_info.short_name, &known_units, &unit_aliases),
+        };
+        // Localized banks put the locale in the file's path rather than its name
+        let classification = metadata::ParsedName {
+            language: metadata::detect_language(&file_info.path).or(classification.language.clone()),
+            ..classification
+        };
 
         // Build output path based on file metadata
         let output_subdir = if is_music {
             // Music goes to sounds/music/
             sounds_dir.join("music")
         } else {
-            let (category, unit_type, _) = metadata::parse_short_name(&file_info.short_name, &known_units);
-            if let Some(ref unit) = unit_type {
-                sounds_dir.join(&category).join(unit.to_lowercase())
+            // Skip categories outside the requested allowlist
+            if !is_category_selected(&classification.category, options.categories.as_deref()) {
+                progress.skip();
+                continue;
+            }
+
+            if let Some(unit) = &classification.unit_type {
+                sounds_dir.join(&classification.category).join(unit.to_lowercase())
             } else {
-                sounds_dir.join(&category)
+                sounds_dir.join(&classification.category)
             }
         };
-        std::fs::create_dir_all(&output_subdir)
-            .map_err(|e| format!("Failed to create output dir: {}", e))?;
 
         // Generate clean filename from file ID and short name
         let filename = format!("{}_{}", entry.file_id, sanitize_filename(&file_info.short_name));
-        let output_path = output_subdir.join(format!("{}.ogg", filename));
+        let output_path = output_subdir.join(format!("{}.{}", filename, options.output_format.extension()));
 
-        // Skip if already converted
-        if output_path.exists() {
-            processed += 1;
-            let progress = bnk_start + (processed as f32 / total as f32) * (bnk_end - bnk_start);
-            manager.update_status(
-                ExtractionState::InProgress,
-                progress,
-                Some(file_info.short_name.clone()),
-            );
+        // Skip if already converted and cataloged, unless a full re-extraction was requested
+        if !options.force && output_path.exists() && is_cataloged(&catalog, entry.file_id, is_music) {
+            manager.update_plan(|plan| plan.already_extracted += 1);
+            progress.finish(&file_info.short_name, true, false);
             continue;
         }
 
-        // Extract WEM bytes to temp file
-        let wem_path = temp_dir.join(format!("{}.wem", entry.file_id));
-        if let Err(e) = bnk_parser::extract_wem_bytes(&entry, &wem_path) {
-            eprintln!("Failed to extract WEM {}: {}", entry.file_id, e);
-            processed += 1;
+        if dry_run {
+            manager.record_planned((!is_music).then_some(&classification), entry.size as u64);
+            progress.finish(&file_info.short_name, true, false);
             continue;
         }
+        std::fs::create_dir_all(&output_subdir)
+            .map_err(|e| KitharaError::Io(format!("Failed to create output dir: {}", e)))?;
+
+        // Wait for a free conversion slot before starting the next one
+        let permit = Arc::clone(&semaphore)
+            .acquire_owned()
+            .await
+            .map_err(|e| KitharaError::Io(format!("Failed to acquire conversion slot: {}", e)))?;
+
+        let job = WemJob {
+            wem_path: temp_dir.join(format!("{}.wem", entry.file_id)),
+            entry,
+            short_name: file_info.short_name.clone(),
+            output_path,
+            is_music,
+            classification,
+            encode: encode.clone(),
+            measure_duration: options.measure_durations,
+        };
+        let app = app.clone();
+        let manager = Arc::clone(&manager);
+        let catalog = Arc::clone(&catalog);
+        let batch = Arc::clone(&batch);
+        let progress = Arc::clone(&progress);
+        let seen_payloads = seen_payloads.clone();
+
+        tasks.push(tauri::async_runtime::spawn(async move {
+            let _permit = permit;
+            if manager.is_cancelled() {
+                return;
+            }
 
-        // Convert WEM -> WAV -> OGG
-        match converter::convert_wem_to_ogg(&app, &wem_path, &output_path).await {
-            Ok(_) => {
-                if is_music {
-                    // Get duration from the converted file
-                    let duration_secs = converter::get_audio_duration(&output_path)
-                        .await
-                        .unwrap_or(0.0);
-
-                    // Insert into music_tracks table
-                    let track = MusicTrack {
-                        id: format!("{}", entry.file_id),
-                        title: metadata::format_music_title(&file_info.short_name),
-                        file_path: output_path.to_string_lossy().to_string(),
-                        duration_secs,
-                    };
-
-                    if let Err(e) = catalog.insert_music_track(&track) {
-                        eprintln!("Failed to insert music track into catalog: {}", e);
-                    } else {
-                        successful += 1;
-                    }
-                } else {
-                    // Insert into sounds table
-                    let (category, unit_type, subcategory) = metadata::parse_short_name(&file_info.short_name, &known_units);
-                    let sound = Sound {
-                        id: format!("{}", entry.file_id),
-                        event_name: file_info.short_name.clone(),
-                        display_name: metadata::format_short_name_display(&file_info.short_name),
-                        category: category.clone(),
-                        unit_type: unit_type.clone(),
-                        subcategory: subcategory.clone(),
-                        duration: 0.0, // Duration not available from file metadata
-                        file_path: output_path.to_string_lossy().to_string(),
-                        tags: build_tags(&file_info.short_name, &category, unit_type.as_deref()),
-                        is_favorite: false,
-                    };
-
-                    if let Err(e) = catalog.insert_sound(&sound) {
-                        eprintln!("Failed to insert sound into catalog: {}", e);
-                    } else {
-                        successful += 1;
-                    }
+            match process_wem_job(&app, &job, &catalog, &batch, seen_payloads.as_deref()).await {
+                WemOutcome::Added => {
+                    manager.record_extracted(job.is_music);
+                    progress.finish(&job.short_name, true, true);
                 }
+                WemOutcome::Failed(error) => {
+                    manager.record_failure(&app, job.entry.file_id, &job.short_name, error);
+                    progress.finish(&job.short_name, false, true);
+                }
+                WemOutcome::Duplicate => progress.skip(),
             }
-            Err(e) => {
-                eprintln!("Failed to convert {}: {}", file_info.short_name, e);
-            }
-        }
-
-        // Cleanup temp WEM
-        let _ = std::fs::remove_file(&wem_path);
+        }));
+    }
 
-        processed += 1;
-        let progress = bnk_start + (processed as f32 / total as f32) * (bnk_end - bnk_start);
-        manager.update_status(
-            ExtractionState::InProgress,
-            progress,
-            Some(file_info.short_name.clone()),
-        );
+    // Let in-flight conversions finish so their temp files aren't removed underneath them
+    for task in tasks {
+        let _ = task.await;
+    }
+    // Write sounds still buffered from the last partial batch
+    batch.flush();
+
+    if manager.is_cancelled() {
+        // Cleanup temp files
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        // A cancelled run is not offered for resuming
+        let _ = catalog.set_pending_extraction(None);
+        return Err(KitharaError::Cancelled);
     }
+    let successful = progress.successful();
 
     if skipped_no_metadata > 0 {
         println!("Skipped {} files without metadata", skipped_no_metadata);
@@ -342,6 +681,15 @@ pub async fn run_extraction(
         successful
     );
 
+    // Compare category counts against the previous run to catch parser regressions
+    if !dry_run {
+        let warnings = check_category_counts(&catalog);
+        for warning in &warnings {
+            println!("Warning: {}", warning);
+        }
+        manager.set_warnings(warnings);
+    }
+
     // Step 5: Extract streamed music files if requested
     if include_music {
         manager.update_status(
@@ -350,14 +698,14 @@ pub async fn run_extraction(
             Some("Extracting music tracks...".into()),
         );
 
-        let music_result = extract_streamed_music(
+        let music_result = extract_loose_wems(
             &app,
             &game_path,
-            &sounds_dir,
             &catalog,
             &manager,
-            music_start,
-            music_end,
+            &options,
+            LooseWemKind::Music,
+            (music_start, music_end),
         ).await;
 
         if let Err(e) = music_result {
@@ -365,10 +713,437 @@ pub async fn run_extraction(
         }
     }
 
+    if !dry_run {
+        if let Err(e) = catalog.set_pending_extraction(None) {
+            eprintln!("Warning: Failed to clear pending extraction: {}", e);
+        }
+    }
+
     manager.update_status(ExtractionState::Complete, 1.0, None);
     Ok(())
 }
 
+/// Number of recent conversions the ETA is averaged over
+const ETA_WINDOW: usize = 50;
+
+/// Conversions needed before an ETA is reported
+const MIN_ETA_SAMPLES: usize = 5;
+
+/// Counts and ETA for the WEM conversion step, shared across conversion tasks
+struct ConversionProgress {
+    manager: Arc<ExtractionManager>,
+    total: usize,
+    progress_range: (f32, f32),
+    counts: Mutex<ConversionCounts>,
+}
+
+#[derive(Default)]
+struct ConversionCounts {
+    processed: usize,
+    successful: usize,
+    skipped: usize,
+    /// Completion times of the most recent conversions
+    recent: VecDeque<Instant>,
+}
+
+impl ConversionProgress {
+    fn new(manager: Arc<ExtractionManager>, total: usize, progress_range: (f32, f32)) -> Self {
+        Self {
+            manager,
+            total,
+            progress_range,
+            counts: Mutex::new(ConversionCounts::default()),
+        }
+    }
+
+    /// Records an entry that was filtered out without being converted
+    fn skip(&self) {
+        let mut counts = self.counts.lock().unwrap();
+        counts.processed += 1;
+        counts.skipped += 1;
+        self.publish(&counts);
+    }
+
+    /// Records a finished entry. `converted` is false for cached files, which
+    /// complete instantly and would skew the ETA.
+    fn finish(&self, short_name: &str, success: bool, converted: bool) {
+        let mut counts = self.counts.lock().unwrap();
+        counts.processed += 1;
+        if success {
+            counts.successful += 1;
+        }
+        if converted {
+            if counts.recent.len() == ETA_WINDOW {
+                counts.recent.pop_front();
+            }
+            counts.recent.push_back(Instant::now());
+        }
+        self.publish(&counts);
+
+        let (start, end) = self.progress_range;
+        let progress = start + (counts.processed as f32 / self.total as f32) * (end - start);
+        self.manager.update_status(
+            ExtractionState::InProgress,
+            progress,
+            Some(short_name.to_string()),
+        );
+    }
+
+    fn successful(&self) -> usize {
+        self.counts.lock().unwrap().successful
+    }
+
+    fn publish(&self, counts: &ConversionCounts) {
+        self.manager.update_counts(
+            counts.processed as u32,
+            self.total as u32,
+            counts.successful as u32,
+            counts.skipped as u32,
+            self.eta_secs(counts),
+        );
+    }
+
+    /// Remaining time from the average interval between recent conversions
+    fn eta_secs(&self, counts: &ConversionCounts) -> Option<f64> {
+        if counts.recent.len() < MIN_ETA_SAMPLES {
+            return None;
+        }
+        let first = counts.recent.front()?;
+        let last = counts.recent.back()?;
+        let per_file = last.duration_since(*first).as_secs_f64() / (counts.recent.len() - 1) as f64;
+        let remaining = self.total.saturating_sub(counts.processed);
+        Some(per_file * remaining as f64)
+    }
+}
+
+/// Sound rows written to the catalog per transaction
+const INSERT_BATCH_SIZE: usize = 100;
+
+/// Buffers converted sounds and writes them to the catalog in batched transactions.
+/// Sounds are counted as extracted when buffered; rows that fail to insert are moved to `failed`.
+struct SoundBatch {
+    app: AppHandle,
+    catalog: Arc<Catalog>,
+    manager: Arc<ExtractionManager>,
+    pending: Mutex<Vec<Sound>>,
+}
+
+impl SoundBatch {
+    fn new(app: AppHandle, catalog: Arc<Catalog>, manager: Arc<ExtractionManager>) -> Self {
+        Self {
+            app,
+            catalog,
+            manager,
+            pending: Mutex::new(Vec::with_capacity(INSERT_BATCH_SIZE)),
+        }
+    }
+
+    /// Buffers a sound, writing the batch once it is full
+    fn push(&self, sound: Sound) {
+        let full = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.push(sound);
+            if pending.len() >= INSERT_BATCH_SIZE {
+                std::mem::take(&mut *pending)
+            } else {
+                Vec::new()
+            }
+        };
+        if !full.is_empty() {
+            self.write(&full);
+        }
+    }
+
+    /// Writes any buffered sounds
+    fn flush(&self) {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if !pending.is_empty() {
+            self.write(&pending);
+        }
+    }
+
+    fn write(&self, sounds: &[Sound]) {
+        let failures = match self.catalog.insert_sounds_batch(sounds) {
+            Ok(failures) => failures,
+            // The whole transaction was lost, so every row in it failed
+            Err(e) => sounds.iter().map(|sound| (sound.id.clone(), e.clone())).collect(),
+        };
+        if failures.is_empty() {
+            return;
+        }
+
+        for (id, e) in failures {
+            let short_name = sounds
+                .iter()
+                .find(|sound| sound.id == id)
+                .map(|sound| sound.short_name.clone().unwrap_or_else(|| sound.event_name.clone()))
+                .unwrap_or_default();
+            let file_id = id.parse().unwrap_or_default();
+            let error = format!("Failed to insert into catalog: {}", e);
+            self.manager.record_failure(&self.app, file_id, &short_name, error);
+            self.manager
+                .update_summary(|summary| summary.sounds_extracted = summary.sounds_extracted.saturating_sub(1));
+        }
+    }
+}
+
+/// A WEM entry queued for extraction and conversion
+struct WemJob {
+    entry: bnk_parser::WemEntry,
+    short_name: String,
+    wem_path: PathBuf,
+    output_path: PathBuf,
+    is_music: bool,
+    classification: metadata::ParsedName,
+    encode: EncodeOptions,
+    measure_duration: bool,
+}
+
+/// Returns true if the catalog already has a row for this WEM file ID
+fn is_cataloged(catalog: &Catalog, file_id: u32, is_music: bool) -> bool {
+    let id = file_id.to_string();
+    let result = if is_music {
+        catalog.has_music_track(&id)
+    } else {
+        catalog.has_sound(&id)
+    };
+    result.unwrap_or(false)
+}
+
+/// ffmpeg encoding settings for an extraction run
+fn encode_options(options: &ExtractionOptions) -> EncodeOptions {
+    EncodeOptions {
+        format: options.output_format,
+        normalize: options.normalize,
+        trim_silence: options.trim_silence,
+    }
+}
+
+/// ffmpeg `-metadata` tags for a converted file
+fn embedded_tags(title: &str, tags: &[String]) -> Vec<(&'static str, String)> {
+    let mut embedded = vec![("title", title.to_string()), ("album", "Old World".to_string())];
+    if !tags.is_empty() {
+        embedded.push(("comment", tags.join(", ")));
+    }
+    embedded
+}
+
+/// Duration to catalog for a converted sound, measured from the output file.
+/// Sounds get 0 when measuring is turned off to save an ffprobe call per file.
+async fn sound_duration(app: &AppHandle, measure: bool, output_path: &Path) -> f64 {
+    if !measure {
+        return 0.0;
+    }
+    converter::get_audio_duration(app, output_path).await.unwrap_or_else(|e| {
+        eprintln!("Warning: {}", e);
+        0.0
+    })
+}
+
+/// Upper bound on the default concurrency; more ffmpeg/vgmstream processes
+/// than this tends to thrash laptops
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Default number of WEM conversions to run at once
+fn default_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(DEFAULT_MAX_CONCURRENCY)
+}
+
+/// Make sure `dir` exists (creating it if needed) and that files can be written to it
+fn check_writable(dir: &Path) -> Result<(), KitharaError> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| KitharaError::Io(format!("Cannot create output directory {}: {}", dir.display(), e)))?;
+
+    let probe = dir.join(".kithara-write-test");
+    std::fs::write(&probe, b"")
+        .map_err(|e| KitharaError::Io(format!("Output directory {} is not writable: {}", dir.display(), e)))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Check extraction options before starting a run. A dry run writes nothing, so its
+/// output directory is left uncreated.
+pub fn validate_options(options: &ExtractionOptions) -> Result<(), KitharaError> {
+    if !options.include_sounds && !options.include_music {
+        return Err(KitharaError::InvalidInput("Nothing to extract: include sounds, music, or both".into()));
+    }
+    if options.max_concurrency == Some(0) {
+        return Err(KitharaError::InvalidInput("Concurrency limit must be at least 1".into()));
+    }
+    if let Some(dir) = options.output_dir.as_ref().filter(|_| !options.dry_run) {
+        check_writable(Path::new(dir))?;
+    }
+    Ok(())
+}
+
+/// Result of processing one WEM entry
+enum WemOutcome {
+    /// Converted and added to the catalog
+    Added,
+    /// Extraction, conversion or the catalog insert failed, with the reason
+    Failed(String),
+    /// Identical audio was already converted under another file ID
+    Duplicate,
+}
+
+/// Hash a WEM file's bytes to detect identical payloads
+fn payload_hash(wem_path: &Path) -> Result<u64, KitharaError> {
+    let bytes = std::fs::read(wem_path).map_err(|e| KitharaError::Io(format!("Failed to read WEM: {}", e)))?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Extracts one WEM entry, converts it to the requested format, and inserts it into the catalog.
+/// With `seen_payloads`, entries whose bytes match an earlier entry are skipped.
+async fn process_wem_job(
+    app: &AppHandle,
+    job: &WemJob,
+    catalog: &Catalog,
+    batch: &SoundBatch,
+    seen_payloads: Option<&Mutex<HashSet<u64>>>,
+) -> WemOutcome {
+    // Extract WEM bytes to temp file
+    if let Err(e) = bnk_parser::extract_wem_bytes(&job.entry, &job.wem_path) {
+        return WemOutcome::Failed(e.to_string());
+    }
+
+    if let Some(seen_payloads) = seen_payloads {
+        match payload_hash(&job.wem_path) {
+            Ok(hash) if !seen_payloads.lock().unwrap().insert(hash) => {
+                let _ = std::fs::remove_file(&job.wem_path);
+                return WemOutcome::Duplicate;
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to hash WEM {}: {}", job.entry.file_id, e),
+        }
+    }
+
+    // Tags embedded in the output file, so it's self-describing in other apps
+    let parsed = &job.classification;
+    let gender = gender_tag(parsed.is_female, &parsed.category);
+    let sound_tags = build_tags(&job.short_name, &parsed.category, parsed.unit_type.as_deref(), gender.as_deref());
+    let file_tags = if job.is_music {
+        embedded_tags(&metadata::format_music_title(&job.short_name), &[])
+    } else {
+        embedded_tags(&metadata::format_short_name_display(&job.short_name), &sound_tags)
+    };
+
+    // Convert WEM -> WAV -> output format
+    let outcome = match converter::convert_wem(app, &job.wem_path, &job.output_path, &job.encode, &file_tags).await {
+        Ok(_) => {
+            if job.is_music {
+                // Get duration from the converted file
+                let duration_secs = converter::get_audio_duration_or_estimate(app, &job.output_path).await;
+
+                // Insert into music_tracks table
+                let track = MusicTrack {
+                    id: format!("{}", job.entry.file_id),
+                    title: metadata::format_music_title(&job.short_name),
+                    file_path: job.output_path.to_string_lossy().to_string(),
+                    duration_secs,
+                };
+
+                match catalog.insert_music_track(&track) {
+                    Ok(()) => WemOutcome::Added,
+                    Err(e) => WemOutcome::Failed(format!("Failed to insert music track into catalog: {}", e)),
+                }
+            } else {
+                // Insert into sounds table
+                let metadata::ParsedName {
+                    category,
+                    unit_type,
+                    subcategory,
+                    language,
+                    is_loop,
+                    ..
+                } = job.classification.clone();
+                let duration = sound_duration(app, job.measure_duration, &job.output_path).await;
+                let sound = Sound {
+                    id: format!("{}", job.entry.file_id),
+                    event_name: job.short_name.clone(),
+                    display_name: metadata::format_short_name_display(&job.short_name),
+                    category,
+                    unit_type,
+                    subcategory,
+                    duration,
+                    file_path: job.output_path.to_string_lossy().to_string(),
+                    tags: sound_tags,
+                    is_favorite: false,
+                    gender,
+                    language,
+                    // Prefer the bank's own name over its file name
+                    source_bank: job.entry.bank_name.clone().or_else(|| {
+                        job.entry
+                            .bnk_path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                    }),
+                    gain_db: 0.0,
+                    is_loop,
+                    short_name: Some(job.short_name.clone()),
+                    available: true,
+                };
+
+                batch.push(sound);
+                WemOutcome::Added
+            }
+        }
+        Err(e) => WemOutcome::Failed(format!("Failed to convert: {}", e)),
+    };
+
+    // Cleanup temp WEM
+    let _ = std::fs::remove_file(&job.wem_path);
+
+    outcome
+}
+
+/// Categories whose count drops below this fraction of the previous run are flagged
+const CATEGORY_DROP_THRESHOLD: f64 = 0.5;
+
+/// Compare per-category sound counts with those saved by the previous extraction,
+/// then save the current counts. Returns a warning for each category that is now
+/// empty or dropped sharply.
+fn check_category_counts(catalog: &Catalog) -> Vec<String> {
+    let current: HashMap<String, u32> = match catalog.get_categories() {
+        Ok(categories) => categories.into_iter().map(|c| (c.id, c.count)).collect(),
+        Err(e) => {
+            eprintln!("Failed to count categories: {}", e);
+            return Vec::new();
+        }
+    };
+    let previous = catalog.get_saved_category_counts().unwrap_or_default();
+
+    let mut warnings = Vec::new();
+    for (category, &previous_count) in &previous {
+        if previous_count == 0 {
+            continue;
+        }
+        let current_count = current.get(category).copied().unwrap_or(0);
+        if current_count == 0 {
+            warnings.push(format!(
+                "Category '{}' has no sounds (previously {})",
+                category, previous_count
+            ));
+        } else if (current_count as f64) < previous_count as f64 * CATEGORY_DROP_THRESHOLD {
+            warnings.push(format!(
+                "Category '{}' dropped from {} to {} sounds",
+                category, previous_count, current_count
+            ));
+        }
+    }
+    warnings.sort();
+
+    if let Err(e) = catalog.save_category_counts(&current) {
+        eprintln!("Failed to save category counts: {}", e);
+    }
+
+    warnings
+}
+
 /// Sanitize a filename by removing/replacing invalid characters
 fn sanitize_filename(name: &str) -> String {
     name.chars()
@@ -379,14 +1154,103 @@ fn sanitize_filename(name: &str) -> String {
         .collect()
 }
 
+/// Built-in unit abbreviations plus any the user has taught us
+fn unit_aliases() -> Vec<(String, String)> {
+    metadata::get_unit_aliases_path()
+        .and_then(|path| metadata::load_unit_aliases(&path))
+        .unwrap_or_else(|e| {
+            println!("Warning: {}. Using built-in unit aliases only.", e);
+            metadata::builtin_unit_aliases()
+        })
+}
+
+/// Re-runs the short_name heuristics over every cataloged sound that kept its
+/// short_name and updates its classification in place, without touching audio.
+/// A category or unit the heuristics can't settle (often one that came from
+/// Events.xml) is kept, as are tags the user added. Returns how many sounds changed.
+pub fn recategorize_all(catalog: &Catalog) -> Result<usize, KitharaError> {
+    let known_units = catalog.get_known_units()?;
+    let aliases = unit_aliases();
+
+    let mut changed = Vec::new();
+    for sound in catalog.get_all_sounds()? {
+        let Some(short_name) = sound.short_name.as_deref() else {
+            continue;
+        };
+        let parsed = metadata::parse_short_name(short_name, &known_units, &aliases);
+        let category = if parsed.category == "other" {
+            sound.category.clone()
+        } else {
+            parsed.category
+        };
+        let unit_type = parsed.unit_type.or_else(|| sound.unit_type.clone());
+        // Which voices are male depends on the category; female variants may have
+        // been marked by the event name, which isn't stored
+        let is_female = parsed.is_female || sound.gender.as_deref() == Some("female");
+        let gender = gender_tag(is_female, &category);
+
+        // Tags the old classification wouldn't have generated were added by the user
+        let generated = build_tags(short_name, &sound.category, sound.unit_type.as_deref(), sound.gender.as_deref());
+        let mut tags = build_tags(short_name, &category, unit_type.as_deref(), gender.as_deref());
+        for tag in &sound.tags {
+            if !generated.contains(tag) && !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+
+        let updated = Sound {
+            category,
+            unit_type,
+            subcategory: parsed.subcategory,
+            display_name: metadata::format_short_name_display(short_name),
+            tags,
+            gender,
+            ..sound.clone()
+        };
+        if updated.category != sound.category
+            || updated.unit_type != sound.unit_type
+            || updated.subcategory != sound.subcategory
+            || updated.display_name != sound.display_name
+            || updated.tags != sound.tags
+            || updated.gender != sound.gender
+        {
+            changed.push(updated);
+        }
+    }
+
+    catalog.update_classifications(&changed)?;
+    Ok(changed.len())
+}
+
+/// The catalog gender for a sound. Female variants are marked in their names;
+/// other voice lines are the default male voices. Non-vocal sounds have none.
+fn gender_tag(is_female: bool, category: &str) -> Option<String> {
+    if is_female {
+        Some("female".to_string())
+    } else if category == "vocal" {
+        Some("male".to_string())
+    } else {
+        None
+    }
+}
+
 /// Build searchable tags from event metadata
-fn build_tags(event_name: &str, category: &str, unit_type: Option<&str>) -> Vec<String> {
+fn build_tags(
+    event_name: &str,
+    category: &str,
+    unit_type: Option<&str>,
+    gender: Option<&str>,
+) -> Vec<String> {
     let mut tags = vec![category.to_string()];
 
     if let Some(unit) = unit_type {
         tags.push(unit.to_lowercase());
     }
 
+    if let Some(gender) = gender {
+        tags.push(gender.to_string());
+    }
+
     // Add action keywords as tags
     let keywords = ["attack", "death", "hit", "run", "vocal", "impact", "step"];
     let name_lower = event_name.to_lowercase();
@@ -426,33 +1290,57 @@ fn is_excluded(name: &str, include_music: bool) -> bool {
     false
 }
 
-/// Extract streamed music files (loose WEM files referenced in SoundbanksInfo.xml)
-async fn extract_streamed_music(
+/// Check a sound category against the optional allowlist.
+/// No allowlist (or an empty one) selects every category.
+fn is_category_selected(category: &str, allowlist: Option<&[String]>) -> bool {
+    match allowlist {
+        Some(categories) if !categories.is_empty() => {
+            categories.iter().any(|c| c.eq_ignore_ascii_case(category))
+        }
+        _ => true,
+    }
+}
+
+/// What loose WEM files are cataloged as
+enum LooseWemKind<'a> {
+    /// Every streamed file is a music track (the normal Old World layout)
+    Music,
+    /// Streamed files are sound effects, classified from their short names.
+    /// Music-named files become music tracks when music is included.
+    Sounds {
+        known_units: &'a [String],
+        aliases: &'a [(String, String)],
+    },
+}
+
+/// Extract loose WEM files referenced in SoundbanksInfo.xml, either as music
+/// tracks or as sounds when the game has no embedded audio.
+async fn extract_loose_wems(
     app: &AppHandle,
     game_path: &PathBuf,
-    sounds_dir: &PathBuf,
     catalog: &Arc<Catalog>,
     manager: &Arc<ExtractionManager>,
-    progress_start: f32,
-    progress_end: f32,
-) -> Result<(), String> {
+    options: &ExtractionOptions,
+    kind: LooseWemKind<'_>,
+    (progress_start, progress_end): (f32, f32),
+) -> Result<(), KitharaError> {
     // Parse SoundbanksInfo.xml to get streamed file mappings
     let soundbanks_info_path = game_path.join("SoundbanksInfo.xml");
     if !soundbanks_info_path.exists() {
-        return Err("SoundbanksInfo.xml not found".into());
+        return Err(KitharaError::NotFound("SoundbanksInfo.xml not found".into()));
     }
 
     let streamed_files = metadata::parse_soundbanks_info_xml(&soundbanks_info_path)?;
-    println!("Found {} streamed music files", streamed_files.len());
+    println!("Found {} streamed files", streamed_files.len());
 
     if streamed_files.is_empty() {
         return Ok(());
     }
 
-    // Create music output directory
+    let sounds_dir = output_sounds_dir(catalog, options)?;
     let music_dir = sounds_dir.join("music");
-    std::fs::create_dir_all(&music_dir)
-        .map_err(|e| format!("Failed to create music dir: {}", e))?;
+    let extension = options.output_format.extension();
+    let encode = encode_options(options);
 
     let total = streamed_files.len();
     let mut processed = 0;
@@ -460,7 +1348,7 @@ async fn extract_streamed_music(
 
     for (file_id, file_info) in &streamed_files {
         if manager.is_cancelled() {
-            return Err("Extraction cancelled".into());
+            return Err(KitharaError::Cancelled);
         }
 
         // The WEM file should exist as {file_id}.wem in the game directory
@@ -470,49 +1358,136 @@ async fn extract_streamed_music(
             continue;
         }
 
-        // Generate output filename
-        let title = metadata::format_streamed_music_title(&file_info.short_name);
-        let safe_title = sanitize_filename(&title);
-        let output_path = music_dir.join(format!("{}_{}.ogg", file_id, safe_title));
+        // Decide where the file goes: a music track, a sound, or nowhere
+        let (is_music, sound_class) = match kind {
+            LooseWemKind::Music => (true, None),
+            LooseWemKind::Sounds { known_units, aliases } => {
+                if is_excluded(&file_info.short_name, options.include_music) {
+                    manager.update_summary(|summary| summary.excluded += 1);
+                    processed += 1;
+                    continue;
+                }
+                if is_music_file(&file_info.short_name) {
+                    (true, None)
+                } else if !options.include_sounds {
+                    processed += 1;
+                    continue;
+                } else {
+                    let classification = metadata::parse_short_name(&file_info.short_name, known_units, aliases);
+                    if !is_category_selected(&classification.category, options.categories.as_deref()) {
+                        processed += 1;
+                        continue;
+                    }
+                    (false, Some(classification))
+                }
+            }
+        };
+
+        // Generate output path
+        let (title, output_path) = match &sound_class {
+            None => {
+                let title = metadata::format_streamed_music_title(&file_info.short_name);
+                let filename = format!("{}_{}.{}", file_id, sanitize_filename(&title), extension);
+                (title, music_dir.join(filename))
+            }
+            Some(parsed) => {
+                let mut dir = sounds_dir.join(&parsed.category);
+                if let Some(unit) = &parsed.unit_type {
+                    dir = dir.join(unit.to_lowercase());
+                }
+                let filename = format!("{}_{}.{}", file_id, sanitize_filename(&file_info.short_name), extension);
+                (metadata::format_short_name_display(&file_info.short_name), dir.join(filename))
+            }
+        };
+        let label = if is_music { "Music" } else { "Sound" };
 
         // Skip if already converted
-        if output_path.exists() {
+        if !options.force && output_path.exists() && (is_music || is_cataloged(catalog, *file_id, false)) {
+            manager.update_plan(|plan| plan.already_extracted += 1);
             processed += 1;
             successful += 1;
             let progress = progress_start + (processed as f32 / total as f32) * (progress_end - progress_start);
             manager.update_status(
                 ExtractionState::InProgress,
                 progress,
-                Some(format!("Music: {} (cached)", title)),
+                Some(format!("{}: {} (cached)", label, title)),
             );
             continue;
         }
 
-        // Convert WEM -> OGG
-        match converter::convert_wem_to_ogg(app, &wem_path, &output_path).await {
-            Ok(_) => {
-                // Get duration from the converted file
-                let duration_secs = converter::get_audio_duration(&output_path)
-                    .await
-                    .unwrap_or(0.0);
+        if options.dry_run {
+            let source_bytes = std::fs::metadata(&wem_path).map(|m| m.len()).unwrap_or(0);
+            manager.record_planned(sound_class.as_ref(), source_bytes);
+            processed += 1;
+            successful += 1;
+            continue;
+        }
+        if let Some(dir) = output_path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| KitharaError::Io(format!("Failed to create output dir: {}", e)))?;
+        }
 
-                // Insert into music_tracks table
-                let track = MusicTrack {
-                    id: format!("{}", file_id),
-                    title: title.clone(),
-                    file_path: output_path.to_string_lossy().to_string(),
-                    duration_secs,
+        // Tags embedded in the output file
+        let gender = sound_class.as_ref().and_then(|parsed| gender_tag(parsed.is_female, &parsed.category));
+        let sound_tags = match &sound_class {
+            Some(parsed) => {
+                build_tags(&file_info.short_name, &parsed.category, parsed.unit_type.as_deref(), gender.as_deref())
+            }
+            None => Vec::new(),
+        };
+        let file_tags = embedded_tags(&title, &sound_tags);
+
+        // Convert WEM -> output format
+        match converter::convert_wem(app, &wem_path, &output_path, &encode, &file_tags).await {
+            Ok(_) => {
+                let result = match sound_class {
+                    None => {
+                        // Get duration from the converted file
+                        let duration_secs = converter::get_audio_duration_or_estimate(app, &output_path).await;
+
+                        // Insert into music_tracks table
+                        catalog.insert_music_track(&MusicTrack {
+                            id: format!("{}", file_id),
+                            title: title.clone(),
+                            file_path: output_path.to_string_lossy().to_string(),
+                            duration_secs,
+                        })
+                    }
+                    Some(parsed) => {
+                        // Insert into sounds table
+                        let duration = sound_duration(app, options.measure_durations, &output_path).await;
+                        catalog.insert_sound(&Sound {
+                            id: format!("{}", file_id),
+                            event_name: file_info.short_name.clone(),
+                            display_name: title.clone(),
+                            tags: sound_tags,
+                            category: parsed.category,
+                            unit_type: parsed.unit_type,
+                            subcategory: parsed.subcategory,
+                            duration,
+                            file_path: output_path.to_string_lossy().to_string(),
+                            is_favorite: false,
+                            gender,
+                            language: parsed.language,
+                            // Loose files aren't in any bank
+                            source_bank: None,
+                            gain_db: 0.0,
+                            is_loop: parsed.is_loop,
+                            short_name: Some(file_info.short_name.clone()),
+                            available: true,
+                        })
+                    }
                 };
 
-                if let Err(e) = catalog.insert_music_track(&track) {
-                    eprintln!("Failed to insert music track into catalog: {}", e);
+                if let Err(e) = result {
+                    let error = format!("Failed to insert into catalog: {}", e);
+                    manager.record_failure(app, *file_id, &file_info.short_name, error);
                 } else {
                     successful += 1;
+                    manager.record_extracted(is_music);
                 }
             }
-            Err(e) => {
-                eprintln!("Failed to convert music file {}: {}", file_info.short_name, e);
-            }
+            Err(e) => manager.record_failure(app, *file_id, &file_info.short_name, format!("Failed to convert: {}", e)),
         }
 
         processed += 1;
@@ -520,10 +1495,10 @@ async fn extract_streamed_music(
         manager.update_status(
             ExtractionState::InProgress,
             progress,
-            Some(format!("Music: {}", title)),
+            Some(format!("{}: {}", label, title)),
         );
     }
 
-    println!("Music extraction complete: {} tracks extracted", successful);
+    println!("Loose WEM extraction complete: {} files extracted", successful);
     Ok(())
 }
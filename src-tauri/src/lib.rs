@@ -1,24 +1,35 @@
 mod catalog;
 mod commands;
+mod decoder;
+mod error;
+mod export;
 mod extractor;
+mod maintenance;
 mod models;
 mod player;
+mod protocol;
 
 use catalog::{get_db_path, Catalog};
+use error::KitharaError;
 use extractor::ExtractionManager;
-use player::create_player_state;
+use player::{create_player_state, PlayerState};
 use std::sync::Arc;
-use tauri::Manager;
+use tauri::{Manager, RunEvent};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let player_state = create_player_state().expect("Failed to initialize audio player");
-
     // Initialize catalog database
     let db_path = get_db_path().expect("Failed to determine database path");
     println!("Database path: {:?}", db_path);
     let catalog = Catalog::open(db_path).expect("Failed to initialize catalog");
 
+    // Restore the last volume so playback doesn't start at full volume,
+    // and the output device the user picked last time
+    let initial_volume = catalog.get_saved_volume().ok().flatten().unwrap_or(1.0);
+    let output_device = catalog.get_saved_output_device().ok().flatten();
+    let player_state = create_player_state(initial_volume, output_device)
+        .expect("Failed to initialize audio player");
+
     // Initialize extraction manager
     let extraction_manager = Arc::new(ExtractionManager::new());
 
@@ -27,32 +38,92 @@ pub fn run() {
         .manage(player_state)
         .manage(catalog)
         .manage(extraction_manager)
+        // Serves cataloged audio by ID with range support, for <audio> and waveform views
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, protocol::handle)
         .invoke_handler(tauri::generate_handler![
             commands::search_sounds,
             commands::get_categories,
+            commands::get_subcategories,
             commands::get_unit_types,
+            commands::get_languages,
+            commands::get_sounds_by_bank,
+            commands::get_similar_sounds,
             commands::toggle_favorite,
+            commands::set_favorites_bulk,
+            commands::set_sound_gain,
+            commands::set_category_gain,
+            commands::get_category_gains,
+            commands::delete_sound,
+            commands::reveal_in_explorer,
+            commands::add_sound_tag,
+            commands::remove_sound_tag,
             commands::get_favorites_count,
             commands::get_favorites,
+            commands::get_catalog_stats,
+            commands::export_catalog,
+            commands::create_playlist,
+            commands::delete_playlist,
+            commands::get_playlists,
+            commands::add_to_playlist,
+            commands::remove_from_playlist,
+            commands::get_playlist_sounds,
+            commands::export_favorites_zip,
+            commands::export_sounds,
+            commands::export_soundboard,
             commands::play_sound,
+            commands::play_sound_layered,
+            commands::preload_sound,
+            commands::audition_wem,
+            commands::enqueue_sound,
+            commands::clear_queue,
+            commands::skip_next,
+            commands::set_repeat_mode,
             commands::stop_sound,
+            commands::stop_sound_immediate,
+            commands::stop_all_sounds,
+            commands::get_last_session,
             commands::pause_sound,
             commands::resume_sound,
             commands::seek_sound,
+            commands::seek_relative,
             commands::set_volume,
+            commands::toggle_mute,
+            commands::start_sleep_timer,
+            commands::cancel_sleep_timer,
+            commands::list_output_devices,
+            commands::select_output_device,
+            commands::set_playback_speed,
             commands::get_playback_status,
             commands::get_extraction_status,
+            commands::get_pending_extraction,
             commands::start_extraction,
             commands::update_library,
             commands::cancel_extraction,
             commands::check_audio_dependencies,
+            commands::check_dependencies,
+            commands::backup_database,
+            commands::restore_database,
+            commands::optimize_database,
+            commands::verify_catalog,
+            commands::prune_orphaned_sounds,
+            commands::recategorize_all,
             commands::clear_cache,
+            commands::clear_output_dir,
             commands::detect_game_path,
+            commands::get_last_game_path,
+            commands::validate_game_path,
             commands::get_music_tracks,
             commands::search_music_tracks,
             commands::get_music_tracks_count,
+            commands::play_music_track,
+            commands::shuffle_music,
+            commands::backfill_music_durations,
         ])
         .setup(|app| {
+            // Push playback status to the frontend instead of requiring polling
+            let player = app.state::<PlayerState>();
+            player::spawn_status_emitter(app.handle().clone(), Arc::clone(&player));
+
             let catalog = app.state::<Catalog>();
 
             // Run one-time data migrations
@@ -79,17 +150,23 @@ pub fn run() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Remember what was playing so the next launch can offer to resume it
+            if let RunEvent::Exit = event {
+                commands::save_last_session(&app.state::<PlayerState>(), &app.state::<Catalog>());
+            }
+        });
 }
 
 /// Seeds the database with test sounds from bundled resources.
-fn seed_test_sounds(app: &tauri::App) -> Result<(), String> {
+fn seed_test_sounds(app: &tauri::App) -> Result<(), KitharaError> {
     let catalog = app.state::<Catalog>();
     let resource_path = app
         .path()
         .resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?;
+        .map_err(|e| KitharaError::Io(format!("Failed to get resource dir: {}", e)))?;
 
     let test_sounds = vec![
         models::Sound {
@@ -106,6 +183,13 @@ fn seed_test_sounds(app: &tauri::App) -> Result<(), String> {
                 .to_string(),
             tags: vec!["test".to_string(), "short".to_string()],
             is_favorite: false,
+            gender: None,
+            language: None,
+            source_bank: None,
+            gain_db: 0.0,
+            is_loop: false,
+            short_name: None,
+            available: true,
         },
         models::Sound {
             id: "test-medium".to_string(),
@@ -121,6 +205,13 @@ fn seed_test_sounds(app: &tauri::App) -> Result<(), String> {
                 .to_string(),
             tags: vec!["test".to_string(), "medium".to_string()],
             is_favorite: false,
+            gender: None,
+            language: None,
+            source_bank: None,
+            gain_db: 0.0,
+            is_loop: false,
+            short_name: None,
+            available: true,
         },
         models::Sound {
             id: "test-long".to_string(),
@@ -136,6 +227,13 @@ fn seed_test_sounds(app: &tauri::App) -> Result<(), String> {
                 .to_string(),
             tags: vec!["test".to_string(), "long".to_string()],
             is_favorite: false,
+            gender: None,
+            language: None,
+            source_bank: None,
+            gain_db: 0.0,
+            is_loop: false,
+            short_name: None,
+            available: true,
         },
     ];
 
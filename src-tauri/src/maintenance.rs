@@ -0,0 +1,79 @@
+//! Long-running filesystem and database maintenance tasks.
+//! Tasks report progress through a callback so commands can forward it as events.
+
+use crate::error::KitharaError;
+use crate::models::MaintenanceProgress;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Report progress at most this many times over a task, so big deletes don't flood the UI
+const PROGRESS_STEPS: usize = 100;
+
+/// Delete a directory and everything in it, reporting progress per file.
+/// Files are counted first so progress is a true fraction of the work.
+pub fn remove_dir_with_progress(
+    dir: &Path,
+    mut on_progress: impl FnMut(MaintenanceProgress),
+) -> Result<(), KitharaError> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    on_progress(MaintenanceProgress {
+        progress: 0.0,
+        message: "Counting files...".into(),
+    });
+    let files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_type().is_dir())
+        .map(|entry| entry.into_path())
+        .collect();
+
+    let total = files.len();
+    let report_every = (total / PROGRESS_STEPS).max(1);
+    for (i, file) in files.iter().enumerate() {
+        std::fs::remove_file(file)
+            .map_err(|e| KitharaError::Io(format!("Failed to delete {}: {}", file.display(), e)))?;
+
+        let done = i + 1;
+        if done % report_every == 0 || done == total {
+            on_progress(MaintenanceProgress {
+                progress: done as f32 / total as f32,
+                message: format!("Deleted {} of {} files", done, total),
+            });
+        }
+    }
+
+    // Only empty directories are left
+    std::fs::remove_dir_all(dir)
+        .map_err(|e| KitharaError::Io(format!("Failed to delete {}: {}", dir.display(), e)))?;
+
+    on_progress(MaintenanceProgress {
+        progress: 1.0,
+        message: format!("Deleted {} files", total),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_dir_with_progress() {
+        let dir = std::env::temp_dir().join(format!("kithara-test-remove-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("combat/archer")).unwrap();
+        for name in ["a.ogg", "b.ogg", "combat/c.ogg", "combat/archer/d.ogg"] {
+            std::fs::write(dir.join(name), b"ogg").unwrap();
+        }
+
+        let mut updates = Vec::new();
+        remove_dir_with_progress(&dir, |p| updates.push(p)).unwrap();
+
+        assert!(!dir.exists());
+        assert!(updates.iter().any(|p| p.message == "Deleted 4 of 4 files"));
+        assert_eq!(updates.last().unwrap().progress, 1.0);
+        assert!(updates.windows(2).all(|w| w[0].progress <= w[1].progress));
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[cfg(test)]
 use ts_rs::TS;
@@ -18,6 +19,22 @@ pub struct Sound {
     pub file_path: String,
     pub tags: Vec<String>,
     pub is_favorite: bool,
+    /// Voice gender (e.g. "female"); female variants are named as such, other voice lines are male
+    pub gender: Option<String>,
+    /// Locale of a localized voice line (e.g. "fr"), if its path indicates one
+    pub language: Option<String>,
+    /// Soundbank the audio was extracted from, if any: its STID name, else its file name
+    pub source_bank: Option<String>,
+    /// Per-sound gain trim in decibels, applied on top of the player volume
+    pub gain_db: f64,
+    /// Designed to loop seamlessly (ambiences, engine loops); plays with repeat-one
+    pub is_loop: bool,
+    /// Soundbank short_name the sound was classified from, kept so it can be
+    /// re-classified without re-extracting. None for sounds added some other way.
+    pub short_name: Option<String>,
+    /// Whether `file_path` exists on disk. Computed, not stored: only listings that
+    /// run `catalog::mark_availability` check it, everything else assumes true.
+    pub available: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +55,16 @@ pub struct UnitType {
     pub count: u32,
 }
 
+/// A subcategory within one category, for category -> subcategory -> sounds browsing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+pub struct Subcategory {
+    pub id: String,
+    pub name: String,
+    pub count: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(test, derive(TS))]
 #[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
@@ -58,6 +85,211 @@ pub struct ExtractionStatus {
     pub progress: f32,
     pub current_file: Option<String>,
     pub error: Option<String>,
+    /// Non-fatal issues found after extraction (e.g. categories that came up empty)
+    pub warnings: Vec<String>,
+    /// WEM entries handled so far, out of `total`
+    pub processed: u32,
+    pub total: u32,
+    /// Entries converted or already present in the library
+    pub successful: u32,
+    /// Entries left out (no metadata, excluded, or outside the selected categories)
+    pub skipped: u32,
+    /// Estimated seconds remaining; None until enough files have been converted
+    pub eta_secs: Option<f64>,
+}
+
+/// Report sent with the "extraction-complete" event, for both finished and failed runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionSummary {
+    /// Sounds newly converted and added to the library
+    pub sounds_extracted: u32,
+    /// Music tracks newly converted and added to the library
+    pub music_extracted: u32,
+    /// Soundbank entries with no file metadata to name them
+    pub skipped_no_metadata: u32,
+    /// Entries matching the exclusion patterns
+    pub excluded: u32,
+    /// Entries whose conversion or cataloging failed
+    pub failed: u32,
+    /// What went wrong with each failed entry
+    pub failures: Vec<ExtractionError>,
+    pub elapsed_secs: f64,
+    /// Why the run stopped early; None when it finished
+    pub error: Option<String>,
+}
+
+/// One entry that failed to extract, sent as an `extraction-error` event when it
+/// happens and listed in the completion summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionError {
+    pub file_id: u32,
+    pub short_name: String,
+    pub error: String,
+}
+
+/// Preview of what an extraction would do, sent as the "extraction-plan" event
+/// when a dry run finishes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionPlan {
+    /// Sounds that would be converted
+    pub sounds: u32,
+    /// Music tracks that would be converted
+    pub music_tracks: u32,
+    /// Entries matching the exclusion patterns
+    pub excluded: u32,
+    /// Soundbank entries with no file metadata to name them
+    pub skipped_no_metadata: u32,
+    /// Entries already converted and cataloged, which would be skipped
+    pub already_extracted: u32,
+    /// Sounds that would be converted, by category
+    pub categories: BTreeMap<String, u32>,
+    /// Sounds that would be converted, by unit type
+    pub unit_types: BTreeMap<String, u32>,
+    /// Size of the WEM audio that would be converted; OGG output is of a similar size
+    #[cfg_attr(test, ts(type = "number"))]
+    pub source_bytes: u64,
+}
+
+/// Options for an extraction run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExtractionOptions {
+    /// Extract sound effects and voice lines; on by default
+    pub include_sounds: bool,
+    /// Also extract music tracks
+    pub include_music: bool,
+    /// Re-convert sounds even if they were already extracted and cataloged
+    pub force: bool,
+    /// Audio format the extracted files are written in
+    pub output_format: OutputFormat,
+    /// Only extract sounds in these categories (all categories if unset)
+    pub categories: Option<Vec<String>>,
+    /// Continue an interrupted extraction with the options it was started with
+    pub resume: bool,
+    /// Normalize loudness (EBU R128, -16 LUFS); slower, so off by default
+    pub normalize: bool,
+    /// Strip leading and trailing silence; off by default to keep the exact source audio
+    pub trim_silence: bool,
+    /// Measure each sound's duration with ffprobe; on by default, turn off for faster runs
+    pub measure_durations: bool,
+    /// Conversions to run at once; defaults to the last value used, else min(CPUs, 4)
+    pub max_concurrency: Option<usize>,
+    /// Directory to write sounds to instead of the app cache, inside a `kithara-sounds`
+    /// subfolder; remembered for later runs
+    pub output_dir: Option<String>,
+    /// Also skip WEMs whose bytes match one already converted under a different ID
+    pub dedupe_payloads: bool,
+    /// Only report what would be extracted, as an `ExtractionPlan`, without converting anything
+    pub dry_run: bool,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            include_sounds: true,
+            include_music: false,
+            force: false,
+            output_format: OutputFormat::default(),
+            categories: None,
+            resume: false,
+            normalize: false,
+            trim_silence: false,
+            measure_durations: true,
+            max_concurrency: None,
+            output_dir: None,
+            dedupe_payloads: false,
+            dry_run: false,
+        }
+    }
+}
+
+/// One expected file in a game audio folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct GameFileCheck {
+    pub name: String,
+    pub found: bool,
+    /// Extraction can't run without it
+    pub required: bool,
+}
+
+/// Result of checking a folder for the game's audio files, for a setup checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct GamePathReport {
+    pub path_exists: bool,
+    pub files: Vec<GameFileCheck>,
+    /// Soundbanks with both their XML and BNK present
+    pub soundbank_count: u32,
+    /// Loose `.wem` files streamed from outside the soundbanks
+    pub streamed_wem_count: u32,
+    /// Whether extraction can run from this folder
+    pub is_valid: bool,
+    /// Everything missing that extraction needs, for error messages
+    pub missing: Vec<String>,
+}
+
+/// Availability of the external tools extraction relies on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyStatus {
+    pub vgmstream: bool,
+    pub ffmpeg: bool,
+    /// Names of the missing tools ("vgmstream", "ffmpeg")
+    pub missing: Vec<String>,
+}
+
+/// Audio format for extracted sounds and music
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Vorbis in an OGG container; quality ranges from 0 (smallest) to 10 (best)
+    OggVorbis { quality: u8 },
+    /// Uncompressed PCM, straight from vgmstream
+    Wav,
+    /// Lossless compression, for re-mixing
+    Flac,
+    Mp3 {
+        #[serde(rename = "bitrateKbps")]
+        bitrate_kbps: u32,
+    },
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::OggVorbis { quality: 4 }
+    }
+}
+
+impl OutputFormat {
+    /// File extension for this format, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::OggVorbis { .. } => "ogg",
+            OutputFormat::Wav => "wav",
+            OutputFormat::Flac => "flac",
+            OutputFormat::Mp3 { .. } => "mp3",
+        }
+    }
 }
 
 impl Default for ExtractionStatus {
@@ -67,6 +299,12 @@ impl Default for ExtractionStatus {
             progress: 0.0,
             current_file: None,
             error: None,
+            warnings: Vec::new(),
+            processed: 0,
+            total: 0,
+            successful: 0,
+            skipped: 0,
+            eta_secs: None,
         }
     }
 }
@@ -81,9 +319,121 @@ pub struct PlaybackStatus {
     pub current_sound_id: Option<String>,
     pub position_secs: f64,
     pub duration_secs: f64,
+    /// Volume restored on unmute; still reported while muted
     pub volume: f32,
+    pub is_muted: bool,
     pub sample_rate: u32,
     pub bitrate_kbps: u32,
+    pub channels: u16,
+    /// Decoded format inferred from the file extension ("ogg", "wav", "flac", "mp3")
+    pub codec: String,
+    /// Number of sounds waiting in the playback queue
+    pub queue_length: u32,
+    pub repeat_mode: RepeatMode,
+    /// True while the queue is a shuffled music list
+    pub shuffle: bool,
+    /// Playback speed multiplier (pitch changes with speed)
+    pub speed: f32,
+    /// Number of sounds currently audible, including layered voices
+    pub active_voices: u32,
+    /// Seconds until the sleep timer stops playback, if one is running
+    pub sleep_remaining_secs: Option<f64>,
+}
+
+/// Filters applied on top of the text query when searching sounds.
+/// Multiple categories or unit types match any of them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchFilters {
+    pub categories: Option<Vec<String>>,
+    pub unit_types: Option<Vec<String>>,
+    pub gender: Option<String>,
+    pub language: Option<String>,
+    pub favorites_only: bool,
+    /// Some(true) keeps only looping sounds, Some(false) leaves them out
+    pub is_loop: Option<bool>,
+}
+
+/// Sort order for sound search results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    NameAsc,
+    NameDesc,
+    DurationAsc,
+    DurationDesc,
+    Category,
+    /// Best FTS match first; falls back to name order without a query
+    #[default]
+    Relevance,
+}
+
+/// Markers wrapped around the matched terms in a search result's snippet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase", default)]
+pub struct HighlightMarkers {
+    pub open: String,
+    pub close: String,
+}
+
+impl Default for HighlightMarkers {
+    fn default() -> Self {
+        Self {
+            open: "<mark>".to_string(),
+            close: "</mark>".to_string(),
+        }
+    }
+}
+
+/// A sound returned by search, with the name it matched on highlighted.
+/// `match_snippet` is only set for text queries that matched a name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub sound: Sound,
+    pub match_snippet: Option<String>,
+}
+
+/// The sound that was playing when playback last stopped or the app closed,
+/// so the UI can offer to resume where it left off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct LastSession {
+    pub sound_id: String,
+    pub position_secs: f64,
+}
+
+/// Payload of the `playback-finished` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackFinished {
+    pub sound_id: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "snake_case")]
+pub enum RepeatMode {
+    #[default]
+    Off,
+    /// Repeat the current track
+    One,
+    /// Cycle through the current track and the queue
+    All,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +447,124 @@ pub struct MusicTrack {
     pub duration_secs: f64,
 }
 
+/// A user-defined collection of sounds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct Playlist {
+    #[cfg_attr(test, ts(type = "number"))]
+    pub id: i64,
+    pub name: String,
+    pub sound_count: u32,
+}
+
+/// File format for exporting the sound catalog
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "snake_case")]
+pub enum ZipCompression {
+    /// No compression; fastest, and OGG files are already compressed
+    #[default]
+    Stored,
+    Deflated,
+}
+
+/// Progress of a long-running maintenance task, sent as `maintenance-progress` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceProgress {
+    /// 0.0 to 1.0
+    pub progress: f32,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgress {
+    pub files_done: u32,
+    pub files_total: u32,
+    pub current_file: Option<String>,
+    /// Audio bytes added to the archive so far (before compression)
+    #[cfg_attr(test, ts(type = "number"))]
+    pub bytes_written: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub file_count: u32,
+    /// Final size of the archive on disk
+    #[cfg_attr(test, ts(type = "number"))]
+    pub bytes_written: u64,
+    /// IDs of sounds whose audio file was missing
+    pub skipped: Vec<String>,
+}
+
+/// A sound's favorite state after `toggle_favorite`, with the updated favorites total
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteToggle {
+    pub is_favorite: bool,
+    #[cfg_attr(test, ts(type = "number"))]
+    pub total_favorites: u64,
+}
+
+/// Database file size before and after `optimize_database`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeResult {
+    #[cfg_attr(test, ts(type = "number"))]
+    pub size_before: u64,
+    #[cfg_attr(test, ts(type = "number"))]
+    pub size_after: u64,
+}
+
+/// Result of `verify_catalog`: how many sounds point at files that no longer exist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogVerification {
+    pub sounds_checked: u32,
+    pub orphaned_sounds: u32,
+}
+
+/// Aggregate counts and durations for the stats panel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(TS))]
+#[cfg_attr(test, ts(export, export_to = "../../src/lib/types/"))]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogStats {
+    pub sound_count: u32,
+    pub music_track_count: u32,
+    pub favorite_count: u32,
+    pub category_count: u32,
+    pub unit_type_count: u32,
+    pub total_sound_duration_secs: f64,
+    pub total_music_duration_secs: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,9 +574,35 @@ mod tests {
         Sound::export_all().expect("Failed to export Sound");
         Category::export_all().expect("Failed to export Category");
         UnitType::export_all().expect("Failed to export UnitType");
+        Subcategory::export_all().expect("Failed to export Subcategory");
         ExtractionState::export_all().expect("Failed to export ExtractionState");
         ExtractionStatus::export_all().expect("Failed to export ExtractionStatus");
+        ExtractionSummary::export_all().expect("Failed to export ExtractionSummary");
+        ExtractionError::export_all().expect("Failed to export ExtractionError");
+        ExtractionPlan::export_all().expect("Failed to export ExtractionPlan");
+        FavoriteToggle::export_all().expect("Failed to export FavoriteToggle");
+        ExtractionOptions::export_all().expect("Failed to export ExtractionOptions");
+        OutputFormat::export_all().expect("Failed to export OutputFormat");
+        DependencyStatus::export_all().expect("Failed to export DependencyStatus");
+        GameFileCheck::export_all().expect("Failed to export GameFileCheck");
+        GamePathReport::export_all().expect("Failed to export GamePathReport");
         PlaybackStatus::export_all().expect("Failed to export PlaybackStatus");
+        PlaybackFinished::export_all().expect("Failed to export PlaybackFinished");
+        LastSession::export_all().expect("Failed to export LastSession");
+        SearchFilters::export_all().expect("Failed to export SearchFilters");
+        SortOrder::export_all().expect("Failed to export SortOrder");
+        HighlightMarkers::export_all().expect("Failed to export HighlightMarkers");
+        SearchResult::export_all().expect("Failed to export SearchResult");
+        RepeatMode::export_all().expect("Failed to export RepeatMode");
         MusicTrack::export_all().expect("Failed to export MusicTrack");
+        Playlist::export_all().expect("Failed to export Playlist");
+        ZipCompression::export_all().expect("Failed to export ZipCompression");
+        ExportProgress::export_all().expect("Failed to export ExportProgress");
+        MaintenanceProgress::export_all().expect("Failed to export MaintenanceProgress");
+        ExportSummary::export_all().expect("Failed to export ExportSummary");
+        OptimizeResult::export_all().expect("Failed to export OptimizeResult");
+        CatalogVerification::export_all().expect("Failed to export CatalogVerification");
+        CatalogStats::export_all().expect("Failed to export CatalogStats");
+        CatalogFormat::export_all().expect("Failed to export CatalogFormat");
     }
 }
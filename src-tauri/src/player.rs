@@ -3,23 +3,57 @@
 //! rodio's OutputStream is not Send+Sync, so we spawn a dedicated thread
 //! to handle audio playback and communicate via channels.
 
-use rodio::{Decoder, OutputStream, Sink, Source};
-use std::fs::File;
-use std::io::BufReader;
-use std::path::PathBuf;
+use crate::decoder::{self, AudioSource};
+use crate::error::KitharaError;
+use crate::models::{PlaybackFinished, PlaybackStatus, RepeatMode};
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Sender};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Length of the volume ramp applied by `Stop` to avoid an audible click
+const STOP_RAMP: Duration = Duration::from_millis(15);
+
+/// Number of volume steps used for the stop ramp
+const STOP_RAMP_STEPS: u32 = 5;
+
+/// Supported playback speed range
+pub const MIN_SPEED: f32 = 0.5;
+pub const MAX_SPEED: f32 = 2.0;
+
+/// Largest per-sound gain trim, in dB, accepted in either direction
+pub const MAX_GAIN_DB: f64 = 24.0;
+
+/// Maximum number of layered voices playing on top of the main track
+pub const MAX_LAYERED_VOICES: usize = 8;
 
 /// Commands sent to the audio thread
 enum AudioCommand {
-    Play { id: String, path: PathBuf },
+    Play { id: String, path: PathBuf, gain_db: f64, looping: bool, response: Sender<Result<(), KitharaError>> },
+    PlayLayered { id: String, path: PathBuf, gain_db: f64 },
+    Preload { path: PathBuf },
     Stop,
+    StopAll,
+    StopImmediate,
     Pause,
     Resume,
     SetVolume { volume: f32 },
+    ToggleMute { response: Sender<bool> },
     Seek { position_secs: f64 },
+    SeekRelative { delta_secs: f64 },
+    Enqueue { id: String, path: PathBuf, gain_db: f64 },
+    ClearQueue,
+    Shuffle { tracks: Vec<(String, PathBuf, f64)>, seed: u64 },
+    SkipNext,
+    SetRepeat(RepeatMode),
+    SetSpeed(f32),
+    SetOutputDevice { name: Option<String>, response: Sender<Result<(), KitharaError>> },
     GetStatus { response: Sender<AudioStatus> },
     Shutdown,
 }
@@ -33,24 +67,53 @@ pub struct AudioStatus {
     pub position_secs: f64,
     pub duration_secs: f64,
     pub volume: f32,
+    pub is_muted: bool,
     pub sample_rate: u32,
     pub bitrate_kbps: u32,
+    pub channels: u16,
+    pub codec: String,
+    pub queue_length: u32,
+    pub repeat_mode: RepeatMode,
+    pub shuffle: bool,
+    pub speed: f32,
+    /// Number of sounds currently audible, including layered voices
+    pub active_voices: u32,
+    /// Time left before the sleep timer stops playback, if one is running
+    pub sleep_remaining_secs: Option<f64>,
+    /// Number of tracks that have finished playing since startup
+    pub finished_count: u64,
+    /// ID of the most recently finished track
+    pub last_finished_id: Option<String>,
 }
 
 /// Handle to communicate with the audio thread
 pub struct AudioPlayer {
     command_tx: Sender<AudioCommand>,
+    /// Set when playback state changes so the status emitter starts pushing updates
+    emitter_active: Arc<AtomicBool>,
+    /// When the running sleep timer fires. A timer thread only stops playback
+    /// if this still matches the deadline it was started with.
+    sleep_deadline: Arc<Mutex<Option<Instant>>>,
 }
 
 impl AudioPlayer {
     /// Creates a new audio player, spawning the audio thread.
-    pub fn new() -> Result<Self, String> {
+    /// `initial_volume` is applied to all playback until changed. `output_device`
+    /// selects an output by name, falling back to the default if it isn't available.
+    pub fn new(initial_volume: f32, output_device: Option<String>) -> Result<Self, KitharaError> {
         let (command_tx, command_rx) = mpsc::channel::<AudioCommand>();
 
         // Spawn the audio thread
         thread::spawn(move || {
             // Create the audio output on this thread (it must stay on this thread)
-            let (_stream, stream_handle) = match OutputStream::try_default() {
+            let output = match open_output_stream(output_device.as_deref()) {
+                Err(e) if output_device.is_some() => {
+                    eprintln!("{}, using default output", e);
+                    open_output_stream(None)
+                }
+                output => output,
+            };
+            let (mut _stream, stream_handle) = match output {
                 Ok(output) => output,
                 Err(e) => {
                     eprintln!("Failed to create audio output: {}", e);
@@ -58,296 +121,955 @@ impl AudioPlayer {
                 }
             };
 
-            let mut sink: Option<Sink> = None;
-            let mut current_sound_id: Option<String> = None;
-            let mut current_path: Option<PathBuf> = None;
-            let mut current_volume: f32 = 1.0;
-            let mut duration_secs: f64 = 0.0;
-            let mut sample_rate: u32 = 0;
-            let mut bitrate_kbps: u32 = 0;
-
-            // Position tracking
-            let mut playback_start: Option<Instant> = None;
-            let mut playback_offset: f64 = 0.0; // Position when playback started/resumed
-            let mut paused_position: Option<f64> = None; // Position when paused
-
-            // Helper to calculate current position
-            let calc_position = |start: Option<Instant>, offset: f64, paused: Option<f64>| -> f64 {
-                if let Some(pos) = paused {
-                    return pos;
-                }
-                if let Some(start_time) = start {
-                    return offset + start_time.elapsed().as_secs_f64();
-                }
-                0.0
-            };
+            let mut playback = Playback::new(stream_handle, initial_volume);
 
             // Process commands
             while let Ok(cmd) = command_rx.recv() {
                 match cmd {
-                    AudioCommand::Play { id, path } => {
-                        // Stop any currently playing sound
-                        if let Some(s) = sink.take() {
-                            s.stop();
-                        }
-
-                        // Get file size for bitrate calculation
-                        let file_size = std::fs::metadata(&path)
-                            .map(|m| m.len())
-                            .unwrap_or(0);
-
-                        // Open and decode the audio file
-                        match File::open(&path) {
-                            Ok(file) => {
-                                let reader = BufReader::new(file);
-                                match Decoder::new(reader) {
-                                    Ok(source) => {
-                                        // Get audio properties before consuming source
-                                        sample_rate = source.sample_rate();
-                                        duration_secs = source.total_duration()
-                                            .map(|d| d.as_secs_f64())
-                                            .unwrap_or(0.0);
-
-                                        // Calculate approximate bitrate (file_size in bytes / duration in seconds * 8 / 1000)
-                                        if duration_secs > 0.0 {
-                                            bitrate_kbps = ((file_size as f64 * 8.0) / (duration_secs * 1000.0)) as u32;
-                                        } else {
-                                            bitrate_kbps = 0;
-                                        }
-
-                                        match Sink::try_new(&stream_handle) {
-                                            Ok(new_sink) => {
-                                                new_sink.set_volume(current_volume);
-                                                new_sink.append(source);
-                                                sink = Some(new_sink);
-                                                current_sound_id = Some(id);
-                                                current_path = Some(path);
-                                                playback_start = Some(Instant::now());
-                                                playback_offset = 0.0;
-                                                paused_position = None;
-                                            }
-                                            Err(e) => {
-                                                eprintln!("Failed to create sink: {}", e);
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!("Failed to decode audio: {}", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Failed to open audio file: {}", e);
-                            }
-                        }
+                    AudioCommand::Play { id, path, gain_db, looping, response } => {
+                        let _ = response.send(playback.play(id, path, gain_db, looping));
+                    }
+                    AudioCommand::PlayLayered { id, path, gain_db } => {
+                        playback.play_layered(id, path, gain_db);
+                    }
+                    AudioCommand::Preload { path } => {
+                        playback.preload(path);
                     }
                     AudioCommand::Stop => {
-                        if let Some(s) = sink.take() {
-                            s.stop();
-                        }
-                        current_sound_id = None;
-                        current_path = None;
-                        playback_start = None;
-                        playback_offset = 0.0;
-                        paused_position = None;
-                        duration_secs = 0.0;
-                        sample_rate = 0;
-                        bitrate_kbps = 0;
+                        playback.end_shuffle();
+                        playback.stop(true);
+                    }
+                    AudioCommand::StopAll => {
+                        playback.end_shuffle();
+                        playback.stop_layers();
+                        playback.stop(true);
+                    }
+                    AudioCommand::StopImmediate => {
+                        playback.end_shuffle();
+                        playback.stop(false);
                     }
                     AudioCommand::Pause => {
-                        if let Some(ref s) = sink {
-                            if !s.is_paused() {
-                                // Record position before pausing
-                                paused_position = Some(calc_position(playback_start, playback_offset, None));
-                                s.pause();
-                            }
-                        }
+                        playback.pause();
                     }
                     AudioCommand::Resume => {
-                        if let Some(ref s) = sink {
-                            if s.is_paused() {
-                                // Resume from paused position
-                                if let Some(pos) = paused_position {
-                                    playback_offset = pos;
-                                    playback_start = Some(Instant::now());
-                                    paused_position = None;
-                                }
-                                s.play();
-                            }
-                        }
+                        playback.resume();
                     }
                     AudioCommand::SetVolume { volume } => {
-                        current_volume = volume.clamp(0.0, 1.0);
-                        if let Some(ref s) = sink {
-                            s.set_volume(current_volume);
-                        }
+                        playback.set_volume(volume);
                     }
-                    AudioCommand::Seek { position_secs: seek_pos } => {
-                        // Seeking requires stopping current playback and starting fresh
-                        if let Some(ref path) = current_path.clone() {
-                            // Stop the current sink
-                            if let Some(s) = sink.take() {
-                                s.stop();
-                            }
-
-                            // Don't clamp to duration if duration is unknown (0)
-                            let seek_pos = if duration_secs > 0.0 {
-                                seek_pos.max(0.0).min(duration_secs)
-                            } else {
-                                seek_pos.max(0.0)
-                            };
-
-                            match File::open(&path) {
-                                Ok(file) => {
-                                    let reader = BufReader::new(file);
-                                    match Decoder::new(reader) {
-                                        Ok(source) => {
-                                            // Use skip_duration for lazy seeking (doesn't decode all samples upfront)
-                                            let skip_dur = Duration::from_secs_f64(seek_pos);
-                                            let skipped_source = source.skip_duration(skip_dur);
-
-                                            match Sink::try_new(&stream_handle) {
-                                                Ok(new_sink) => {
-                                                    new_sink.set_volume(current_volume);
-                                                    new_sink.append(skipped_source);
-                                                    sink = Some(new_sink);
-                                                    playback_start = Some(Instant::now());
-                                                    playback_offset = seek_pos;
-                                                    paused_position = None;
-                                                }
-                                                Err(e) => {
-                                                    eprintln!("Failed to create audio sink: {}", e);
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            eprintln!("Failed to decode audio for seek: {}", e);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("Failed to open audio file for seek: {}", e);
-                                }
-                            }
-                        }
+                    AudioCommand::ToggleMute { response } => {
+                        let _ = response.send(playback.toggle_mute());
                     }
-                    AudioCommand::GetStatus { response } => {
-                        let sink_empty = sink.as_ref().map(|s| s.empty()).unwrap_or(true);
-                        let sink_paused = sink.as_ref().map(|s| s.is_paused()).unwrap_or(false);
-                        let sink_len = sink.as_ref().map(|s| s.len()).unwrap_or(0);
-
-                        // Consider "playing" if sink has content and is not paused
-                        let has_sink_content = (sink_len > 0 || !sink_empty) && !sink_paused;
-                        let is_playing = has_sink_content;
-                        let is_paused = sink_paused || paused_position.is_some();
-
-                        // Calculate position first (needed for clear logic)
-                        let position = calc_position(playback_start, playback_offset, paused_position);
-
-                        // Detect when track finishes playing:
-                        // - Sink is truly empty (no sources queued)
-                        // - Not paused
-                        // - We have a current track (were playing something)
-                        let track_finished = sink_empty && sink_len == 0 && !sink_paused &&
-                            sink.is_some() &&
-                            current_sound_id.is_some() &&
-                            playback_start.is_some();  // Was actively playing
-
-                        if track_finished {
-                            // Clear playback state so position stops incrementing
-                            // but keep current_sound_id so frontend knows what just finished
-                            playback_start = None;
-                            paused_position = None;
+                    AudioCommand::Seek { position_secs } => {
+                        playback.seek(position_secs);
+                    }
+                    AudioCommand::SeekRelative { delta_secs } => {
+                        playback.seek_relative(delta_secs);
+                    }
+                    AudioCommand::Enqueue { id, path, gain_db } => {
+                        playback.queue.push_back((id, path, gain_db));
+                    }
+                    AudioCommand::ClearQueue => {
+                        playback.queue.clear();
+                        playback.end_shuffle();
+                    }
+                    AudioCommand::Shuffle { tracks, seed } => {
+                        playback.start_shuffle(tracks, seed);
+                    }
+                    AudioCommand::SkipNext => {
+                        if !playback.play_next() {
+                            playback.stop(true);
                         }
-
-                        // Don't clamp position if duration is 0 (Vorbis doesn't report duration)
-                        let clamped_position = if duration_secs > 0.0 {
-                            position.min(duration_secs)
-                        } else {
-                            position
-                        };
-
-                        let _ = response.send(AudioStatus {
-                            is_playing,
-                            is_paused,
-                            current_sound_id: current_sound_id.clone(),
-                            position_secs: clamped_position,
-                            duration_secs,
-                            volume: current_volume,
-                            sample_rate,
-                            bitrate_kbps,
+                    }
+                    AudioCommand::SetRepeat(mode) => {
+                        // Takes effect when the current track finishes
+                        playback.repeat_mode = mode;
+                    }
+                    AudioCommand::SetSpeed(speed) => {
+                        playback.set_speed(speed);
+                    }
+                    AudioCommand::SetOutputDevice { name, response } => {
+                        let result = open_output_stream(name.as_deref()).map(|(stream, handle)| {
+                            // Sinks are bound to the old stream, so stop everything first
+                            playback.stop_layers();
+                            playback.stop(true);
+                            playback.stream_handle = handle;
+                            _stream = stream;
                         });
+                        let _ = response.send(result);
+                    }
+                    AudioCommand::GetStatus { response } => {
+                        let _ = response.send(playback.status());
                     }
                     AudioCommand::Shutdown => {
-                        if let Some(s) = sink.take() {
-                            s.stop();
-                        }
+                        playback.stop_layers();
+                        playback.stop(false);
                         break;
                     }
                 }
             }
         });
 
-        Ok(Self { command_tx })
+        Ok(Self {
+            command_tx,
+            emitter_active: Arc::new(AtomicBool::new(false)),
+            sleep_deadline: Arc::new(Mutex::new(None)),
+        })
     }
 
     /// Plays an audio file, stopping any currently playing sound.
-    pub fn play(&self, sound_id: String, file_path: PathBuf) -> Result<(), String> {
+    /// `gain_db` trims the level of this sound relative to the player volume.
+    /// With `looping`, this sound repeats until stopped whatever the repeat mode.
+    /// Fails with the file and reason if it can't be opened or decoded.
+    pub fn play(&self, sound_id: String, file_path: PathBuf, gain_db: f64, looping: bool) -> Result<(), KitharaError> {
+        let (response_tx, response_rx) = mpsc::channel();
         self.command_tx
             .send(AudioCommand::Play {
                 id: sound_id,
                 path: file_path,
+                gain_db,
+                looping,
+                response: response_tx,
+            })
+            .map_err(|e| KitharaError::Playback(format!("Failed to send play command: {}", e)))?;
+        let result = response_rx
+            .recv()
+            .map_err(|e| KitharaError::Playback(format!("Failed to receive play result: {}", e)))?;
+        self.notify_emitter();
+        result
+    }
+
+    /// Plays a sound on top of whatever is already playing, without stopping it.
+    /// The oldest layered voice is dropped once `MAX_LAYERED_VOICES` is reached.
+    pub fn play_layered(&self, sound_id: String, file_path: PathBuf, gain_db: f64) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::PlayLayered {
+                id: sound_id,
+                path: file_path,
+                gain_db,
             })
-            .map_err(|e| format!("Failed to send play command: {}", e))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send play command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
     }
 
-    /// Stops the currently playing sound.
-    pub fn stop(&self) -> Result<(), String> {
+    /// Opens and decodes a file ahead of time so a following `play` of the
+    /// same path starts immediately. Replaces any previously preloaded file.
+    pub fn preload(&self, file_path: PathBuf) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::Preload { path: file_path })
+            .map_err(|e| KitharaError::Playback(format!("Failed to send preload command: {}", e)))
+    }
+
+    /// Stops the main track and all layered voices.
+    pub fn stop_all(&self) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::StopAll)
+            .map_err(|e| KitharaError::Playback(format!("Failed to send stop command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Stops the currently playing sound after a short fade to avoid a click.
+    pub fn stop(&self) -> Result<(), KitharaError> {
         self.command_tx
             .send(AudioCommand::Stop)
-            .map_err(|e| format!("Failed to send stop command: {}", e))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send stop command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Stops the currently playing sound with a hard cut (no fade).
+    pub fn stop_immediate(&self) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::StopImmediate)
+            .map_err(|e| KitharaError::Playback(format!("Failed to send stop command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
     }
 
     /// Pauses playback.
-    pub fn pause(&self) -> Result<(), String> {
+    pub fn pause(&self) -> Result<(), KitharaError> {
         self.command_tx
             .send(AudioCommand::Pause)
-            .map_err(|e| format!("Failed to send pause command: {}", e))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send pause command: {}", e)))
     }
 
     /// Resumes playback after pause.
-    pub fn resume(&self) -> Result<(), String> {
+    pub fn resume(&self) -> Result<(), KitharaError> {
         self.command_tx
             .send(AudioCommand::Resume)
-            .map_err(|e| format!("Failed to send resume command: {}", e))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send resume command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
     }
 
     /// Sets the playback volume (0.0 to 1.0).
-    pub fn set_volume(&self, volume: f32) -> Result<(), String> {
+    pub fn set_volume(&self, volume: f32) -> Result<(), KitharaError> {
         self.command_tx
             .send(AudioCommand::SetVolume { volume })
-            .map_err(|e| format!("Failed to send volume command: {}", e))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send volume command: {}", e)))
     }
 
-    /// Seeks to a position in seconds.
-    pub fn seek(&self, position_secs: f64) -> Result<(), String> {
+    /// Mutes or unmutes all playback, keeping the volume to restore on unmute.
+    /// Returns true if playback is now muted.
+    pub fn toggle_mute(&self) -> Result<bool, KitharaError> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.command_tx
+            .send(AudioCommand::ToggleMute {
+                response: response_tx,
+            })
+            .map_err(|e| KitharaError::Playback(format!("Failed to send mute command: {}", e)))?;
+        let muted = response_rx
+            .recv()
+            .map_err(|e| KitharaError::Playback(format!("Failed to receive mute state: {}", e)))?;
+        self.notify_emitter();
+        Ok(muted)
+    }
+
+    /// Seeks to a position in seconds. A paused track stays paused at the new position.
+    pub fn seek(&self, position_secs: f64) -> Result<(), KitharaError> {
         self.command_tx
             .send(AudioCommand::Seek { position_secs })
-            .map_err(|e| format!("Failed to send seek command: {}", e))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send seek command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Seeks relative to the current position (negative values seek backwards).
+    /// Playback stays paused if it was paused.
+    pub fn seek_relative(&self, delta_secs: f64) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::SeekRelative { delta_secs })
+            .map_err(|e| KitharaError::Playback(format!("Failed to send seek command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Adds a sound to the end of the playback queue.
+    /// Queued sounds play automatically when the current one finishes.
+    pub fn enqueue(&self, sound_id: String, file_path: PathBuf, gain_db: f64) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::Enqueue {
+                id: sound_id,
+                path: file_path,
+                gain_db,
+            })
+            .map_err(|e| KitharaError::Playback(format!("Failed to send enqueue command: {}", e)))
+    }
+
+    /// Replaces the queue with `tracks` in a random order from `seed` and starts
+    /// playing. With repeat-all, the tracks are reshuffled each time the queue runs out.
+    pub fn shuffle(&self, tracks: Vec<(String, PathBuf, f64)>, seed: u64) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::Shuffle { tracks, seed })
+            .map_err(|e| KitharaError::Playback(format!("Failed to send shuffle command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Removes all sounds from the playback queue (ending shuffle).
+    pub fn clear_queue(&self) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::ClearQueue)
+            .map_err(|e| KitharaError::Playback(format!("Failed to send clear queue command: {}", e)))
+    }
+
+    /// Skips to the next queued sound, or stops if the queue is empty.
+    pub fn skip_next(&self) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::SkipNext)
+            .map_err(|e| KitharaError::Playback(format!("Failed to send skip command: {}", e)))?;
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Sets what happens when a track finishes (repeat it, cycle the queue, or stop).
+    pub fn set_repeat_mode(&self, mode: RepeatMode) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::SetRepeat(mode))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send repeat command: {}", e)))
+    }
+
+    /// Sets the playback speed multiplier (clamped to 0.5-2.0).
+    /// Speed changes also change pitch.
+    pub fn set_speed(&self, speed: f32) -> Result<(), KitharaError> {
+        self.command_tx
+            .send(AudioCommand::SetSpeed(speed))
+            .map_err(|e| KitharaError::Playback(format!("Failed to send speed command: {}", e)))
+    }
+
+    /// Switches audio output to the named device (or the system default for
+    /// `None`). Anything currently playing is stopped.
+    pub fn set_output_device(&self, name: Option<String>) -> Result<(), KitharaError> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.command_tx
+            .send(AudioCommand::SetOutputDevice {
+                name,
+                response: response_tx,
+            })
+            .map_err(|e| KitharaError::Playback(format!("Failed to send output device command: {}", e)))?;
+        self.notify_emitter();
+
+        response_rx
+            .recv()
+            .map_err(|e| KitharaError::Playback(format!("Failed to receive output device result: {}", e)))?
+    }
+
+    /// Stops playback (with the usual fade) once `duration` has elapsed, calling
+    /// `before_stop` first while the sound is still playing.
+    /// Replaces any sleep timer that is already running.
+    pub fn start_sleep_timer(
+        &self,
+        duration: Duration,
+        before_stop: impl FnOnce() + Send + 'static,
+    ) -> Result<(), KitharaError> {
+        let deadline = Instant::now() + duration;
+        *self.sleep_deadline.lock().map_err(|e| KitharaError::Playback(e.to_string()))? = Some(deadline);
+
+        let command_tx = self.command_tx.clone();
+        let sleep_deadline = Arc::clone(&self.sleep_deadline);
+        let emitter_active = Arc::clone(&self.emitter_active);
+        thread::spawn(move || {
+            thread::sleep(duration);
+
+            let Ok(mut current) = sleep_deadline.lock() else {
+                return;
+            };
+            // Replaced or cancelled timers leave without stopping anything
+            if *current != Some(deadline) {
+                return;
+            }
+            *current = None;
+            // Status reads take this lock, so release it before the callback
+            drop(current);
+
+            before_stop();
+            let _ = command_tx.send(AudioCommand::Stop);
+            emitter_active.store(true, Ordering::Relaxed);
+        });
+
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Cancels the running sleep timer, if any.
+    pub fn cancel_sleep_timer(&self) -> Result<(), KitharaError> {
+        *self.sleep_deadline.lock().map_err(|e| KitharaError::Playback(e.to_string()))? = None;
+        self.notify_emitter();
+        Ok(())
+    }
+
+    /// Wakes the status emitter so it pushes updates until playback goes idle.
+    fn notify_emitter(&self) {
+        self.emitter_active.store(true, Ordering::Relaxed);
     }
 
     /// Gets the current playback status.
-    pub fn get_status(&self) -> Result<AudioStatus, String> {
+    pub fn get_status(&self) -> Result<AudioStatus, KitharaError> {
         let (response_tx, response_rx) = mpsc::channel();
         self.command_tx
             .send(AudioCommand::GetStatus {
                 response: response_tx,
             })
-            .map_err(|e| format!("Failed to send status command: {}", e))?;
+            .map_err(|e| KitharaError::Playback(format!("Failed to send status command: {}", e)))?;
 
-        response_rx
+        let mut status = response_rx
             .recv()
-            .map_err(|e| format!("Failed to receive status: {}", e))
+            .map_err(|e| KitharaError::Playback(format!("Failed to receive status: {}", e)))?;
+
+        // The sleep timer lives outside the audio thread
+        status.sleep_remaining_secs = self
+            .sleep_deadline
+            .lock()
+            .map_err(|e| KitharaError::Playback(e.to_string()))?
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()).as_secs_f64());
+        Ok(status)
+    }
+}
+
+/// Playback state owned by the audio thread
+struct Playback {
+    stream_handle: OutputStreamHandle,
+    sink: Option<Sink>,
+    current_sound_id: Option<String>,
+    current_path: Option<PathBuf>,
+    /// Gain trim of the current track in dB
+    current_gain_db: f64,
+    /// Whether the current track repeats until stopped, independent of `repeat_mode`
+    current_looping: bool,
+    /// Player volume, kept while muted so unmuting restores it
+    volume: f32,
+    muted: bool,
+    duration_secs: f64,
+    sample_rate: u32,
+    bitrate_kbps: u32,
+    channels: u16,
+    codec: String,
+
+    // Position tracking
+    playback_start: Option<Instant>,
+    playback_offset: f64, // Position when playback started/resumed
+    paused_position: Option<f64>, // Position when paused
+
+    /// Sounds to play after the current one finishes
+    queue: VecDeque<(String, PathBuf, f64)>,
+    repeat_mode: RepeatMode,
+    /// Tracks being shuffled, reshuffled into the queue on repeat-all
+    shuffle_pool: Vec<(String, PathBuf, f64)>,
+    shuffle_rng: u64,
+    /// Playback speed multiplier (also shifts pitch)
+    speed: f32,
+    /// Extra voices layered over the main track with their linear gain, oldest first
+    layers: Vec<(String, Sink, f32)>,
+
+    // Finished-track bookkeeping for playback-finished events
+    finished_count: u64,
+    last_finished_id: Option<String>,
+
+    /// Most recently preloaded file, decoded and ready to append
+    preloaded: Option<(PathBuf, AudioSource)>,
+}
+
+impl Playback {
+    fn new(stream_handle: OutputStreamHandle, initial_volume: f32) -> Self {
+        Self {
+            stream_handle,
+            sink: None,
+            current_sound_id: None,
+            current_path: None,
+            current_gain_db: 0.0,
+            current_looping: false,
+            volume: initial_volume.clamp(0.0, 1.0),
+            muted: false,
+            duration_secs: 0.0,
+            sample_rate: 0,
+            bitrate_kbps: 0,
+            channels: 0,
+            codec: String::new(),
+            playback_start: None,
+            playback_offset: 0.0,
+            paused_position: None,
+            queue: VecDeque::new(),
+            repeat_mode: RepeatMode::Off,
+            shuffle_pool: Vec::new(),
+            shuffle_rng: 0,
+            speed: 1.0,
+            layers: Vec::new(),
+            finished_count: 0,
+            last_finished_id: None,
+            preloaded: None,
+        }
+    }
+
+    /// Calculates the current playback position in seconds.
+    fn position(&self) -> f64 {
+        if let Some(pos) = self.paused_position {
+            return pos;
+        }
+        if let Some(start_time) = self.playback_start {
+            // Media time advances faster or slower than wall time at non-1.0 speeds
+            return self.playback_offset + start_time.elapsed().as_secs_f64() * self.speed as f64;
+        }
+        0.0
+    }
+
+    /// Volume actually applied to sinks: silent while muted.
+    fn output_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+
+    /// Sink volume for the current track: output volume scaled by its gain trim.
+    fn track_volume(&self) -> f32 {
+        self.output_volume() * db_to_linear(self.current_gain_db)
+    }
+
+    /// Starts playing a file from the beginning, replacing the current sound.
+    /// The current sound is stopped even if the new one fails to open.
+    fn play(&mut self, id: String, path: PathBuf, gain_db: f64, looping: bool) -> Result<(), KitharaError> {
+        // Stop any currently playing sound
+        self.stop(false);
+
+        // Get file size for bitrate calculation
+        let file_size = std::fs::metadata(&path)
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        // Use the preloaded source if it's this file; any other preload is dropped
+        let source = match self.preloaded.take() {
+            Some((preloaded_path, source)) if preloaded_path == path => source,
+            _ => decoder::open(&path)?,
+        };
+
+        // Get audio properties before consuming source
+        self.sample_rate = source.sample_rate();
+        self.channels = source.channels();
+        self.codec = codec_from_path(&path);
+        self.duration_secs = source.total_duration()
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+
+        // Calculate approximate bitrate (file_size in bytes / duration in seconds * 8 / 1000)
+        if self.duration_secs > 0.0 {
+            self.bitrate_kbps = ((file_size as f64 * 8.0) / (self.duration_secs * 1000.0)) as u32;
+        } else {
+            self.bitrate_kbps = 0;
+        }
+
+        let new_sink = Sink::try_new(&self.stream_handle)
+            .map_err(|e| KitharaError::Playback(format!("Failed to create audio sink: {}", e)))?;
+        self.current_gain_db = gain_db;
+        self.current_looping = looping;
+        new_sink.set_volume(self.track_volume());
+        new_sink.set_speed(self.speed);
+        new_sink.append(source);
+        self.sink = Some(new_sink);
+        self.current_sound_id = Some(id);
+        self.current_path = Some(path);
+        self.playback_start = Some(Instant::now());
+        self.playback_offset = 0.0;
+        self.paused_position = None;
+        Ok(())
+    }
+
+    /// Decodes a file ahead of `play`, keeping only the latest one to bound memory.
+    fn preload(&mut self, path: PathBuf) {
+        if self.preloaded.as_ref().is_some_and(|(preloaded_path, _)| *preloaded_path == path) {
+            return;
+        }
+        match decoder::open(&path) {
+            Ok(source) => self.preloaded = Some((path, source)),
+            Err(e) => {
+                self.preloaded = None;
+                eprintln!("Failed to preload sound: {}", e);
+            }
+        }
+    }
+
+    /// Starts a layered voice alongside the main track. Layered voices
+    /// don't affect position tracking, the queue, or repeat.
+    fn play_layered(&mut self, id: String, path: PathBuf, gain_db: f64) {
+        self.prune_layers();
+        if self.layers.len() >= MAX_LAYERED_VOICES {
+            let (_, oldest, _) = self.layers.remove(0);
+            oldest.stop();
+        }
+
+        let gain = db_to_linear(gain_db);
+        match open_sink_at(&self.stream_handle, &path, 0.0, self.output_volume() * gain, self.speed) {
+            Ok(sink) => self.layers.push((id, sink, gain)),
+            Err(e) => eprintln!("Failed to play layered sound: {}", e),
+        }
+    }
+
+    /// Drops layered voices that have finished playing.
+    fn prune_layers(&mut self) {
+        self.layers.retain(|(_, sink, _)| !sink.empty());
+    }
+
+    /// Stops all layered voices.
+    fn stop_layers(&mut self) {
+        for (_, sink, _) in self.layers.drain(..) {
+            sink.stop();
+        }
+    }
+
+    /// Queues the shuffle pool in a fresh random order and starts the first track.
+    fn start_shuffle(&mut self, tracks: Vec<(String, PathBuf, f64)>, seed: u64) {
+        self.shuffle_pool = tracks;
+        self.shuffle_rng = seed;
+        self.queue.clear();
+        self.reshuffle_queue();
+        if !self.play_next() {
+            self.stop(true);
+        }
+    }
+
+    /// Refills the queue from the shuffle pool in a new order.
+    fn reshuffle_queue(&mut self) {
+        let mut order = self.shuffle_pool.clone();
+        shuffle_in_place(&mut order, &mut self.shuffle_rng);
+        // Don't play the track that just finished twice in a row
+        if order.len() > 1 && order.first().map(|(id, _, _)| id) == self.current_sound_id.as_ref() {
+            let last = order.len() - 1;
+            order.swap(0, last);
+        }
+        self.queue.extend(order);
+    }
+
+    fn end_shuffle(&mut self) {
+        self.shuffle_pool.clear();
+    }
+
+    /// Plays the next queued sound. Returns false if the queue is empty.
+    /// When shuffling with repeat-all, an empty queue is reshuffled first.
+    fn play_next(&mut self) -> bool {
+        if self.queue.is_empty() && self.repeat_mode == RepeatMode::All && !self.shuffle_pool.is_empty() {
+            self.reshuffle_queue();
+        }
+        match self.queue.pop_front() {
+            Some((id, path, gain_db)) => {
+                if let Err(e) = self.play(id, path, gain_db, false) {
+                    eprintln!("Failed to play queued sound: {}", e);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops playback and clears the current track. With `ramp`, fades out
+    /// briefly first to avoid a click.
+    fn stop(&mut self, ramp: bool) {
+        if let Some(s) = self.sink.take() {
+            if ramp && !s.is_paused() {
+                ramp_to_silence(&s, self.track_volume());
+            }
+            s.stop();
+        }
+        self.current_sound_id = None;
+        self.current_path = None;
+        self.playback_start = None;
+        self.playback_offset = 0.0;
+        self.paused_position = None;
+        self.duration_secs = 0.0;
+        self.sample_rate = 0;
+        self.bitrate_kbps = 0;
+        self.channels = 0;
+        self.codec.clear();
+    }
+
+    fn pause(&mut self) {
+        if let Some(ref s) = self.sink {
+            if !s.is_paused() {
+                // Record position before pausing
+                self.paused_position = Some(self.position());
+                s.pause();
+            }
+        }
+    }
+
+    fn resume(&mut self) {
+        if let Some(ref s) = self.sink {
+            if s.is_paused() {
+                // Resume from paused position
+                if let Some(pos) = self.paused_position {
+                    self.playback_offset = pos;
+                    self.playback_start = Some(Instant::now());
+                    self.paused_position = None;
+                }
+                s.play();
+            }
+        }
+    }
+
+    /// Sets the player volume. While muted only the stored level changes.
+    fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+        self.apply_volume();
+    }
+
+    /// Flips mute, returning the new state.
+    fn toggle_mute(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.apply_volume();
+        self.muted
+    }
+
+    /// Pushes the output volume to the current track and layered voices.
+    fn apply_volume(&self) {
+        if let Some(ref s) = self.sink {
+            s.set_volume(self.track_volume());
+        }
+        for (_, sink, gain) in &self.layers {
+            sink.set_volume(self.output_volume() * gain);
+        }
+    }
+
+    /// Changes the playback speed. Note that rodio resamples to change speed,
+    /// so pitch shifts along with it.
+    fn set_speed(&mut self, speed: f32) {
+        // Rebase position tracking so time already played keeps the old speed
+        if self.playback_start.is_some() {
+            self.playback_offset = self.position();
+            self.playback_start = Some(Instant::now());
+        }
+        self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+        if let Some(ref s) = self.sink {
+            s.set_speed(self.speed);
+        }
+    }
+
+    /// Seeks to an absolute position, staying paused if paused.
+    fn seek(&mut self, position_secs: f64) {
+        self.restart_at(clamp_seek_position(position_secs, self.duration_secs));
+    }
+
+    /// Seeks relative to the current position, staying paused if paused.
+    fn seek_relative(&mut self, delta_secs: f64) {
+        self.restart_at(clamp_seek_position(self.position() + delta_secs, self.duration_secs));
+    }
+
+    /// Seeking requires stopping current playback and starting a fresh sink at
+    /// `seek_pos`. A paused track stays paused there, so scrubbing doesn't resume it.
+    fn restart_at(&mut self, seek_pos: f64) {
+        let Some(path) = self.current_path.clone() else {
+            return;
+        };
+
+        let was_paused = self.paused_position.is_some() || self.sink.as_ref().is_some_and(|s| s.is_paused());
+
+        if let Some(s) = self.sink.take() {
+            s.stop();
+        }
+
+        match open_sink_at(&self.stream_handle, &path, seek_pos, self.track_volume(), self.speed) {
+            Ok(new_sink) => {
+                if was_paused {
+                    // Stay paused at the new position
+                    new_sink.pause();
+                    self.playback_start = None;
+                    self.paused_position = Some(seek_pos);
+                } else {
+                    self.playback_start = Some(Instant::now());
+                    self.paused_position = None;
+                }
+                self.sink = Some(new_sink);
+                self.playback_offset = seek_pos;
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    /// Detects when the track finished playing:
+    /// - Sink is truly empty (no sources queued)
+    /// - Not paused
+    /// - We have a current track (were playing something)
+    fn track_finished(&self) -> bool {
+        let Some(ref s) = self.sink else {
+            return false;
+        };
+        s.empty() && s.len() == 0 && !s.is_paused() &&
+            self.current_sound_id.is_some() &&
+            self.playback_start.is_some()  // Was actively playing
+    }
+
+    /// Starts whatever should follow a finished track according to the repeat
+    /// mode and queue. Returns false if there is nothing left to play.
+    fn advance_after_finish(&mut self) -> bool {
+        let Some((id, path)) = self.current_sound_id.clone().zip(self.current_path.clone()) else {
+            return self.play_next();
+        };
+        let gain_db = self.current_gain_db;
+        match after_finish(self.repeat_mode, self.current_looping) {
+            AfterFinish::Replay => {
+                if let Err(e) = self.play(id, path, gain_db, self.current_looping) {
+                    eprintln!("Failed to repeat sound: {}", e);
+                }
+                true
+            }
+            AfterFinish::CycleQueue => {
+                // Cycle the finished track to the back of the queue, unless shuffling:
+                // then the whole list is reshuffled once it runs out
+                if self.shuffle_pool.is_empty() {
+                    self.queue.push_back((id, path, gain_db));
+                }
+                self.play_next()
+            }
+            AfterFinish::Next => self.play_next(),
+        }
+    }
+
+    /// Builds the current status. Detects finished tracks and advances the queue.
+    fn status(&mut self) -> AudioStatus {
+        if self.track_finished() {
+            self.finished_count += 1;
+            self.last_finished_id = self.current_sound_id.clone();
+
+            if !self.advance_after_finish() {
+                // Clear playback state so position stops incrementing
+                // but keep current_sound_id so frontend knows what just finished
+                self.playback_start = None;
+                self.paused_position = None;
+            }
+        }
+
+        self.prune_layers();
+
+        let sink_empty = self.sink.as_ref().map(|s| s.empty()).unwrap_or(true);
+        let sink_paused = self.sink.as_ref().map(|s| s.is_paused()).unwrap_or(false);
+        let sink_len = self.sink.as_ref().map(|s| s.len()).unwrap_or(0);
+
+        // Consider "playing" if sink has content and is not paused
+        let is_playing = (sink_len > 0 || !sink_empty) && !sink_paused;
+        let is_paused = sink_paused || self.paused_position.is_some();
+        let active_voices = self.layers.len() as u32 + u32::from(is_playing);
+
+        // Don't clamp position if duration is 0 (Vorbis doesn't report duration)
+        let position = self.position();
+        let clamped_position = if self.duration_secs > 0.0 {
+            position.min(self.duration_secs)
+        } else {
+            position
+        };
+
+        AudioStatus {
+            is_playing,
+            is_paused,
+            current_sound_id: self.current_sound_id.clone(),
+            position_secs: clamped_position,
+            duration_secs: self.duration_secs,
+            volume: self.volume,
+            is_muted: self.muted,
+            sample_rate: self.sample_rate,
+            bitrate_kbps: self.bitrate_kbps,
+            channels: self.channels,
+            codec: self.codec.clone(),
+            queue_length: self.queue.len() as u32,
+            repeat_mode: self.repeat_mode,
+            shuffle: !self.shuffle_pool.is_empty(),
+            speed: self.speed,
+            active_voices,
+            sleep_remaining_secs: None,
+            finished_count: self.finished_count,
+            last_finished_id: self.last_finished_id.clone(),
+        }
+    }
+}
+
+/// Clamps a seek target to the start of the track, and to its end when the
+/// duration is known (Vorbis files may report 0 for an unknown duration).
+fn clamp_seek_position(position_secs: f64, duration_secs: f64) -> f64 {
+    if duration_secs > 0.0 {
+        position_secs.max(0.0).min(duration_secs)
+    } else {
+        position_secs.max(0.0)
+    }
+}
+
+/// What follows a finished track
+#[derive(Debug, PartialEq)]
+enum AfterFinish {
+    /// Play the same track again
+    Replay,
+    /// Move the track to the back of the queue, then play the next one
+    CycleQueue,
+    /// Play the next queued track, if any
+    Next,
+}
+
+/// Decides what follows a finished track. A looping track replays whatever the
+/// repeat mode, so the mode never has to change for it and later tracks don't inherit it.
+fn after_finish(repeat_mode: RepeatMode, looping: bool) -> AfterFinish {
+    match repeat_mode {
+        _ if looping => AfterFinish::Replay,
+        RepeatMode::One => AfterFinish::Replay,
+        RepeatMode::All => AfterFinish::CycleQueue,
+        RepeatMode::Off => AfterFinish::Next,
+    }
+}
+
+/// Opens an audio file and returns a new sink playing it from `position_secs`.
+fn open_sink_at(
+    stream_handle: &OutputStreamHandle,
+    path: &Path,
+    position_secs: f64,
+    volume: f32,
+    speed: f32,
+) -> Result<Sink, KitharaError> {
+    let mut source = decoder::open(path)?;
+    let position = Duration::from_secs_f64(position_secs);
+
+    let new_sink = Sink::try_new(stream_handle)
+        .map_err(|e| KitharaError::Playback(format!("Failed to create audio sink: {}", e)))?;
+    new_sink.set_volume(volume);
+    new_sink.set_speed(speed);
+
+    // Seek by timestamp when the decoder supports it (decoder.rs does for OGG);
+    // otherwise fall back to decoding and discarding samples
+    if source.try_seek(position).is_ok() {
+        new_sink.append(source);
+    } else {
+        new_sink.append(source.skip_duration(position));
+    }
+    Ok(new_sink)
+}
+
+/// Lists the names of the available audio output devices.
+pub fn list_output_devices() -> Result<Vec<String>, KitharaError> {
+    let devices = rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| KitharaError::Playback(format!("Failed to enumerate output devices: {}", e)))?;
+    Ok(devices.filter_map(|device| device.name().ok()).collect())
+}
+
+/// Opens an output stream on the named device, or the default device for `None`.
+fn open_output_stream(name: Option<&str>) -> Result<(OutputStream, OutputStreamHandle), KitharaError> {
+    let Some(name) = name else {
+        return OutputStream::try_default()
+            .map_err(|e| KitharaError::Playback(format!("Failed to open default output: {}", e)));
+    };
+
+    let device = rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| KitharaError::Playback(format!("Failed to enumerate output devices: {}", e)))?
+        .find(|device| device.name().map(|n| n == name).unwrap_or(false))
+        .ok_or_else(|| KitharaError::NotFound(format!("Output device not found: {}", name)))?;
+
+    OutputStream::try_from_device(&device)
+        .map_err(|e| KitharaError::Playback(format!("Failed to open output device {}: {}", name, e)))
+}
+
+/// Advances a SplitMix64 state and returns the next pseudo-random value.
+/// Seedable, so a shuffle order can be reproduced.
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Fisher-Yates shuffle driven by `next_random`.
+fn shuffle_in_place<T>(items: &mut [T], rng: &mut u64) {
+    for i in (1..items.len()).rev() {
+        let j = (next_random(rng) % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Converts a gain in decibels to a linear volume multiplier.
+fn db_to_linear(gain_db: f64) -> f32 {
+    10f64.powf(gain_db / 20.0) as f32
+}
+
+/// Infers the codec name from the file extension (e.g. "ogg", "wav").
+/// Returns an empty string for unrecognized formats.
+fn codec_from_path(path: &Path) -> String {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some(ext @ ("ogg" | "wav" | "flac" | "mp3")) => ext.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Ramps the sink volume down to zero over `STOP_RAMP`.
+/// Stopping on a non-zero sample produces an audible click, so this is
+/// applied before a regular stop. Blocks the audio thread for the ramp length.
+fn ramp_to_silence(sink: &Sink, from_volume: f32) {
+    let step_duration = STOP_RAMP / STOP_RAMP_STEPS;
+    for step in 1..=STOP_RAMP_STEPS {
+        let remaining = 1.0 - step as f32 / STOP_RAMP_STEPS as f32;
+        sink.set_volume(from_volume * remaining);
+        thread::sleep(step_duration);
     }
 }
 
@@ -357,11 +1079,99 @@ impl Drop for AudioPlayer {
     }
 }
 
+impl From<AudioStatus> for PlaybackStatus {
+    fn from(status: AudioStatus) -> Self {
+        PlaybackStatus {
+            is_playing: status.is_playing,
+            is_paused: status.is_paused,
+            current_sound_id: status.current_sound_id,
+            position_secs: status.position_secs,
+            duration_secs: status.duration_secs,
+            volume: status.volume,
+            is_muted: status.is_muted,
+            sample_rate: status.sample_rate,
+            bitrate_kbps: status.bitrate_kbps,
+            channels: status.channels,
+            codec: status.codec,
+            queue_length: status.queue_length,
+            repeat_mode: status.repeat_mode,
+            shuffle: status.shuffle,
+            speed: status.speed,
+            active_voices: status.active_voices,
+            sleep_remaining_secs: status.sleep_remaining_secs,
+        }
+    }
+}
+
+/// Interval between `playback-progress` events
+const STATUS_EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Spawns a thread that pushes `playback-progress` events with the full
+/// `PlaybackStatus` while a sound is playing, and a `playback-finished` event
+/// once for each track that finishes. Goes idle when playback stops.
+pub fn spawn_status_emitter(app: AppHandle, player: PlayerState) {
+    thread::spawn(move || {
+        let mut last_finished_count = 0;
+
+        loop {
+            thread::sleep(STATUS_EMIT_INTERVAL);
+            if !player.emitter_active.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            let status = match player.get_status() {
+                Ok(status) => status,
+                Err(_) => break, // Audio thread has shut down
+            };
+
+            if status.finished_count != last_finished_count {
+                last_finished_count = status.finished_count;
+                if let Some(ref sound_id) = status.last_finished_id {
+                    let _ = app.emit(
+                        "playback-finished",
+                        PlaybackFinished {
+                            sound_id: sound_id.clone(),
+                        },
+                    );
+                }
+            }
+
+            let is_idle = !status.is_playing && status.active_voices == 0;
+            let _ = app.emit("playback-progress", PlaybackStatus::from(status));
+
+            // Send one final update after playback stops, then go idle
+            if is_idle {
+                player.emitter_active.store(false, Ordering::Relaxed);
+            }
+        }
+    });
+}
+
 /// Thread-safe wrapper for AudioPlayer, suitable for Tauri managed state.
 /// Uses Arc<Mutex<>> for interior mutability, though AudioPlayer itself is thread-safe.
 pub type PlayerState = Arc<AudioPlayer>;
 
 /// Creates a new PlayerState for use with Tauri's `.manage()`.
-pub fn create_player_state() -> Result<PlayerState, String> {
-    Ok(Arc::new(AudioPlayer::new()?))
+/// Restores the saved volume and output device.
+pub fn create_player_state(
+    initial_volume: f32,
+    output_device: Option<String>,
+) -> Result<PlayerState, KitharaError> {
+    Ok(Arc::new(AudioPlayer::new(initial_volume, output_device)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_sound_does_not_repeat_the_next_sound() {
+        // A loop sound replays with repeat off...
+        assert_eq!(after_finish(RepeatMode::Off, true), AfterFinish::Replay);
+        // ...but a normal sound played after it just finishes
+        assert_eq!(after_finish(RepeatMode::Off, false), AfterFinish::Next);
+        assert_eq!(after_finish(RepeatMode::All, true), AfterFinish::Replay);
+        assert_eq!(after_finish(RepeatMode::All, false), AfterFinish::CycleQueue);
+        assert_eq!(after_finish(RepeatMode::One, false), AfterFinish::Replay);
+    }
 }
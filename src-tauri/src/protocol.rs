@@ -0,0 +1,181 @@
+//! `kithara://` URI scheme that serves cataloged audio to the webview by ID.
+//! Supports HTTP range requests so `<audio>` elements can seek and waveform
+//! views can fetch only the bytes they need, without exposing file paths.
+
+use crate::catalog::Catalog;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{Manager, Runtime, UriSchemeContext, UriSchemeResponder};
+
+/// Scheme name registered with the webview
+pub const SCHEME: &str = "kithara";
+
+/// Largest body sent for one range request. Open-ended ranges are cut short
+/// and the browser asks for the rest.
+const MAX_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Handles a request on a blocking thread so file reads don't stall the webview
+pub fn handle<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let catalog = app.state::<Catalog>();
+        responder.respond(serve_audio(&catalog, &request));
+    });
+}
+
+/// Resolves `/audio/<id>` to a sound or music track and serves its file, honoring `Range`
+fn serve_audio(catalog: &Catalog, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let path = percent_decode(request.uri().path());
+    let Some(id) = path.trim_start_matches('/').strip_prefix("audio/") else {
+        return error_response(StatusCode::NOT_FOUND, "Unknown resource");
+    };
+    let file_path = match catalog.get_audio_path(id) {
+        Ok(Some(file_path)) => file_path,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, &format!("Unknown audio id: {}", id)),
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+    let file_path = Path::new(&file_path);
+
+    let mut file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(e) => {
+            return error_response(StatusCode::NOT_FOUND, &format!("Failed to open audio file: {}", e))
+        }
+    };
+    let len = match file.metadata() {
+        Ok(metadata) => metadata.len(),
+        Err(e) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Failed to read audio file size: {}", e),
+            )
+        }
+    };
+
+    let range_header = request.headers().get(header::RANGE).and_then(|value| value.to_str().ok());
+    let (status, start, end) = match range_header {
+        None => (StatusCode::OK, 0, len),
+        Some(value) => match parse_range(value, len) {
+            Some((start, end)) => (StatusCode::PARTIAL_CONTENT, start, end.min(start + MAX_RANGE_BYTES - 1) + 1),
+            None => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                    .body(Vec::new())
+                    .unwrap_or_default()
+            }
+        },
+    };
+
+    let mut body = vec![0; (end - start) as usize];
+    if let Err(e) = file.seek(SeekFrom::Start(start)).and_then(|_| file.read_exact(&mut body)) {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, &format!("Failed to read audio file: {}", e));
+    }
+
+    let mut response = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type(file_path))
+        .header(header::CONTENT_LENGTH, body.len())
+        .header(header::ACCEPT_RANGES, "bytes")
+        // Lets waveform code read the audio with crossOrigin set
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+    if status == StatusCode::PARTIAL_CONTENT {
+        response = response.header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end - 1, len));
+    }
+    response.body(body).unwrap_or_default()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+/// Parses a `bytes=` range header into inclusive offsets within a file of `len` bytes.
+/// None means the range can't be satisfied (416). Only the first of several ranges is served.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let last = len.checked_sub(1)?;
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        // Suffix range: the final N bytes
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 {
+                return None;
+            }
+            (len.saturating_sub(suffix), last)
+        }
+        (start, "") => (start.parse().ok()?, last),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(last)),
+    };
+    (start <= end).then_some((start, end))
+}
+
+/// Decodes %XX escapes in a URI path (the frontend encodes IDs with encodeURIComponent)
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = path.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// MIME type for the formats extraction can write
+fn content_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "mp3" => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=500-", 1000), Some((500, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        // End past the file is clamped; only the first of several ranges is used
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=0-9, 20-29", 1000), Some((0, 9)));
+
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=50-10", 1000), None);
+        assert_eq!(parse_range("bytes=-0", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+        assert_eq!(parse_range("bytes=0-", 0), None);
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("/audio%2F12345"), "/audio/12345");
+        assert_eq!(percent_decode("/audio/a%20b"), "/audio/a b");
+        assert_eq!(percent_decode("/audio/100%"), "/audio/100%");
+    }
+}
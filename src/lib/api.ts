@@ -1,31 +1,175 @@
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke as tauriInvoke, type InvokeArgs } from '@tauri-apps/api/core';
 import { getCurrentWindow, LogicalSize } from '@tauri-apps/api/window';
-import type { Sound, Category, UnitType, ExtractionStatus, PlaybackStatus, MusicTrack } from './types';
+import type {
+	Sound,
+	Category,
+	UnitType,
+	ExtractionStatus,
+	PlaybackStatus,
+	MusicTrack,
+	ZipCompression,
+	ExportProgress,
+	ExportSummary,
+	RepeatMode,
+	PlaybackFinished,
+	Playlist,
+	SortOrder,
+	CatalogStats,
+	CatalogFormat,
+	ExtractionOptions,
+	OutputFormat,
+	DependencyStatus,
+	MaintenanceProgress,
+	OptimizeResult,
+	SearchFilters,
+	GameFileCheck,
+	GamePathReport,
+	ExtractionSummary,
+	FavoriteToggle,
+	CatalogVerification,
+	ExtractionError,
+	Subcategory,
+	ExtractionPlan,
+	ErrorKind,
+	HighlightMarkers,
+	SearchResult,
+	LastSession
+} from './types';
 
 // Re-export types for convenience
-export type { Sound, Category, UnitType, ExtractionStatus, PlaybackStatus, MusicTrack };
+export type {
+	Sound,
+	Category,
+	UnitType,
+	ExtractionStatus,
+	PlaybackStatus,
+	MusicTrack,
+	ZipCompression,
+	ExportProgress,
+	ExportSummary,
+	RepeatMode,
+	PlaybackFinished,
+	Playlist,
+	SortOrder,
+	CatalogStats,
+	CatalogFormat,
+	ExtractionOptions,
+	OutputFormat,
+	DependencyStatus,
+	MaintenanceProgress,
+	OptimizeResult,
+	SearchFilters,
+	GameFileCheck,
+	GamePathReport,
+	ExtractionSummary,
+	FavoriteToggle,
+	CatalogVerification,
+	ExtractionError,
+	Subcategory,
+	ExtractionPlan,
+	ErrorKind,
+	HighlightMarkers,
+	SearchResult,
+	LastSession
+};
 export type { ExtractionState } from './types';
 
+// Commands reject with { kind, message }; rethrown as an Error whose string form is the message
+export class CommandError extends Error {
+	constructor(
+		readonly kind: ErrorKind,
+		message: string
+	) {
+		super(message);
+		this.name = 'CommandError';
+	}
+
+	toString(): string {
+		return this.message;
+	}
+}
+
+async function invoke<T>(cmd: string, args?: InvokeArgs): Promise<T> {
+	try {
+		return await tauriInvoke<T>(cmd, args);
+	} catch (e) {
+		if (e && typeof e === 'object' && 'kind' in e && 'message' in e) {
+			throw new CommandError(e.kind as ErrorKind, String(e.message));
+		}
+		throw e;
+	}
+}
+
 export async function searchSounds(
 	query: string,
-	category?: string,
-	unitType?: string
-): Promise<Sound[]> {
-	return invoke('search_sounds', { query, category, unitType });
+	filters: Partial<SearchFilters> = {},
+	sort?: SortOrder,
+	fuzzy = true,
+	highlight?: Partial<HighlightMarkers>
+): Promise<SearchResult[]> {
+	return invoke('search_sounds', { query, filters, sort, fuzzy, highlight });
 }
 
 export async function getCategories(): Promise<Category[]> {
 	return invoke('get_categories');
 }
 
-export async function getUnitTypes(): Promise<UnitType[]> {
-	return invoke('get_unit_types');
+export async function getSubcategories(category: string): Promise<Subcategory[]> {
+	return invoke('get_subcategories', { category });
+}
+
+export async function getUnitTypes(includeEmpty: boolean = false): Promise<UnitType[]> {
+	return invoke('get_unit_types', { includeEmpty });
 }
 
-export async function toggleFavorite(soundId: string): Promise<boolean> {
+export async function getSoundsByBank(bankName: string): Promise<Sound[]> {
+	return invoke('get_sounds_by_bank', { bankName });
+}
+
+export async function getSimilarSounds(soundId: string, limit?: number): Promise<Sound[]> {
+	return invoke('get_similar_sounds', { soundId, limit });
+}
+
+export async function getLanguages(): Promise<string[]> {
+	return invoke('get_languages');
+}
+
+export async function toggleFavorite(soundId: string): Promise<FavoriteToggle> {
 	return invoke('toggle_favorite', { soundId });
 }
 
+export async function deleteSound(soundId: string): Promise<void> {
+	return invoke('delete_sound', { soundId });
+}
+
+export async function revealInExplorer(soundId: string): Promise<void> {
+	return invoke('reveal_in_explorer', { soundId });
+}
+
+export async function addSoundTag(soundId: string, tag: string): Promise<string[]> {
+	return invoke('add_sound_tag', { soundId, tag });
+}
+
+export async function removeSoundTag(soundId: string, tag: string): Promise<string[]> {
+	return invoke('remove_sound_tag', { soundId, tag });
+}
+
+export async function setSoundGain(soundId: string, gainDb: number): Promise<void> {
+	return invoke('set_sound_gain', { soundId, gainDb });
+}
+
+export async function setCategoryGain(category: string, gainDb: number): Promise<void> {
+	return invoke('set_category_gain', { category, gainDb });
+}
+
+export async function getCategoryGains(): Promise<Record<string, number>> {
+	return invoke('get_category_gains');
+}
+
+export async function setFavoritesBulk(soundIds: string[], favorite: boolean): Promise<number> {
+	return invoke('set_favorites_bulk', { soundIds, favorite });
+}
+
 export async function getFavoritesCount(): Promise<number> {
 	return invoke('get_favorites_count');
 }
@@ -34,14 +178,118 @@ export async function getFavorites(): Promise<Sound[]> {
 	return invoke('get_favorites');
 }
 
+export async function getCatalogStats(): Promise<CatalogStats> {
+	return invoke('get_catalog_stats');
+}
+
+export async function exportCatalog(format: CatalogFormat, destPath: string): Promise<void> {
+	return invoke('export_catalog', { format, destPath });
+}
+
+export async function createPlaylist(name: string): Promise<Playlist> {
+	return invoke('create_playlist', { name });
+}
+
+export async function deletePlaylist(playlistId: number): Promise<void> {
+	return invoke('delete_playlist', { playlistId });
+}
+
+export async function getPlaylists(): Promise<Playlist[]> {
+	return invoke('get_playlists');
+}
+
+export async function addToPlaylist(
+	playlistId: number,
+	soundId: string,
+	position?: number
+): Promise<void> {
+	return invoke('add_to_playlist', { playlistId, soundId, position });
+}
+
+export async function removeFromPlaylist(playlistId: number, soundId: string): Promise<void> {
+	return invoke('remove_from_playlist', { playlistId, soundId });
+}
+
+export async function getPlaylistSounds(playlistId: number): Promise<Sound[]> {
+	return invoke('get_playlist_sounds', { playlistId });
+}
+
+export async function exportFavoritesZip(
+	destPath: string,
+	compression: ZipCompression = 'stored'
+): Promise<ExportSummary> {
+	return invoke('export_favorites_zip', { destPath, compression });
+}
+
+export async function exportSounds(
+	ids: string[],
+	destZip: string,
+	compression: ZipCompression = 'stored'
+): Promise<ExportSummary> {
+	return invoke('export_sounds', { ids, destZip, compression });
+}
+
+export async function exportSoundboard(
+	ids: string[],
+	dest: string,
+	exportDir?: string
+): Promise<number> {
+	return invoke('export_soundboard', { ids, dest, exportDir });
+}
+
 export async function playSound(id: string, filePath: string): Promise<void> {
 	return invoke('play_sound', { id, filePath });
 }
 
+// URL for a sound or music track served by the kithara:// protocol, seekable by <audio>
+export function audioUrl(id: string): string {
+	return convertFileSrc(`audio/${id}`, 'kithara');
+}
+
+export async function auditionWem(wemPath: string): Promise<void> {
+	return invoke('audition_wem', { wemPath });
+}
+
+export async function preloadSound(filePath: string): Promise<void> {
+	return invoke('preload_sound', { filePath });
+}
+
+export async function playSoundLayered(id: string, filePath: string): Promise<void> {
+	return invoke('play_sound_layered', { id, filePath });
+}
+
+export async function enqueueSound(id: string, filePath: string): Promise<void> {
+	return invoke('enqueue_sound', { id, filePath });
+}
+
+export async function clearQueue(): Promise<void> {
+	return invoke('clear_queue');
+}
+
+export async function skipNext(): Promise<void> {
+	return invoke('skip_next');
+}
+
+export async function setRepeatMode(mode: RepeatMode): Promise<void> {
+	return invoke('set_repeat_mode', { mode });
+}
+
 export async function stopSound(): Promise<void> {
 	return invoke('stop_sound');
 }
 
+export async function stopSoundImmediate(): Promise<void> {
+	return invoke('stop_sound_immediate');
+}
+
+export async function stopAllSounds(): Promise<void> {
+	return invoke('stop_all_sounds');
+}
+
+export async function getLastSession(): Promise<LastSession | null> {
+	return invoke('get_last_session');
+}
+
 export async function pauseSound(): Promise<void> {
 	return invoke('pause_sound');
 }
@@ -54,10 +302,38 @@ export async function seekSound(positionSecs: number): Promise<void> {
 	return invoke('seek_sound', { positionSecs });
 }
 
+export async function seekRelative(deltaSecs: number): Promise<void> {
+	return invoke('seek_relative', { deltaSecs });
+}
+
 export async function setVolume(volume: number): Promise<void> {
 	return invoke('set_volume', { volume });
 }
 
+export async function toggleMute(): Promise<boolean> {
+	return invoke('toggle_mute');
+}
+
+export async function startSleepTimer(minutes: number): Promise<void> {
+	return invoke('start_sleep_timer', { minutes });
+}
+
+export async function cancelSleepTimer(): Promise<void> {
+	return invoke('cancel_sleep_timer');
+}
+
+export async function listOutputDevices(): Promise<string[]> {
+	return invoke('list_output_devices');
+}
+
+export async function selectOutputDevice(name: string | null): Promise<void> {
+	return invoke('select_output_device', { name });
+}
+
+export async function setPlaybackSpeed(speed: number): Promise<void> {
+	return invoke('set_playback_speed', { speed });
+}
+
 export async function getPlaybackStatus(): Promise<PlaybackStatus> {
 	return invoke('get_playback_status');
 }
@@ -66,30 +342,80 @@ export async function getExtractionStatus(): Promise<ExtractionStatus> {
 	return invoke('get_extraction_status');
 }
 
-export async function startExtraction(gamePath: string, includeMusic: boolean = false): Promise<void> {
-	return invoke('start_extraction', { gamePath, includeMusic });
+export async function startExtraction(
+	gamePath: string,
+	options: Partial<ExtractionOptions> = {}
+): Promise<void> {
+	return invoke('start_extraction', { gamePath, options });
 }
 
-export async function updateLibrary(gamePath: string, includeMusic: boolean = false): Promise<void> {
-	return invoke('update_library', { gamePath, includeMusic });
+export async function getPendingExtraction(): Promise<ExtractionOptions | null> {
+	return invoke('get_pending_extraction');
+}
+
+export async function updateLibrary(
+	gamePath: string,
+	options: Partial<ExtractionOptions> = {}
+): Promise<void> {
+	return invoke('update_library', { gamePath, options });
 }
 
 export async function cancelExtraction(): Promise<void> {
 	return invoke('cancel_extraction');
 }
 
+export async function backupDatabase(destPath: string): Promise<void> {
+	return invoke('backup_database', { destPath });
+}
+
+export async function restoreDatabase(srcPath: string): Promise<void> {
+	return invoke('restore_database', { srcPath });
+}
+
+export async function optimizeDatabase(): Promise<OptimizeResult> {
+	return invoke('optimize_database');
+}
+
+export async function verifyCatalog(): Promise<CatalogVerification> {
+	return invoke('verify_catalog');
+}
+
+export async function pruneOrphanedSounds(): Promise<number> {
+	return invoke('prune_orphaned_sounds');
+}
+
+export async function recategorizeAll(): Promise<number> {
+	return invoke('recategorize_all');
+}
+
 export async function clearCache(): Promise<void> {
 	return invoke('clear_cache');
 }
 
+export async function clearOutputDir(): Promise<void> {
+	return invoke('clear_output_dir');
+}
+
+export async function validateGamePath(path: string): Promise<GamePathReport> {
+	return invoke('validate_game_path', { path });
+}
+
 export async function detectGamePath(): Promise<string | null> {
 	return invoke('detect_game_path');
 }
 
+export async function getLastGamePath(): Promise<string | null> {
+	return invoke('get_last_game_path');
+}
+
 export async function checkAudioDependencies(): Promise<string[]> {
 	return invoke('check_audio_dependencies');
 }
 
+export async function checkDependencies(): Promise<DependencyStatus> {
+	return invoke('check_dependencies');
+}
+
 // ========== Music Track API ==========
 
 export async function getMusicTracks(): Promise<MusicTrack[]> {
@@ -104,6 +430,18 @@ export async function getMusicTracksCount(): Promise<number> {
 	return invoke('get_music_tracks_count');
 }
 
+export async function playMusicTrack(id: string, filePath: string): Promise<void> {
+	return invoke('play_music_track', { id, filePath });
+}
+
+export async function shuffleMusic(query?: string, seed?: number): Promise<number> {
+	return invoke('shuffle_music', { query, seed });
+}
+
+export async function backfillMusicDurations(): Promise<number> {
+	return invoke('backfill_music_durations');
+}
+
 // ========== Window Management ==========
 
 // Winamp player dimensions at 2x scale
@@ -3,12 +3,13 @@ import {
 	searchSounds as apiSearchSounds,
 	getCategories as apiGetCategories,
 	getUnitTypes as apiGetUnitTypes,
+	getLanguages as apiGetLanguages,
 	playSound as apiPlaySound,
+	preloadSound as apiPreloadSound,
 	stopSound as apiStopSound,
 	getPlaybackStatus,
 	toggleFavorite as apiToggleFavorite,
-	getFavoritesCount as apiGetFavoritesCount,
-	getFavorites as apiGetFavorites
+	getFavoritesCount as apiGetFavoritesCount
 } from '$lib/api';
 
 // Sounds state using Svelte 5 runes
@@ -16,6 +17,7 @@ export const soundsState = $state<{
 	sounds: Sound[];
 	categories: Category[];
 	unitTypes: UnitType[];
+	languages: string[];
 	favoritesCount: number;
 	loading: boolean;
 	error: string | null;
@@ -23,6 +25,7 @@ export const soundsState = $state<{
 	sounds: [],
 	categories: [],
 	unitTypes: [],
+	languages: [],
 	favoritesCount: 0,
 	loading: false,
 	error: null
@@ -33,11 +36,17 @@ export const filterState = $state<{
 	query: string;
 	category: string | null;
 	unitType: string | null;
+	gender: 'male' | 'female' | null;
+	language: string | null;
+	isLoop: boolean | null;
 	showFavoritesOnly: boolean;
 }>({
 	query: '',
 	category: null,
 	unitType: null,
+	gender: null,
+	language: null,
+	isLoop: null,
 	showFavoritesOnly: false
 });
 
@@ -100,6 +109,17 @@ export async function loadUnitTypes(): Promise<void> {
 	}
 }
 
+/**
+ * Load the languages of localized sounds from the backend.
+ */
+export async function loadLanguages(): Promise<void> {
+	try {
+		soundsState.languages = await apiGetLanguages();
+	} catch (error) {
+		console.error('Failed to load languages:', error);
+	}
+}
+
 /**
  * Load favorites count from the backend.
  */
@@ -120,16 +140,15 @@ export async function fetchSounds(): Promise<void> {
 	soundsState.error = null;
 
 	try {
-		if (filterState.showFavoritesOnly) {
-			// Fetch all favorites from the backend
-			soundsState.sounds = await apiGetFavorites();
-		} else {
-			soundsState.sounds = await apiSearchSounds(
-				filterState.query,
-				filterState.category ?? undefined,
-				filterState.unitType ?? undefined
-			);
-		}
+		// Favorites mode still applies the text query and filters
+		soundsState.sounds = await apiSearchSounds(filterState.query, {
+			categories: filterState.category ? [filterState.category] : null,
+			unitTypes: filterState.unitType ? [filterState.unitType] : null,
+			gender: filterState.gender,
+			language: filterState.language,
+			isLoop: filterState.isLoop,
+			favoritesOnly: filterState.showFavoritesOnly
+		});
 	} catch (error) {
 		console.error('Failed to fetch sounds:', error);
 		soundsState.error = `Failed to fetch sounds: ${error}`;
@@ -149,7 +168,7 @@ export async function initializeSounds(): Promise<void> {
 
 	try {
 		// Load categories, unit types, and favorites count in parallel
-		await Promise.all([loadCategories(), loadUnitTypes(), loadFavoritesCount()]);
+		await Promise.all([loadCategories(), loadUnitTypes(), loadLanguages(), loadFavoritesCount()]);
 
 		// Then fetch initial sounds
 		await fetchSounds();
@@ -209,9 +228,17 @@ export async function stopSoundAction(): Promise<void> {
 	}
 }
 
+/**
+ * Decode a sound in the background so clicking it plays instantly.
+ * Best-effort: failures surface when the sound is actually played.
+ */
+export function preloadSoundAction(sound: Sound): void {
+	apiPreloadSound(sound.filePath).catch(() => {});
+}
+
 /**
  * Toggle favorite status for a sound.
- * Updates both backend and local state optimistically.
+ * Updates local state optimistically, then settles it from the backend's result.
  */
 export async function toggleFavoriteAction(soundId: string): Promise<void> {
 	// Find the sound in the current list
@@ -225,7 +252,9 @@ export async function toggleFavoriteAction(soundId: string): Promise<void> {
 	soundsState.favoritesCount += previousState ? -1 : 1;
 
 	try {
-		await apiToggleFavorite(soundId);
+		const result = await apiToggleFavorite(soundId);
+		soundsState.sounds[soundIndex].isFavorite = result.isFavorite;
+		soundsState.favoritesCount = result.totalFavorites;
 	} catch (error) {
 		// Revert on failure
 		soundsState.sounds[soundIndex].isFavorite = previousState;
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Aggregate counts and durations for the stats panel
+ */
+export type CatalogStats = { soundCount: number, musicTrackCount: number, favoriteCount: number, categoryCount: number, unitTypeCount: number, totalSoundDurationSecs: number, totalMusicDurationSecs: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of `verify_catalog`: how many sounds point at files that no longer exist
+ */
+export type CatalogVerification = { soundsChecked: number, orphanedSounds: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Availability of the external tools extraction relies on
+ */
+export type DependencyStatus = { vgmstream: boolean, ffmpeg: boolean, 
+/**
+ * Names of the missing tools ("vgmstream", "ffmpeg")
+ */
+missing: Array<string>, };
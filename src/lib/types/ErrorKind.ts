@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The variant of a `KitharaError`, sent to the frontend as `kind`
+ */
+export type ErrorKind = "io" | "database" | "decode" | "conversion" | "playback" | "notFound" | "invalidInput" | "cancelled";
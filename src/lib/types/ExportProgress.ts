@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ExportProgress = { filesDone: number, filesTotal: number, currentFile: string | null, 
+/**
+ * Audio bytes added to the archive so far (before compression)
+ */
+bytesWritten: number, };
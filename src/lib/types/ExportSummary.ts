@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ExportSummary = { fileCount: number, 
+/**
+ * Final size of the archive on disk
+ */
+bytesWritten: number, 
+/**
+ * IDs of sounds whose audio file was missing
+ */
+skipped: Array<string>, };
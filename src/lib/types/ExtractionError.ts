@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One entry that failed to extract, sent as an `extraction-error` event when it
+ * happens and listed in the completion summary
+ */
+export type ExtractionError = { fileId: number, shortName: string, error: string, };
@@ -0,0 +1,60 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutputFormat } from "./OutputFormat";
+
+/**
+ * Options for an extraction run
+ */
+export type ExtractionOptions = { 
+/**
+ * Extract sound effects and voice lines; on by default
+ */
+includeSounds: boolean, 
+/**
+ * Also extract music tracks
+ */
+includeMusic: boolean, 
+/**
+ * Re-convert sounds even if they were already extracted and cataloged
+ */
+force: boolean, 
+/**
+ * Audio format the extracted files are written in
+ */
+outputFormat: OutputFormat, 
+/**
+ * Only extract sounds in these categories (all categories if unset)
+ */
+categories: Array<string> | null, 
+/**
+ * Continue an interrupted extraction with the options it was started with
+ */
+resume: boolean, 
+/**
+ * Normalize loudness (EBU R128, -16 LUFS); slower, so off by default
+ */
+normalize: boolean, 
+/**
+ * Strip leading and trailing silence; off by default to keep the exact source audio
+ */
+trimSilence: boolean, 
+/**
+ * Measure each sound's duration with ffprobe; on by default, turn off for faster runs
+ */
+measureDurations: boolean, 
+/**
+ * Conversions to run at once; defaults to the last value used, else min(CPUs, 4)
+ */
+maxConcurrency: number | null, 
+/**
+ * Directory to write sounds to instead of the app cache, inside a `kithara-sounds`
+ * subfolder; remembered for later runs
+ */
+outputDir: string | null, 
+/**
+ * Also skip WEMs whose bytes match one already converted under a different ID
+ */
+dedupePayloads: boolean, 
+/**
+ * Only report what would be extracted, as an `ExtractionPlan`, without converting anything
+ */
+dryRun: boolean, };
@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Preview of what an extraction would do, sent as the "extraction-plan" event
+ * when a dry run finishes
+ */
+export type ExtractionPlan = { 
+/**
+ * Sounds that would be converted
+ */
+sounds: number, 
+/**
+ * Music tracks that would be converted
+ */
+musicTracks: number, 
+/**
+ * Entries matching the exclusion patterns
+ */
+excluded: number, 
+/**
+ * Soundbank entries with no file metadata to name them
+ */
+skippedNoMetadata: number, 
+/**
+ * Entries already converted and cataloged, which would be skipped
+ */
+alreadyExtracted: number, 
+/**
+ * Sounds that would be converted, by category
+ */
+categories: { [key in string]?: number }, 
+/**
+ * Sounds that would be converted, by unit type
+ */
+unitTypes: { [key in string]?: number }, 
+/**
+ * Size of the WEM audio that would be converted; OGG output is of a similar size
+ */
+sourceBytes: number, };
@@ -1,4 +1,24 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ExtractionState } from "./ExtractionState";
 
-export type ExtractionStatus = { state: ExtractionState, progress: number, currentFile: string | null, error: string | null, };
+export type ExtractionStatus = { state: ExtractionState, progress: number, currentFile: string | null, error: string | null, 
+/**
+ * Non-fatal issues found after extraction (e.g. categories that came up empty)
+ */
+warnings: Array<string>, 
+/**
+ * WEM entries handled so far, out of `total`
+ */
+processed: number, total: number, 
+/**
+ * Entries converted or already present in the library
+ */
+successful: number, 
+/**
+ * Entries left out (no metadata, excluded, or outside the selected categories)
+ */
+skipped: number, 
+/**
+ * Estimated seconds remaining; None until enough files have been converted
+ */
+etaSecs: number | null, };
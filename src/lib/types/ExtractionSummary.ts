@@ -0,0 +1,35 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExtractionError } from "./ExtractionError";
+
+/**
+ * Report sent with the "extraction-complete" event, for both finished and failed runs
+ */
+export type ExtractionSummary = { 
+/**
+ * Sounds newly converted and added to the library
+ */
+soundsExtracted: number, 
+/**
+ * Music tracks newly converted and added to the library
+ */
+musicExtracted: number, 
+/**
+ * Soundbank entries with no file metadata to name them
+ */
+skippedNoMetadata: number, 
+/**
+ * Entries matching the exclusion patterns
+ */
+excluded: number, 
+/**
+ * Entries whose conversion or cataloging failed
+ */
+failed: number, 
+/**
+ * What went wrong with each failed entry
+ */
+failures: Array<ExtractionError>, elapsedSecs: number, 
+/**
+ * Why the run stopped early; None when it finished
+ */
+error: string | null, };
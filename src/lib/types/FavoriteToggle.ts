@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A sound's favorite state after `toggle_favorite`, with the updated favorites total
+ */
+export type FavoriteToggle = { isFavorite: boolean, totalFavorites: number, };
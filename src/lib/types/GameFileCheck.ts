@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One expected file in a game audio folder
+ */
+export type GameFileCheck = { name: string, found: boolean, 
+/**
+ * Extraction can't run without it
+ */
+required: boolean, };
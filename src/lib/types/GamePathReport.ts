@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GameFileCheck } from "./GameFileCheck";
+
+/**
+ * Result of checking a folder for the game's audio files, for a setup checklist
+ */
+export type GamePathReport = { pathExists: boolean, files: Array<GameFileCheck>, 
+/**
+ * Soundbanks with both their XML and BNK present
+ */
+soundbankCount: number, 
+/**
+ * Loose `.wem` files streamed from outside the soundbanks
+ */
+streamedWemCount: number, 
+/**
+ * Whether extraction can run from this folder
+ */
+isValid: boolean, 
+/**
+ * Everything missing that extraction needs, for error messages
+ */
+missing: Array<string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Markers wrapped around the matched terms in a search result's snippet
+ */
+export type HighlightMarkers = { open: string, close: string, };
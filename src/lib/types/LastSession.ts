@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The sound that was playing when playback last stopped or the app closed,
+ * so the UI can offer to resume where it left off
+ */
+export type LastSession = { soundId: string, positionSecs: number, };
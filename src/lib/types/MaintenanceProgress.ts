@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Progress of a long-running maintenance task, sent as `maintenance-progress` events
+ */
+export type MaintenanceProgress = { 
+/**
+ * 0.0 to 1.0
+ */
+progress: number, message: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Database file size before and after `optimize_database`
+ */
+export type OptimizeResult = { sizeBefore: number, sizeAfter: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Audio format for extracted sounds and music
+ */
+export type OutputFormat = { "type": "ogg_vorbis", quality: number, } | { "type": "wav" } | { "type": "flac" } | { "type": "mp3", bitrateKbps: number, };
@@ -1,3 +1,32 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RepeatMode } from "./RepeatMode";
 
-export type PlaybackStatus = { isPlaying: boolean, isPaused: boolean, currentSoundId: string | null, positionSecs: number, durationSecs: number, volume: number, sampleRate: number, bitrateKbps: number, };
+export type PlaybackStatus = { isPlaying: boolean, isPaused: boolean, currentSoundId: string | null, positionSecs: number, durationSecs: number, 
+/**
+ * Volume restored on unmute; still reported while muted
+ */
+volume: number, isMuted: boolean, sampleRate: number, bitrateKbps: number, channels: number, 
+/**
+ * Decoded format inferred from the file extension ("ogg", "wav", "flac", "mp3")
+ */
+codec: string, 
+/**
+ * Number of sounds waiting in the playback queue
+ */
+queueLength: number, repeatMode: RepeatMode, 
+/**
+ * True while the queue is a shuffled music list
+ */
+shuffle: boolean, 
+/**
+ * Playback speed multiplier (pitch changes with speed)
+ */
+speed: number, 
+/**
+ * Number of sounds currently audible, including layered voices
+ */
+activeVoices: number, 
+/**
+ * Seconds until the sleep timer stops playback, if one is running
+ */
+sleepRemainingSecs: number | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A user-defined collection of sounds
+ */
+export type Playlist = { id: number, name: string, soundCount: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Filters applied on top of the text query when searching sounds.
+ * Multiple categories or unit types match any of them.
+ */
+export type SearchFilters = { categories: Array<string> | null, unitTypes: Array<string> | null, gender: string | null, language: string | null, favoritesOnly: boolean, 
+/**
+ * Some(true) keeps only looping sounds, Some(false) leaves them out
+ */
+isLoop: boolean | null, };
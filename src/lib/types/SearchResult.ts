@@ -0,0 +1,37 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A sound returned by search, with the name it matched on highlighted.
+ * `match_snippet` is only set for text queries that matched a name.
+ */
+export type SearchResult = { matchSnippet: string | null, id: string, eventName: string, displayName: string, category: string, unitType: string | null, subcategory: string, duration: number, filePath: string, tags: Array<string>, isFavorite: boolean, 
+/**
+ * Voice gender (e.g. "female"); female variants are named as such, other voice lines are male
+ */
+gender: string | null, 
+/**
+ * Locale of a localized voice line (e.g. "fr"), if its path indicates one
+ */
+language: string | null, 
+/**
+ * Soundbank the audio was extracted from, if any: its STID name, else its file name
+ */
+sourceBank: string | null, 
+/**
+ * Per-sound gain trim in decibels, applied on top of the player volume
+ */
+gainDb: number, 
+/**
+ * Designed to loop seamlessly (ambiences, engine loops); plays with repeat-one
+ */
+isLoop: boolean, 
+/**
+ * Soundbank short_name the sound was classified from, kept so it can be
+ * re-classified without re-extracting. None for sounds added some other way.
+ */
+shortName: string | null, 
+/**
+ * Whether `file_path` exists on disk. Computed, not stored: only listings that
+ * run `catalog::mark_availability` check it, everything else assumes true.
+ */
+available: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sort order for sound search results
+ */
+export type SortOrder = "name_asc" | "name_desc" | "duration_asc" | "duration_desc" | "category" | "relevance";
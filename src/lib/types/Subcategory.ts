@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A subcategory within one category, for category -> subcategory -> sounds browsing
+ */
+export type Subcategory = { id: string, name: string, count: number, };
@@ -1,10 +1,37 @@
 // Re-export all generated types
 // Run `npm run types:generate` to regenerate from Rust models
 
+export type { CatalogFormat } from './CatalogFormat';
+export type { CatalogStats } from './CatalogStats';
+export type { CatalogVerification } from './CatalogVerification';
 export type { Category } from './Category';
+export type { DependencyStatus } from './DependencyStatus';
+export type { ErrorKind } from './ErrorKind';
+export type { ExportProgress } from './ExportProgress';
+export type { ExportSummary } from './ExportSummary';
+export type { ExtractionError } from './ExtractionError';
+export type { ExtractionOptions } from './ExtractionOptions';
+export type { ExtractionPlan } from './ExtractionPlan';
 export type { ExtractionState } from './ExtractionState';
 export type { ExtractionStatus } from './ExtractionStatus';
+export type { ExtractionSummary } from './ExtractionSummary';
+export type { FavoriteToggle } from './FavoriteToggle';
+export type { GameFileCheck } from './GameFileCheck';
+export type { GamePathReport } from './GamePathReport';
+export type { HighlightMarkers } from './HighlightMarkers';
+export type { LastSession } from './LastSession';
+export type { MaintenanceProgress } from './MaintenanceProgress';
 export type { MusicTrack } from './MusicTrack';
+export type { OptimizeResult } from './OptimizeResult';
+export type { OutputFormat } from './OutputFormat';
+export type { PlaybackFinished } from './PlaybackFinished';
 export type { PlaybackStatus } from './PlaybackStatus';
+export type { Playlist } from './Playlist';
+export type { RepeatMode } from './RepeatMode';
+export type { SearchFilters } from './SearchFilters';
+export type { SearchResult } from './SearchResult';
+export type { SortOrder } from './SortOrder';
 export type { Sound } from './Sound';
+export type { Subcategory } from './Subcategory';
 export type { UnitType } from './UnitType';
+export type { ZipCompression } from './ZipCompression';